@@ -0,0 +1,120 @@
+#ifndef KHAN_ACADEMY_EXTRACTOR_H
+#define KHAN_ACADEMY_EXTRACTOR_H
+
+/* Generated with cbindgen from src/ffi_operations.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Extractor handed to C callers as an opaque pointer.
+typedef struct KaeExtractor KaeExtractor;
+
+// Buffer of bytes owned by the caller, holding the UTF-8 content of a capture.
+typedef struct KaeBuffer {
+  // Pointer to the first byte of the buffer.
+  const uint8_t *data;
+  // Number of bytes of the buffer.
+  uintptr_t len;
+} KaeBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates an extractor from the capture files of a directory, an archive, or a bundle file.
+//
+// # Parameters
+//
+// - `path`: The path of the captures, as a NUL-terminated UTF-8 string.
+// - `prefix`: The prefix of the capture files, as a NUL-terminated UTF-8 string, or null
+//   for no prefix.
+//
+// # Returns
+//
+// - `*mut KaeExtractor`: The extractor, to be freed with `kae_extractor_free`, or null on
+//   failure, with the message available from `kae_last_error`.
+//
+// # Safety
+//
+// `path` must point to a NUL-terminated string, and `prefix` must be null or point to one.
+struct KaeExtractor *kae_extractor_from_dir(const char *path, const char *prefix);
+
+// Creates an extractor from the contents of the captures held in memory.
+//
+// The buffers are copied, so they can be released as soon as the function returns.
+//
+// # Parameters
+//
+// - `content_for_path`: The content of the `contentForPath` capture.
+// - `course_progress`: The content of the `courseProgressQuery` capture.
+// - `unit_progress`: The contents of the `getUserInfoForTopicProgressMastery-N` captures.
+// - `unit_progress_count`: The number of buffers in `unit_progress`.
+// - `quiz_test_progress`: The contents of the `quizAndUnitTestAttemptsQuery-N` captures.
+// - `quiz_test_progress_count`: The number of buffers in `quiz_test_progress`.
+//
+// # Returns
+//
+// - `*mut KaeExtractor`: The extractor, to be freed with `kae_extractor_free`, or null on
+//   failure, with the message available from `kae_last_error`.
+//
+// # Safety
+//
+// Every buffer must point to its number of readable bytes, and every array of buffers must
+// be null with a count of 0, or point to its number of buffers.
+struct KaeExtractor *kae_extractor_from_buffers(struct KaeBuffer content_for_path,
+                                                struct KaeBuffer course_progress,
+                                                const struct KaeBuffer *unit_progress,
+                                                uintptr_t unit_progress_count,
+                                                const struct KaeBuffer *quiz_test_progress,
+                                                uintptr_t quiz_test_progress_count);
+
+// Runs the extraction and returns the enriched course as JSON, with its `rows`, `attempts`,
+// and `unmappedAttempts`.
+//
+// # Parameters
+//
+// - `extractor`: The extractor.
+//
+// # Returns
+//
+// - `*mut c_char`: The JSON of the course, as a NUL-terminated UTF-8 string to be freed with
+//   `kae_string_free`, or null on failure, with the message available from `kae_last_error`.
+//
+// # Safety
+//
+// `extractor` must be null or a pointer returned by one of the `kae_extractor_from_*`
+// functions that has not been freed.
+char *kae_extractor_extract_json(const struct KaeExtractor *extractor);
+
+// Frees an extractor. Null is ignored.
+//
+// # Safety
+//
+// `extractor` must be null or a pointer returned by one of the `kae_extractor_from_*`
+// functions that has not been freed.
+void kae_extractor_free(struct KaeExtractor *extractor);
+
+// Frees a string returned by the C API. Null is ignored.
+//
+// # Safety
+//
+// `value` must be null or a string returned by `kae_extractor_extract_json` that has not
+// been freed.
+void kae_string_free(char *value);
+
+// Returns the message of the last error of the C API on the current thread.
+//
+// # Returns
+//
+// - `*const c_char`: The message, as a NUL-terminated UTF-8 string owned by the library and
+//   valid until the next call to the C API on the same thread, or null if the last call
+//   succeeded.
+const char *kae_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KHAN_ACADEMY_EXTRACTOR_H */
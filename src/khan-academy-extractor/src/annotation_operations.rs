@@ -0,0 +1,258 @@
+use crate::error::AppError;
+use crate::models::{Annotation, DataStruct, ReviewItem};
+use crate::review_operations::parse_completed_date;
+use chrono::NaiveDate;
+use csv::Reader;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// Separator of the tags of a row in the outputs and in CSV annotation files.
+pub const TAG_SEPARATOR: char = ';';
+
+/// Annotations of the content of a course, keyed by the id or the slug of the content.
+pub type Annotations = BTreeMap<String, Annotation>;
+
+/// Line of a CSV annotation file, with the id or slug of the content in the `key` column.
+#[derive(Deserialize)]
+struct AnnotationRecord {
+    key: String,
+    tags: Option<String>,
+    notes: Option<String>,
+    priority: Option<u32>,
+    skip: Option<bool>,
+    due: Option<String>,
+}
+
+/// Parses the content of an annotation file.
+///
+/// TOML and JSON files map the id or slug of the content to its annotation, such as
+/// `[x1a2b3c]` followed by `tags = ["hard"]` and `skip = true` in TOML. CSV files have a `key`
+/// column with the id or slug, and `tags`, `notes`, `priority`, `skip` and `due` columns, with
+/// the tags separated by semicolons. Due dates are in YYYY-MM-DD format.
+///
+/// # Parameters
+///
+/// - `content`: A string slice with the content of the file.
+/// - `format`: The format of the file, `toml`, `json` or `csv`.
+///
+/// # Returns
+///
+/// - `Result<Annotations, AppError>`: On success, returns the annotations by key. On failure,
+///   returns an `AppError` indicating the type of error that occurred, such as a parsing error
+///   or an unknown format.
+pub fn parse_annotations(content: &str, format: &str) -> Result<Annotations, AppError> {
+    match format {
+        "toml" => Ok(toml::from_str(content)?),
+        "json" => Ok(serde_json::from_str(content)?),
+        "csv" => {
+            let mut annotations: Annotations = Annotations::new();
+            for record in Reader::from_reader(content.as_bytes()).deserialize() {
+                let record: AnnotationRecord = record?;
+                let tags: Vec<String> = record
+                    .tags
+                    .iter()
+                    .flat_map(|tags| tags.split(TAG_SEPARATOR))
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+                annotations.insert(
+                    record.key,
+                    Annotation {
+                        tags,
+                        notes: record.notes.filter(|notes| !notes.is_empty()),
+                        priority: record.priority,
+                        skip: record.skip.unwrap_or(false),
+                        due: record.due.filter(|due| !due.is_empty()),
+                    },
+                );
+            }
+            Ok(annotations)
+        }
+        _ => Err(AppError::InvalidArgument(format!(
+            "unknown annotation format {}, expected toml, json or csv",
+            format
+        ))),
+    }
+}
+
+/// Loads an annotation file, choosing its format from its extension.
+///
+/// # Parameters
+///
+/// - `path`: The path of the annotation file, ending in `.toml`, `.json` or `.csv`.
+///
+/// # Returns
+///
+/// - `Result<Annotations, AppError>`: On success, returns the annotations by key. On failure,
+///   returns an `AppError` indicating the type of error that occurred, such as an I/O error or
+///   a parsing error.
+pub fn load_annotations<P: AsRef<Path>>(path: P) -> Result<Annotations, AppError> {
+    let format: String = path
+        .as_ref()
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    parse_annotations(&read_to_string(path)?, &format)
+}
+
+/// Merges the annotations into the rows of a course.
+///
+/// A key matches the row with the same id. When no row has that id, it matches every row
+/// with the same slug, so annotations written by slug survive the content being replaced.
+///
+/// # Parameters
+///
+/// - `rows`: A mutable slice of `DataStruct` with the rows of the course. The rows are updated
+///   in place.
+/// - `annotations`: A reference to the annotations by key.
+///
+/// # Returns
+///
+/// - `Result<Vec<String>, AppError>`: On success, returns the keys that did not match any
+///   row. On failure, returns an `AppError::InvalidDate` if a due date cannot be parsed.
+pub fn apply_annotations(
+    rows: &mut [DataStruct],
+    annotations: &Annotations,
+) -> Result<Vec<String>, AppError> {
+    let mut unmatched: Vec<String> = Vec::new();
+
+    for (key, annotation) in annotations {
+        let due: Option<NaiveDate> = annotation
+            .due
+            .as_deref()
+            .map(parse_completed_date)
+            .transpose()?;
+        let by_id: bool = rows.iter().any(|row| &row.id == key);
+        let mut matched: bool = false;
+        for row in rows.iter_mut().filter(|row| {
+            if by_id {
+                &row.id == key
+            } else {
+                &row.slug == key
+            }
+        }) {
+            matched = true;
+            row.tags = Some(annotation.tags.join(&TAG_SEPARATOR.to_string()))
+                .filter(|tags| !tags.is_empty());
+            row.notes = annotation.notes.clone();
+            row.priority = annotation.priority;
+            row.skip = Some(annotation.skip);
+            row.due = due;
+        }
+        if !matched {
+            unmatched.push(key.clone());
+        }
+    }
+
+    Ok(unmatched)
+}
+
+/// Checks whether a row has a tag.
+fn has_tag(row: &DataStruct, tag: &str) -> bool {
+    row.tags
+        .as_deref()
+        .is_some_and(|tags| tags.split(TAG_SEPARATOR).any(|row_tag| row_tag == tag))
+}
+
+/// Flags the skipped rows.
+///
+/// A row is skipped when it is annotated with `skip` or when one of its ancestors is, so
+/// skipping a unit skips all its content. Content listed in several lessons is flagged per
+/// occurrence.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course, with their annotations, in course order.
+///
+/// # Returns
+///
+/// - `Vec<bool>`: Whether each row is skipped, by row index.
+fn skipped_rows(rows: &[DataStruct]) -> Vec<bool> {
+    let mut skipped_parents: HashSet<&str> = HashSet::new();
+
+    rows.iter()
+        .map(|row| {
+            let is_skipped: bool = row.skip == Some(true)
+                || row
+                    .parent_id
+                    .as_deref()
+                    .is_some_and(|parent_id| skipped_parents.contains(parent_id));
+            if is_skipped {
+                skipped_parents.insert(row.id.as_str());
+            }
+            is_skipped
+        })
+        .collect()
+}
+
+/// Keeps the rows that have a tag and, optionally, that are not skipped.
+///
+/// A row is skipped when it is annotated with `skip` or when one of its ancestors is, so
+/// skipping a unit skips all its content.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course, with their annotations, in course order.
+/// - `tag`: An optional tag that the kept rows must have.
+/// - `hide_skipped`: Whether the skipped rows are removed.
+///
+/// # Returns
+///
+/// - `Vec<DataStruct>`: The kept rows, in course order.
+pub fn filter_rows(
+    rows: Vec<DataStruct>,
+    tag: Option<&str>,
+    hide_skipped: bool,
+) -> Vec<DataStruct> {
+    let skipped: Vec<bool> = skipped_rows(&rows);
+
+    rows.into_iter()
+        .zip(skipped)
+        .filter(|(_, is_skipped)| !(hide_skipped && *is_skipped))
+        .map(|(row, _)| row)
+        .filter(|row| tag.is_none_or(|tag| has_tag(row, tag)))
+        .collect()
+}
+
+/// Applies the annotations of the rows to the scheduled reviews.
+///
+/// Skipped exercises, including the ones of a skipped unit or lesson, are removed from the
+/// reviews unless they are also listed in content that is not skipped, and the due date of an
+/// annotation replaces the scheduled one.
+///
+/// # Parameters
+///
+/// - `review_items`: The scheduled exercises.
+/// - `rows`: The rows of the course, with their annotations, in course order.
+///
+/// # Returns
+///
+/// - `Vec<ReviewItem>`: The scheduled exercises with their annotations, sorted by due date.
+pub fn annotate_reviews(review_items: Vec<ReviewItem>, rows: &[DataStruct]) -> Vec<ReviewItem> {
+    let skipped: Vec<bool> = skipped_rows(rows);
+    let mut annotated: Vec<ReviewItem> = review_items
+        .into_iter()
+        .filter_map(|mut item| {
+            let occurrences: Vec<bool> = rows
+                .iter()
+                .zip(&skipped)
+                .filter(|(row, _)| row.id == item.id)
+                .map(|(_, is_skipped)| *is_skipped)
+                .collect();
+            if !occurrences.is_empty() && occurrences.iter().all(|is_skipped| *is_skipped) {
+                return None;
+            }
+            let row: Option<&DataStruct> = rows.iter().find(|row| row.id == item.id);
+            if let Some(due) = row.and_then(|row| row.due) {
+                item.due_date = due;
+            }
+            Some(item)
+        })
+        .collect();
+    annotated.sort_by_key(|item| item.due_date);
+
+    annotated
+}
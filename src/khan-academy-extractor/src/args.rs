@@ -1,13 +1,304 @@
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args as _, CommandFactory, FromArgMatches, Parser, Subcommand};
+use khan_academy_extractor::fetch_operations::DEFAULT_BASE_URL;
 
 /// Command-line arguments for the application.
 #[derive(Parser)]
 pub struct Args {
-    /// Directory path
-    #[clap(short, long, default_value = ".")]
+    /// Directory of the capture files, a zip or tar.gz archive of them, or a bundle file
+    #[clap(short, long, default_value = ".", global = true)]
     pub path: String,
 
     /// File prefix
-    #[clap(short = 'e', long, default_value = "")]
+    #[clap(short = 'e', long, default_value = "", global = true)]
     pub prefix: String,
+
+    /// Options of the extraction run when no command is given, rejected with any command
+    #[clap(flatten)]
+    pub extract: ExtractArgs,
+
+    /// Command to run, extracts the course information into a CSV file when omitted
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Location and naming of the output files.
+#[derive(clap::Args)]
+pub struct OutputArgs {
+    /// Directory where the outputs are written, defaults to the directory path
+    #[clap(long)]
+    pub output_dir: Option<String>,
+
+    /// Template of the output file names, with the {course}, {prefix}, {name}, {date} and
+    /// {format} placeholders
+    #[clap(long, default_value = "{prefix}{name}.{format}")]
+    pub output_template: String,
+
+    /// Number of previous versions of each output to keep as backups
+    #[clap(long, default_value_t = 0)]
+    pub backups: usize,
+}
+
+/// Annotations merged into the rows of the course.
+#[derive(clap::Args)]
+pub struct AnnotationArgs {
+    /// Annotation file in TOML, JSON or CSV format, keyed by content id or slug
+    #[clap(long)]
+    pub annotations: Option<String>,
+}
+
+/// Filters of the rows on their annotations.
+#[derive(clap::Args)]
+pub struct FilterArgs {
+    /// Keep only the rows with this annotation tag
+    #[clap(long)]
+    pub tag: Option<String>,
+
+    /// Remove the rows annotated with skip, and their content
+    #[clap(long)]
+    pub hide_skipped: bool,
+}
+
+/// Replacement of the learner labels with stable aliases.
+#[derive(clap::Args)]
+pub struct PseudonymArgs {
+    /// Replace the learner labels with stable aliases in the matrix
+    #[clap(long)]
+    pub pseudonymise: bool,
+
+    /// Local file with the mapping of learner labels to aliases, keep it private
+    #[clap(long, default_value = "pseudonyms.json")]
+    pub pseudonym_map: String,
+
+    /// Salt for the aliases of a new pseudonym map, random when omitted
+    #[clap(long)]
+    pub pseudonym_salt: Option<String>,
+}
+
+/// Session and pacing of the requests sent to the site.
+#[derive(clap::Args)]
+pub struct ClientArgs {
+    /// `Cookie` header of a logged-in session, copied from the browser
+    #[clap(short, long, required_unless_present = "cookie_file")]
+    pub cookie: Option<String>,
+
+    /// File holding the `Cookie` header, which keeps it out of the shell history
+    #[clap(long, conflicts_with = "cookie")]
+    pub cookie_file: Option<String>,
+
+    /// Base URL of the site, such as the address of a replay server
+    #[clap(short, long, default_value = DEFAULT_BASE_URL)]
+    pub base_url: String,
+
+    /// Minimum delay between two requests, in milliseconds
+    #[clap(short, long, default_value_t = 1000)]
+    pub delay_ms: u64,
+
+    /// Number of times a failed request is retried
+    #[clap(short, long, default_value_t = 3)]
+    pub retries: u32,
+}
+
+/// Options of the extraction of the course information and attempt history.
+#[derive(clap::Args)]
+pub struct ExtractArgs {
+    #[clap(flatten)]
+    pub output: OutputArgs,
+
+    /// Format of the course information and attempt history, csv or json
+    #[clap(long, default_value = "csv")]
+    pub format: String,
+
+    /// Write the course information to the standard output instead of files
+    #[clap(long)]
+    pub stdout: bool,
+
+    /// Merge the course information into the previous output, keeping the columns added to it,
+    /// which cannot be combined with --tag or --hide-skipped
+    #[clap(long, conflicts_with_all = ["tag", "hide_skipped"])]
+    pub merge: bool,
+
+    /// Previous output to merge into, defaults to the course information file
+    #[clap(long)]
+    pub previous: Option<String>,
+
+    #[clap(flatten)]
+    pub annotations: AnnotationArgs,
+
+    #[clap(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Subcommands of the application.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Extract the course information and attempt history, which is also done when no
+    /// command is given
+    Extract(ExtractArgs),
+
+    /// List the finished exercises that are due for review
+    Review {
+        #[clap(flatten)]
+        annotations: AnnotationArgs,
+
+        /// Date used as today, in YYYY-MM-DD format
+        #[clap(short, long)]
+        today: Option<String>,
+
+        /// Number of days after today to include in the upcoming reviews
+        #[clap(short, long, default_value_t = 7)]
+        days: i64,
+    },
+
+    /// Forecast the finish date of the course and each unit, and draw a burn-up chart
+    Forecast {
+        /// Date used as today, in YYYY-MM-DD format
+        #[clap(short, long)]
+        today: Option<String>,
+
+        /// Date on which the course should be finished, in YYYY-MM-DD format
+        #[clap(short = 'g', long)]
+        target: Option<String>,
+
+        /// Path of the SVG burn-up chart, defaults to the output template with `burnup` as name
+        #[clap(short, long)]
+        svg: Option<String>,
+
+        #[clap(flatten)]
+        output_options: OutputArgs,
+
+        #[clap(flatten)]
+        annotations: AnnotationArgs,
+    },
+
+    /// Compare the progress of several learners in a matrix of course content by learner
+    Matrix {
+        /// Learner capture directory in `label=directory` form, repeat once per learner
+        #[clap(short, long = "learner", required = true)]
+        learners: Vec<String>,
+
+        /// Path of the CSV matrix, defaults to the output template with `matrix` as name
+        #[clap(short, long)]
+        output: Option<String>,
+
+        #[clap(flatten)]
+        pseudonyms: PseudonymArgs,
+
+        #[clap(flatten)]
+        output_options: OutputArgs,
+
+        #[clap(flatten)]
+        annotations: AnnotationArgs,
+
+        #[clap(flatten)]
+        filter: FilterArgs,
+    },
+
+    /// Write a redacted copy of the capture files, keeping only the fields the extractors read
+    Sanitize {
+        /// Directory where the sanitized capture files are written
+        #[clap(short, long)]
+        output: String,
+    },
+
+    /// Check that the capture files are complete before extracting them
+    Validate,
+
+    /// Compare two `contentForPath` captures and list the changes of the curriculum
+    Diff {
+        /// Path of the old `contentForPath` capture
+        #[clap(long)]
+        old: String,
+
+        /// Path of the new `contentForPath` capture
+        #[clap(long)]
+        new: String,
+
+        /// Path of a CSV file where the mapping of old nodes to new nodes is written
+        #[clap(short, long)]
+        mapping: Option<String>,
+
+        /// Number of previous versions of the mapping to keep as backups
+        #[clap(long, default_value_t = 0)]
+        backups: usize,
+    },
+
+    /// Serve the extracted courses as a local JSON API, reloading them when the captures change
+    Serve {
+        /// Course captures in `name=path` form, repeat once per course, defaults to the path as `course`
+        #[clap(short, long = "course")]
+        courses: Vec<String>,
+
+        /// Address the server listens on
+        #[clap(short, long, default_value = "127.0.0.1:8080")]
+        address: String,
+
+        #[clap(flatten)]
+        annotations: AnnotationArgs,
+    },
+
+    /// Answer GraphQL requests with the captured responses, as an offline stand-in for the API
+    Replay {
+        /// Address the server listens on
+        #[clap(short, long, default_value = "127.0.0.1:8081")]
+        address: String,
+    },
+
+    /// Capture the data of a course through the GraphQL API into the directory of the path
+    ///
+    /// Without `--requests`, built-in requests naming each operation and its course or unit
+    /// are sent; a replay server answers them, but they have not been checked against the
+    /// live site. With `--requests`, the requests are the ones the browser sent while
+    /// browsing a course, saved from the network panel of its developer tools as a HAR file:
+    /// open the course page and one of its unit pages, then save all the requests. They are
+    /// sent again with the course and unit values replaced, so the query text or hash always
+    /// matches what the site expects.
+    Fetch {
+        /// Path of the course, such as `/math/algebra`, or its full URL
+        course: String,
+
+        /// HAR file with the requests recorded by the browser, saved with their content
+        #[clap(long)]
+        requests: Option<String>,
+
+        #[clap(flatten)]
+        client: ClientArgs,
+
+        /// Directory where the capture files are written, defaults to the directory path
+        #[clap(long)]
+        output_dir: Option<String>,
+    },
+}
+
+/// Parses the command-line arguments, exiting with a usage error when they are invalid.
+///
+/// The options of the extraction can be given without a command, but they are rejected when
+/// a command is given, since no other command reads them.
+///
+/// # Returns
+///
+/// - `Args`: The parsed command-line arguments.
+pub fn parse_args() -> Args {
+    let matches: ArgMatches = Args::command().get_matches();
+    let args: Args = Args::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+
+    let Some((name, _)) = matches.subcommand() else {
+        return args;
+    };
+    let extract_args: clap::Command = ExtractArgs::augment_args(clap::Command::new("extract"));
+    let conflict: Option<String> = extract_args
+        .get_arguments()
+        .find(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .map(|arg| arg.get_long().unwrap_or_default().to_string());
+    if let Some(long) = conflict {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("the subcommand '{}' cannot be used with '--{}'", name, long),
+            )
+            .exit();
+    }
+
+    args
 }
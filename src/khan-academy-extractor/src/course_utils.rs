@@ -0,0 +1,112 @@
+use crate::models::{DataStruct, TopicQuizAttempt};
+use std::collections::{HashMap, HashSet};
+
+/// Types of the nodes that group or assess other content, rather than being content items.
+pub const NON_ITEM_TYPES: &[&str] = &[
+    "Course",
+    "Unit",
+    "Lesson",
+    "TopicQuiz",
+    "TopicUnitTest",
+    "CourseChallenge",
+    "MasteryChallenge",
+];
+
+/// Maps the id of every row to its index in the rows of a course.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+///
+/// # Returns
+///
+/// - `HashMap<&str, usize>`: The index of every row, by id.
+pub fn index_rows(rows: &[DataStruct]) -> HashMap<&str, usize> {
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| (row.id.as_str(), index))
+        .collect()
+}
+
+/// Finds the unit that contains a row, walking up through its parents.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+/// - `row_indexes`: A map from the id of every row to its index in `rows`.
+/// - `row`: The row whose unit is searched.
+///
+/// # Returns
+///
+/// - `Option<&str>`: The id of the unit, or `None` if the row is not inside a unit.
+pub fn find_unit_id<'a>(
+    rows: &'a [DataStruct],
+    row_indexes: &HashMap<&str, usize>,
+    row: &'a DataStruct,
+) -> Option<&'a str> {
+    let mut current: &DataStruct = row;
+    loop {
+        if current.type_name == "Unit" {
+            return Some(&current.id);
+        }
+        let parent_id: &str = current.parent_id.as_deref()?;
+        current = &rows[*row_indexes.get(parent_id)?];
+    }
+}
+
+/// Lists the content items of a course, at any depth.
+///
+/// A content item is a row without children whose type is not one of `NON_ITEM_TYPES`,
+/// such as an exercise, a video, or an article.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+///
+/// # Returns
+///
+/// - `Vec<&DataStruct>`: The content items, in course order.
+pub fn content_items(rows: &[DataStruct]) -> Vec<&DataStruct> {
+    let parent_ids: HashSet<&str> = rows
+        .iter()
+        .filter_map(|row| row.parent_id.as_deref())
+        .collect();
+
+    rows.iter()
+        .filter(|row| !parent_ids.contains(row.id.as_str()))
+        .filter(|row| !NON_ITEM_TYPES.contains(&row.type_name.as_str()))
+        .collect()
+}
+
+/// Finds the row of the quiz of a quiz attempt.
+///
+/// The quizzes of the parent of the attempt are taken in course order. When the parent has a
+/// single quiz, the attempt belongs to it. Otherwise, the position decoded from the position
+/// key of the attempt selects the quiz, counting from 1.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+/// - `quiz_attempt`: The quiz attempt, with its decoded parent id and position.
+///
+/// # Returns
+///
+/// - `Option<usize>`: The index of the row of the quiz, or `None` if the attempt cannot be
+///   mapped to a single quiz.
+pub fn find_quiz_index(rows: &[DataStruct], quiz_attempt: &TopicQuizAttempt) -> Option<usize> {
+    let quizzes: Vec<usize> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            row.type_name == "TopicQuiz"
+                && row.parent_topic.as_deref() == Some(&quiz_attempt.parent_id)
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    match (&quizzes[..], quiz_attempt.position) {
+        ([index], _) => Some(*index),
+        (_, Some(position)) => quizzes.get(position as usize - 1).copied(),
+        _ => None,
+    }
+}
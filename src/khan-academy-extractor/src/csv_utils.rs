@@ -2,6 +2,7 @@ use crate::error::AppError;
 use crate::models::DataStruct;
 use csv::Writer;
 use std::fs::File;
+use std::io::Write;
 use std::path::Path;
 
 /// Creates a new CSV file and returns a CSV writer for it.
@@ -31,7 +32,10 @@ pub fn create_csv_file<P: AsRef<Path>>(filename: P) -> Result<Writer<File>, AppE
 /// # Returns
 ///
 /// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError` indicating the type of error that occurred, such as a CSV serialization error.
-pub fn append_data_to_csv(content: &DataStruct, writer: &mut Writer<File>) -> Result<(), AppError> {
+pub fn append_data_to_csv<W: Write>(
+    content: &DataStruct,
+    writer: &mut Writer<W>,
+) -> Result<(), AppError> {
     writer.serialize(content)?;
 
     Ok(())
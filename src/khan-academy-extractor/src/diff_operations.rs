@@ -0,0 +1,240 @@
+use crate::error::AppError;
+use crate::models::{CurriculumChange, DataStruct, NodeMapping};
+use csv::Writer;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+/// Function returning the key used to match a node between two versions of a course.
+type NodeKey = fn(&DataStruct) -> Option<&str>;
+
+/// Matches the nodes of two versions of a course.
+///
+/// Nodes are matched by `id` first. The nodes left are then matched by `slug` and, after
+/// that, by `progressKey`, always between nodes of the same type. Content listed more than
+/// once, under several parents, is matched in course order, a node under the same parent
+/// being preferred.
+///
+/// # Parameters
+///
+/// - `old_rows`: The rows of the old version of the course.
+/// - `new_rows`: The rows of the new version of the course.
+///
+/// # Returns
+///
+/// - `Vec<NodeMapping>`: The matched nodes, in the order of the old version.
+pub fn match_nodes(old_rows: &[DataStruct], new_rows: &[DataStruct]) -> Vec<NodeMapping> {
+    let mut matches: HashMap<usize, (usize, &str)> = HashMap::new();
+    let mut matched_new: HashSet<usize> = HashSet::new();
+    let keys: [(&str, NodeKey); 3] = [
+        ("id", |row| Some(row.id.as_str())),
+        ("slug", |row| Some(row.slug.as_str())),
+        ("progressKey", |row| row.progress_key.as_deref()),
+    ];
+
+    for (matched_by, key) in keys {
+        for (old_index, old_row) in old_rows.iter().enumerate() {
+            if matches.contains_key(&old_index) {
+                continue;
+            }
+            let Some(old_key) = key(old_row) else {
+                continue;
+            };
+            let candidates: Vec<usize> = new_rows
+                .iter()
+                .enumerate()
+                .filter(|(index, row)| {
+                    !matched_new.contains(index)
+                        && row.type_name == old_row.type_name
+                        && key(row) == Some(old_key)
+                })
+                .map(|(index, _)| index)
+                .collect();
+            if let Some(new_index) = candidates
+                .iter()
+                .find(|index| new_rows[**index].parent_id == old_row.parent_id)
+                .or(candidates.first())
+                .copied()
+            {
+                matches.insert(old_index, (new_index, matched_by));
+                matched_new.insert(new_index);
+            }
+        }
+    }
+
+    let mut mappings: Vec<(usize, NodeMapping)> = matches
+        .into_iter()
+        .map(|(old_index, (new_index, matched_by))| {
+            (
+                old_index,
+                NodeMapping {
+                    old_id: old_rows[old_index].id.clone(),
+                    new_id: new_rows[new_index].id.clone(),
+                    type_name: new_rows[new_index].type_name.clone(),
+                    old_title: old_rows[old_index].title.clone(),
+                    new_title: new_rows[new_index].title.clone(),
+                    matched_by: matched_by.to_string(),
+                    old_index,
+                    new_index,
+                },
+            )
+        })
+        .collect();
+    mappings.sort_by_key(|(old_index, _)| *old_index);
+
+    mappings.into_iter().map(|(_, mapping)| mapping).collect()
+}
+
+/// Compares two versions of a course and lists the structural changes between them.
+///
+/// The changes are additions, removals, moves to another parent, reorders within the same
+/// parent, and title changes. A moved node is not reported as reordered.
+///
+/// # Parameters
+///
+/// - `old_rows`: The rows of the old version of the course.
+/// - `new_rows`: The rows of the new version of the course.
+/// - `mappings`: The matched nodes, as returned by `match_nodes`.
+///
+/// # Returns
+///
+/// - `Vec<CurriculumChange>`: The changes, with removals first, then the changes of the
+///   matched nodes in the order of the old version, then the additions.
+pub fn diff_courses(
+    old_rows: &[DataStruct],
+    new_rows: &[DataStruct],
+    mappings: &[NodeMapping],
+) -> Vec<CurriculumChange> {
+    // Parents are matched by id, the rows themselves by index, so content listed under
+    // several parents is compared occurrence by occurrence
+    let parent_ids: HashMap<&str, &str> = mappings
+        .iter()
+        .map(|mapping| (mapping.old_id.as_str(), mapping.new_id.as_str()))
+        .collect();
+    let old_indexes: HashSet<usize> = mappings.iter().map(|mapping| mapping.old_index).collect();
+    let new_indexes: HashSet<usize> = mappings.iter().map(|mapping| mapping.new_index).collect();
+    let change =
+        |change: &str, row: &DataStruct, old_id, new_id, detail: String| CurriculumChange {
+            change: change.to_string(),
+            type_name: row.type_name.clone(),
+            title: row.title.clone(),
+            old_id,
+            new_id,
+            detail,
+        };
+
+    let mut changes: Vec<CurriculumChange> = Vec::new();
+    for (_, row) in old_rows
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !old_indexes.contains(index))
+    {
+        changes.push(change(
+            "removed",
+            row,
+            Some(row.id.clone()),
+            None,
+            format!("from {}", row.parent_title.as_deref().unwrap_or("")),
+        ));
+    }
+
+    for mapping in mappings {
+        let old_row: &DataStruct = &old_rows[mapping.old_index];
+        let new_row: &DataStruct = &new_rows[mapping.new_index];
+        let ids = || (Some(old_row.id.clone()), Some(new_row.id.clone()));
+
+        let mapped_parent: Option<&str> = old_row
+            .parent_id
+            .as_deref()
+            .map(|parent_id| parent_ids.get(parent_id).copied().unwrap_or(parent_id));
+        if mapped_parent != new_row.parent_id.as_deref() {
+            let (old_id, new_id) = ids();
+            changes.push(change(
+                "moved",
+                new_row,
+                old_id,
+                new_id,
+                format!(
+                    "from {} to {}",
+                    old_row.parent_title.as_deref().unwrap_or(""),
+                    new_row.parent_title.as_deref().unwrap_or("")
+                ),
+            ));
+        } else if old_row.order != new_row.order {
+            let (old_id, new_id) = ids();
+            changes.push(change(
+                "reordered",
+                new_row,
+                old_id,
+                new_id,
+                format!("from position {} to {}", old_row.order, new_row.order),
+            ));
+        }
+        if old_row.title != new_row.title {
+            let (old_id, new_id) = ids();
+            changes.push(change(
+                "retitled",
+                new_row,
+                old_id,
+                new_id,
+                format!("from \"{}\"", old_row.title),
+            ));
+        }
+    }
+
+    for (_, row) in new_rows
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !new_indexes.contains(index))
+    {
+        changes.push(change(
+            "added",
+            row,
+            None,
+            Some(row.id.clone()),
+            format!("to {}", row.parent_title.as_deref().unwrap_or("")),
+        ));
+    }
+
+    changes
+}
+
+/// Prints the changes between two versions of a course, one per line.
+///
+/// # Parameters
+///
+/// - `changes`: The changes, as returned by `diff_courses`.
+pub fn print_diff(changes: &[CurriculumChange]) {
+    if changes.is_empty() {
+        println!("No changes");
+    }
+    for change in changes {
+        println!(
+            "{:<9} {} \"{}\" {}",
+            change.change, change.type_name, change.title, change.detail
+        );
+    }
+}
+
+/// Writes the mapping of the old nodes to the new ones in CSV format, so the progress saved
+/// for the old version of a course can be moved onto the new one.
+///
+/// # Parameters
+///
+/// - `mappings`: The matched nodes, as returned by `match_nodes`.
+/// - `writer`: A mutable reference to the CSV `Writer` used to write the mapping.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred, such as an I/O error or CSV serialization error.
+pub fn write_mapping_csv<W: Write>(
+    mappings: &[NodeMapping],
+    writer: &mut Writer<W>,
+) -> Result<(), AppError> {
+    for mapping in mappings {
+        writer.serialize(mapping)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
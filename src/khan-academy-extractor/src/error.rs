@@ -8,8 +8,22 @@ pub enum AppError {
     Json(#[from] serde_json::Error),
     #[error("CSV error: {0}")]
     Csv(#[from] csv::Error),
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Archive error: {0}")]
+    Archive(#[from] zip::result::ZipError),
     #[error("Missing field: {0}")]
     MissingField(String),
     #[error("Missing file: {0}")]
     MissingFile(String),
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Output directory locked by another run: {0}")]
+    Locked(String),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("Incomplete capture: {0}")]
+    IncompleteCapture(String),
 }
@@ -0,0 +1,140 @@
+use crate::annotation_operations::apply_annotations;
+use crate::error::AppError;
+use crate::file_operations::{read_files, read_source};
+use crate::models::EnrichedCourse;
+use crate::progress_operations::build_course;
+use crate::source_operations::InputSource;
+
+pub use crate::annotation_operations::{load_annotations, Annotations};
+pub use crate::file_operations::FileContents;
+
+/// Entry point of the library, running the whole extraction on a set of captures.
+///
+/// The captures can be read from a directory or a bundle file, as the command-line tool does,
+/// from any `InputSource`, or given as strings by an application that already holds them. The extraction returns an
+/// `EnrichedCourse` with the rows of the course, their progress, annotations, and the
+/// attempt history, without writing any file.
+///
+/// ```no_run
+/// use khan_academy_extractor::extractor::Extractor;
+///
+/// let course = Extractor::from_dir("captures", "")?.extract()?;
+/// for row in &course.rows {
+///     println!("{} {:?}", row.title, row.completion_status);
+/// }
+/// # Ok::<(), khan_academy_extractor::error::AppError>(())
+/// ```
+pub struct Extractor {
+    file_contents: FileContents,
+    annotations: Option<Annotations>,
+}
+
+impl Extractor {
+    /// Creates an extractor from the capture files of a directory, or from a bundle file.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: A string slice with the directory of the capture files, or the bundle file.
+    /// - `prefix`: A string slice with the prefix of the capture files.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Extractor, AppError>`: On success, returns the extractor. On failure, returns
+    ///   an `AppError` indicating the type of error that occurred, such as a missing file.
+    pub fn from_dir(path: &str, prefix: &str) -> Result<Self, AppError> {
+        Ok(Self::from_file_contents(read_files(path, prefix)?))
+    }
+
+    /// Creates an extractor from the capture files of a source.
+    ///
+    /// # Parameters
+    ///
+    /// - `source`: The source of the capture files.
+    /// - `prefix`: A string slice with the prefix of the capture files.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Extractor, AppError>`: On success, returns the extractor. On failure, returns
+    ///   an `AppError` indicating the type of error that occurred, such as a missing file.
+    pub fn from_source(source: &dyn InputSource, prefix: &str) -> Result<Self, AppError> {
+        Ok(Self::from_file_contents(read_source(source, prefix)?))
+    }
+
+    /// Creates an extractor from the contents of the captures.
+    ///
+    /// # Parameters
+    ///
+    /// - `content_for_path`: The content of the `contentForPath` capture.
+    /// - `course_progress`: The content of the `courseProgressQuery` capture.
+    /// - `unit_progress`: The contents of the `getUserInfoForTopicProgressMastery-N` captures.
+    /// - `quiz_test_progress`: The contents of the `quizAndUnitTestAttemptsQuery-N` captures.
+    ///
+    /// # Returns
+    ///
+    /// - `Extractor`: The extractor of the captures.
+    pub fn from_strings(
+        content_for_path: impl Into<String>,
+        course_progress: impl Into<String>,
+        unit_progress: Vec<String>,
+        quiz_test_progress: Vec<String>,
+    ) -> Self {
+        Self::from_file_contents(FileContents {
+            json_content: content_for_path.into(),
+            json_course_progress: course_progress.into(),
+            json_unit_progress_files: unit_progress,
+            json_quiz_test_progress_files: quiz_test_progress,
+        })
+    }
+
+    /// Creates an extractor from the captures read by `read_files`.
+    ///
+    /// # Parameters
+    ///
+    /// - `file_contents`: The contents of the captures.
+    ///
+    /// # Returns
+    ///
+    /// - `Extractor`: The extractor of the captures.
+    pub fn from_file_contents(file_contents: FileContents) -> Self {
+        Self {
+            file_contents,
+            annotations: None,
+        }
+    }
+
+    /// Sets the annotations merged into the rows of the course. Keys that do not match any
+    /// row are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// - `annotations`: The annotations by content id or slug.
+    ///
+    /// # Returns
+    ///
+    /// - `Extractor`: The extractor with the annotations.
+    pub fn with_annotations(mut self, annotations: Annotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Returns the contents of the captures of the extractor.
+    pub fn file_contents(&self) -> &FileContents {
+        &self.file_contents
+    }
+
+    /// Runs the extraction.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<EnrichedCourse, AppError>`: On success, returns the rows of the course with
+    ///   their progress and annotations, and its attempt history. On failure, returns an
+    ///   `AppError` indicating the type of error that occurred during the extraction.
+    pub fn extract(&self) -> Result<EnrichedCourse, AppError> {
+        let mut course: EnrichedCourse = build_course(&self.file_contents)?;
+        if let Some(annotations) = &self.annotations {
+            apply_annotations(&mut course.rows, annotations)?;
+        }
+
+        Ok(course)
+    }
+}
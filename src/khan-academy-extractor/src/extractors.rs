@@ -1,7 +1,7 @@
 use crate::error::AppError;
 use crate::json_utils::extract_nested_value;
 use crate::models::{
-    ContentItemProgress, DataStruct, MasteryMapItem, MasteryV2, TopicQuizAttempt,
+    ChallengeAttempt, ContentItemProgress, DataStruct, MasteryMapItem, MasteryV2, TopicQuizAttempt,
     TopicUnitTestAttempt, UnitProgress,
 };
 use base64::engine::general_purpose::STANDARD;
@@ -9,6 +9,30 @@ use base64::Engine;
 use serde::de::Error;
 use serde_json::{from_str, from_value, Value};
 
+/// Challenge nodes of the course content: the field of the parent node that holds the
+/// challenge and its type name.
+///
+/// These field names have not been checked against a capture that includes a challenge.
+pub const CHALLENGE_FIELDS: &[(&str, &str)] = &[
+    ("courseChallenge", "CourseChallenge"),
+    ("masteryChallenge", "MasteryChallenge"),
+];
+
+/// Challenge attempts of the quiz and unit test captures: the pointer to the list of
+/// attempts and the type name of the challenge they belong to.
+///
+/// Like `CHALLENGE_FIELDS`, these pointers have not been checked against a real capture.
+pub const CHALLENGE_ATTEMPT_POINTERS: &[(&str, &str)] = &[
+    (
+        "/data/user/latestCourseChallengeAttempts",
+        "CourseChallenge",
+    ),
+    (
+        "/data/user/latestMasteryChallengeAttempts",
+        "MasteryChallenge",
+    ),
+];
+
 /// Extracts the course content from a JSON string.
 ///
 /// This function parses the provided JSON string and navigates through its structure
@@ -45,6 +69,8 @@ pub fn extract_course_content(json_content: &str) -> Result<Value, AppError> {
 ///
 /// This function parses a JSON object to extract specific fields and constructs a `DataStruct`
 /// with the extracted data. It also incorporates information from a parent `DataStruct` if provided.
+/// The `depth` of the item is one more than the depth of its parent, or 0 without a parent,
+/// and its `path` is the path of its parent followed by its slug.
 ///
 /// # Parameters
 ///
@@ -69,6 +95,10 @@ pub fn extract_info(
     parent: Option<&DataStruct>,
     order: u32,
 ) -> Result<DataStruct, AppError> {
+    let slug: &str = item["slug"]
+        .as_str()
+        .ok_or_else(|| AppError::MissingField("slug".to_string()))?;
+
     Ok(DataStruct {
         id: item["id"]
             .as_str()
@@ -83,10 +113,7 @@ pub fn extract_info(
             .as_str()
             .ok_or_else(|| AppError::MissingField("translatedTitle".to_string()))?
             .to_string(),
-        slug: item["slug"]
-            .as_str()
-            .ok_or_else(|| AppError::MissingField("slug".to_string()))?
-            .to_string(),
+        slug: slug.to_string(),
         relative_url: item["relativeUrl"]
             .as_str()
             .or_else(|| item["urlWithinCurationNode"].as_str())
@@ -111,9 +138,68 @@ pub fn extract_info(
         num_attempted: None,
         num_correct: None,
         num_incorrect: None,
+        best_score: None,
+        latest_score: None,
+        first_score: None,
+        tags: None,
+        notes: None,
+        priority: None,
+        skip: None,
+        due: None,
+        depth: parent.map(|p| p.depth + 1).unwrap_or(0),
+        path: match parent {
+            Some(p) => format!("{}/{}", p.path, slug),
+            None => slug.to_string(),
+        },
     })
 }
 
+/// Extracts information from a course or mastery challenge node and constructs a `DataStruct`.
+///
+/// Challenge nodes are not always captured with every field of a regular content node, so
+/// the missing fields are filled in before calling `extract_info`. The type name defaults to
+/// the one of the challenge field, the title and slug are left empty rather than made up, and
+/// the URL defaults to the one of the parent, which is where the challenge is started from.
+///
+/// # Parameters
+///
+/// - `item`: A reference to a `Value` representing the JSON object of the challenge. It must
+///   contain at least the "id" field.
+///
+/// - `parent`: A reference to the `DataStruct` of the course or unit holding the challenge.
+///
+/// - `type_name`: The type name of the challenge, used when the node has no "__typename".
+///
+/// - `order`: A `u32` representing the order of the challenge within its parent.
+///
+/// # Returns
+///
+/// - `Result<DataStruct, AppError>`: On success, returns a `DataStruct` populated with the extracted
+///   information. On failure, returns an `AppError` indicating the type of error that occurred,
+///   such as a missing field error if the challenge has no "id".
+pub fn extract_challenge_info(
+    item: &Value,
+    parent: &DataStruct,
+    type_name: &str,
+    order: u32,
+) -> Result<DataStruct, AppError> {
+    let mut challenge: Value = item.clone();
+    if challenge["__typename"].is_null() {
+        challenge["__typename"] = Value::from(type_name);
+    }
+    if challenge["translatedTitle"].is_null() {
+        challenge["translatedTitle"] = Value::from("");
+    }
+    if challenge["slug"].is_null() {
+        challenge["slug"] = Value::from("");
+    }
+    if challenge["relativeUrl"].is_null() && challenge["urlWithinCurationNode"].is_null() {
+        challenge["relativeUrl"] = Value::from(parent.relative_url.as_str());
+    }
+
+    extract_info(&challenge, Some(parent), order)
+}
+
 /// Extracts the current mastery level from a JSON string.
 ///
 /// This function parses the provided JSON content to extract the "currentMasteryV2" field,
@@ -238,7 +324,8 @@ pub fn extract_item_progresses(json_content: &str) -> Result<Vec<ContentItemProg
 ///
 /// This function parses the provided JSON content to extract the "latestQuizAttempts" field,
 /// which represents a list of quiz attempt records. It decodes the `position_key` for each
-/// quiz attempt to determine the `parent_id`.
+/// quiz attempt to determine the `parent_id`, and the `position` of the quiz within its
+/// parent when the key encodes it.
 ///
 /// # Parameters
 ///
@@ -260,10 +347,16 @@ pub fn extract_quiz_attempts(json_content: &str) -> Result<Vec<TopicQuizAttempt>
                 .map(|item| {
                     let mut quiz_attempt: TopicQuizAttempt =
                         from_value(item).map_err(AppError::Json)?;
-                    let decoded_str: String = decode_base64(&quiz_attempt.position_key)?;
-                    quiz_attempt.parent_id = decoded_str[decoded_str.find('\u{11}').unwrap() + 1
-                        ..decoded_str.find('\u{c}').unwrap()]
-                        .to_string();
+                    let decoded: Vec<u8> = decode_base64_bytes(&quiz_attempt.position_key)?;
+                    let (parent_id, position): (String, Option<u32>) =
+                        decode_position_key(&decoded).ok_or_else(|| {
+                            AppError::MissingField(format!(
+                                "parent id in positionKey {}",
+                                quiz_attempt.position_key
+                            ))
+                        })?;
+                    quiz_attempt.parent_id = parent_id;
+                    quiz_attempt.position = position;
 
                     Ok(quiz_attempt)
                 })
@@ -302,10 +395,16 @@ pub fn extract_unit_test_attempts(
                 .map(|item| {
                     let mut quiz_attempt: TopicUnitTestAttempt =
                         from_value(item).map_err(AppError::Json)?;
-                    let decoded_str: String = decode_base64(&quiz_attempt.id)?;
-                    quiz_attempt.parent_id = decoded_str
-                        [decoded_str.find(':').unwrap() + 1..decoded_str.find('\u{c}').unwrap()]
-                        .to_string();
+                    let decoded: Vec<u8> = decode_base64_bytes(&quiz_attempt.id)?;
+                    let (parent_start, parent_end): (usize, usize) = find_parent_id(&decoded, b':')
+                        .ok_or_else(|| {
+                            AppError::MissingField(format!(
+                                "parent id in unit test attempt id {}",
+                                quiz_attempt.id
+                            ))
+                        })?;
+                    quiz_attempt.parent_id =
+                        String::from_utf8_lossy(&decoded[parent_start..parent_end]).to_string();
 
                     Ok(quiz_attempt)
                 })
@@ -316,6 +415,145 @@ pub fn extract_unit_test_attempts(
     Ok(unit_test_attempts)
 }
 
+/// Extracts course and mastery challenge attempts from a JSON string.
+///
+/// This function parses the provided JSON content to extract the lists in
+/// `CHALLENGE_ATTEMPT_POINTERS`, when the capture includes them. It decodes the `id` of each
+/// attempt to determine the `parent_id`, in the same way as for unit test attempts, leaving
+/// it empty when the id does not follow that format. Since the shape of challenge attempts
+/// has not been checked against a real capture, attempts that do not have the expected
+/// fields are skipped rather than failing the extraction.
+///
+/// # Parameters
+///
+/// - `json_content`: A string slice containing the JSON content to be parsed. The JSON is
+///   expected to be a valid JSON object with a specific structure.
+///
+/// # Returns
+///
+/// - `Result<Vec<ChallengeAttempt>, AppError>`: On success, returns a vector of `ChallengeAttempt`
+///   structs, empty if the capture has no challenge attempts. On failure, returns an `AppError`
+///   indicating the type of error that occurred, such as a JSON parsing error of the capture.
+pub fn extract_challenge_attempts(json_content: &str) -> Result<Vec<ChallengeAttempt>, AppError> {
+    let parsed: Value = from_str(json_content)?;
+    let mut challenge_attempts: Vec<ChallengeAttempt> = Vec::new();
+
+    for (pointer, challenge_type) in CHALLENGE_ATTEMPT_POINTERS {
+        let Some(items) = parsed.pointer(pointer).and_then(|v| v.as_array()) else {
+            continue;
+        };
+        for item in items {
+            let Ok(mut challenge_attempt) = from_value::<ChallengeAttempt>(item.clone()) else {
+                continue;
+            };
+            let decoded: Vec<u8> = decode_base64_bytes(&challenge_attempt.id).unwrap_or_default();
+            challenge_attempt.challenge_type = challenge_type.to_string();
+            challenge_attempt.parent_id = find_parent_id(&decoded, b':')
+                .map(|(start, end)| String::from_utf8_lossy(&decoded[start..end]).to_string());
+            challenge_attempts.push(challenge_attempt);
+        }
+    }
+
+    Ok(challenge_attempts)
+}
+
+/// Finds the parent id in the bytes of a decoded attempt id, between a start marker and the
+/// `0x0c` byte that follows it.
+///
+/// # Parameters
+///
+/// - `decoded`: The bytes of the decoded key.
+/// - `start_marker`: The byte that precedes the parent id.
+///
+/// # Returns
+///
+/// - `Option<(usize, usize)>`: The start and end of the parent id in the key, or `None` if the
+///   key does not hold one.
+fn find_parent_id(decoded: &[u8], start_marker: u8) -> Option<(usize, usize)> {
+    let start: usize = decoded.iter().position(|byte| *byte == start_marker)? + 1;
+    let end: usize = start + decoded[start..].iter().position(|byte| *byte == 0x0c)?;
+
+    Some((start, end))
+}
+
+/// Decodes the parent id and the position of a quiz from the bytes of a decoded position key.
+///
+/// The key is read as a Protocol Buffers message: each field starts with a varint tag holding
+/// the field number and wire type, followed by a varint value or, for length-delimited fields,
+/// a varint length and that many bytes. The parent id is the length-delimited field 1, up to
+/// the first `0x0c` byte it may hold, and the position is the varint field 2, counting the
+/// quizzes of the parent from 1. Fields with other numbers are skipped.
+///
+/// # Parameters
+///
+/// - `bytes`: The bytes of the decoded position key.
+///
+/// # Returns
+///
+/// - `Option<(String, Option<u32>)>`: The parent id and the position of the quiz, if the key
+///   encodes it, or `None` if the key is not a valid message or does not hold a parent id.
+pub fn decode_position_key(bytes: &[u8]) -> Option<(String, Option<u32>)> {
+    let mut index: usize = 0;
+    let mut parent_id: Option<String> = None;
+    let mut position: Option<u32> = None;
+    while index < bytes.len() {
+        let tag: u64 = read_varint(bytes, &mut index)?;
+        match (tag >> 3, tag & 0x07) {
+            (field, 0) => {
+                let value: u64 = read_varint(bytes, &mut index)?;
+                if field == 2 {
+                    position = u32::try_from(value).ok().filter(|position| *position > 0);
+                }
+            }
+            (field, 2) => {
+                let length: usize = usize::try_from(read_varint(bytes, &mut index)?).ok()?;
+                let value: &[u8] = bytes.get(index..index.checked_add(length)?)?;
+                index += length;
+                if field == 1 {
+                    let end: usize = value
+                        .iter()
+                        .position(|byte| *byte == 0x0c)
+                        .unwrap_or(value.len());
+                    parent_id = Some(String::from_utf8_lossy(&value[..end]).to_string());
+                }
+            }
+            (_, 1) => index = index.checked_add(8).filter(|end| *end <= bytes.len())?,
+            (_, 5) => index = index.checked_add(4).filter(|end| *end <= bytes.len())?,
+            _ => return None,
+        }
+    }
+
+    Some((
+        parent_id.filter(|parent_id| !parent_id.is_empty())?,
+        position,
+    ))
+}
+
+/// Reads a Protocol Buffers varint from `bytes`, starting at `index`, and moves `index` past it.
+///
+/// # Parameters
+///
+/// - `bytes`: The bytes holding the varint.
+/// - `index`: The index of the first byte of the varint, moved past its last byte.
+///
+/// # Returns
+///
+/// - `Option<u64>`: The value of the varint, or `None` if it is truncated or longer than ten
+///   bytes.
+fn read_varint(bytes: &[u8], index: &mut usize) -> Option<u64> {
+    let mut value: u64 = 0;
+    for shift in 0..10 {
+        let byte: u8 = *bytes.get(*index)?;
+        *index += 1;
+        value |= ((byte & 0x7f) as u64) << (7 * shift);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
 /// Decodes a Base64-encoded string into a UTF-8 string.
 ///
 /// This function takes a Base64-encoded string, ensures it is properly padded,
@@ -331,14 +569,32 @@ pub fn extract_unit_test_attempts(
 ///   On failure, returns an `AppError` indicating the type of error that occurred,
 ///   such as a Base64 decoding error.
 pub fn decode_base64(position_key: &str) -> Result<String, AppError> {
-    let mut key: String = position_key.to_string();
-    while key.len() % 4 != 0 {
-        key.push('=');
-    }
-    let decoded_position_key: Vec<u8> = STANDARD
-        .decode(&key)
-        .map_err(|e| AppError::Json(Error::custom(format!("Base64 decode error: {}", e))))?;
+    let decoded_position_key: Vec<u8> = decode_base64_bytes(position_key)?;
     let decoded_str: String = String::from_utf8_lossy(&decoded_position_key).to_string();
 
     Ok(decoded_str)
 }
+
+/// Decodes a Base64-encoded string into its raw bytes.
+///
+/// Unlike `decode_base64`, the bytes are kept as they are, so the varints of a position key,
+/// whose bytes are 0x80 or more when the value is over 127, are not replaced by the UTF-8
+/// replacement character.
+///
+/// # Parameters
+///
+/// - `position_key`: A string slice containing the Base64-encoded data, with or without its
+///   padding.
+///
+/// # Returns
+///
+/// - `Result<Vec<u8>, AppError>`: On success, returns the decoded bytes. On failure, returns
+///   an `AppError` for a Base64 decoding error.
+pub fn decode_base64_bytes(position_key: &str) -> Result<Vec<u8>, AppError> {
+    let padding: usize = (4 - position_key.len() % 4) % 4;
+    let key: String = format!("{}{}", position_key, "=".repeat(padding));
+
+    STANDARD
+        .decode(&key)
+        .map_err(|e| AppError::Json(Error::custom(format!("Base64 decode error: {}", e))))
+}
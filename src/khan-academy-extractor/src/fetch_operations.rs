@@ -0,0 +1,800 @@
+use crate::error::AppError;
+use crate::extractors::extract_course_content;
+use crate::file_utils::find_json_files;
+use crate::json_operations::collect_course;
+use crate::models::DataStruct;
+use crate::server_operations::decode_component;
+use crate::write_utils::write_file_atomically;
+use serde_json::{from_str, json, Value};
+use std::fs::{create_dir_all, read_dir, remove_file, write};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use ureq::{Agent, AgentBuilder};
+
+/// Base URL of the site, used when no other base URL is given.
+pub const DEFAULT_BASE_URL: &str = "https://www.khanacademy.org";
+
+/// Path of the GraphQL endpoint, followed by the name of the operation.
+const GRAPHQL_PATH: &str = "/api/internal/graphql";
+
+/// Delay before the first retry of a failed request, doubled on every following retry.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest delay before a retry, whatever the backoff or the `Retry-After` header asks for.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Time after which a request without any response fails.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Headers of a recorded request that are not sent again: the ones the client sets itself,
+/// and the pseudo-headers of HTTP/2, which start with a colon.
+const SKIPPED_HEADERS: [&str; 6] = [
+    "cookie",
+    "host",
+    "content-length",
+    "accept-encoding",
+    "connection",
+    "x-ka-fkey",
+];
+
+/// Request of a GraphQL operation, as the browser sent it.
+///
+/// The site sends its operations either as persisted queries, a `GET` whose URL holds the
+/// hash of the query and its variables, or as a `POST` whose body holds the text of the query
+/// and its variables. A recorded request is sent again unchanged, except for the values of
+/// its variables that name the recorded course or unit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedRequest {
+    /// Name of the operation, such as `contentForPath`.
+    pub operation_name: String,
+    /// HTTP method of the request.
+    pub method: String,
+    /// Path and query of the URL, without the scheme and the host.
+    pub path: String,
+    /// Headers of the request, in the recorded order.
+    pub headers: Vec<(String, String)>,
+    /// Body of the request, if any.
+    pub body: Option<String>,
+    /// Body of the recorded response, if the recording holds it.
+    pub response: Option<String>,
+}
+
+impl RecordedRequest {
+    /// Returns the variables of the request, from the URL then from the body.
+    fn variables(&self) -> Vec<Value> {
+        let mut variables: Vec<Value> = Vec::new();
+        if let Some((_, query)) = self.path.split_once('?') {
+            variables.extend(
+                query
+                    .split('&')
+                    .filter_map(|pair| pair.split_once('='))
+                    .filter(|(key, _)| *key == "variables")
+                    .filter_map(|(_, value)| from_str(&decode_component(value)).ok()),
+            );
+        }
+        if let Some(body) = self.body.as_deref() {
+            if let Ok(body) = from_str::<Value>(body) {
+                variables.push(body["variables"].clone());
+            }
+        }
+
+        variables
+    }
+
+    /// Checks whether a string value of the variables of the request equals a value.
+    fn mentions(&self, value: &str) -> bool {
+        let mut strings: Vec<&str> = Vec::new();
+        let variables: Vec<Value> = self.variables();
+        variables
+            .iter()
+            .for_each(|variables| collect_strings(variables, &mut strings));
+
+        strings.contains(&value)
+    }
+
+    /// Returns the request with values of its variables replaced.
+    ///
+    /// Only the string values of the variables that equal a replaced value change; the text
+    /// or hash of the query, the other parameters of the URL, and the headers are kept as
+    /// recorded.
+    ///
+    /// # Parameters
+    ///
+    /// - `replacements`: The pairs of a recorded value and the value replacing it.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<RecordedRequest, AppError>`: On success, returns the request with the
+    ///   replaced values. On failure, returns an `AppError::InvalidArgument` if no variable
+    ///   holds any of the replaced values, since the request would then fetch the recorded
+    ///   course or unit rather than the wanted one.
+    pub fn with_values(
+        &self,
+        replacements: &[(String, String)],
+    ) -> Result<RecordedRequest, AppError> {
+        let mut replaced: usize = 0;
+        let mut request: RecordedRequest = self.clone();
+
+        if let Some((path, query)) = self.path.split_once('?') {
+            let pairs: Vec<String> = query
+                .split('&')
+                .map(|pair| match pair.split_once('=') {
+                    Some(("variables", value)) => {
+                        match from_str::<Value>(&decode_component(value)) {
+                            Ok(mut variables) => {
+                                replaced += replace_strings(&mut variables, replacements);
+                                format!("variables={}", encode_component(&variables.to_string()))
+                            }
+                            Err(_) => pair.to_string(),
+                        }
+                    }
+                    _ => pair.to_string(),
+                })
+                .collect();
+            request.path = format!("{}?{}", path, pairs.join("&"));
+        }
+        if let Some(body) = self.body.as_deref() {
+            if let Ok(mut body) = from_str::<Value>(body) {
+                if let Some(variables) = body.get_mut("variables") {
+                    let count: usize = replace_strings(variables, replacements);
+                    if count > 0 {
+                        replaced += count;
+                        request.body = Some(body.to_string());
+                    }
+                }
+            }
+        }
+
+        if replaced == 0 {
+            return Err(AppError::InvalidArgument(format!(
+                "no variable of the recorded {} request names the recorded course or unit",
+                self.operation_name
+            )));
+        }
+
+        Ok(request)
+    }
+}
+
+/// Collects the string values of a JSON value, at any depth.
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(string) => strings.push(string),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| collect_strings(field, strings)),
+        _ => {}
+    }
+}
+
+/// Replaces the string values of a JSON value that equal a recorded value, at any depth, and
+/// returns the number of replaced values.
+fn replace_strings(value: &mut Value, replacements: &[(String, String)]) -> usize {
+    match value {
+        Value::String(string) => match replacements.iter().find(|(from, _)| from == string) {
+            Some((_, to)) => {
+                *string = to.clone();
+                1
+            }
+            None => 0,
+        },
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| replace_strings(item, replacements))
+            .sum(),
+        Value::Object(fields) => fields
+            .values_mut()
+            .map(|field| replace_strings(field, replacements))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Percent-encodes a component of a URL, keeping only the unreserved characters.
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// GraphQL requests recorded by the browser while a learner browsed a course.
+///
+/// The requests are read from a HAR file, the format in which the network panel of the
+/// developer tools of the browser saves the requests of a page. Only the requests to the
+/// GraphQL endpoint are kept, the first one of every operation.
+#[derive(Debug)]
+pub struct RecordedRequests {
+    requests: Vec<RecordedRequest>,
+}
+
+impl RecordedRequests {
+    /// Reads the GraphQL requests of a HAR file.
+    ///
+    /// # Parameters
+    ///
+    /// - `content`: A string slice with the content of the HAR file.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<RecordedRequests, AppError>`: On success, returns the recorded requests. On
+    ///   failure, returns an `AppError` if the content is not JSON, or has no `log.entries`
+    ///   array.
+    pub fn from_har(content: &str) -> Result<Self, AppError> {
+        let har: Value = from_str(content)?;
+        let entries: &Vec<Value> = har["log"]["entries"]
+            .as_array()
+            .ok_or_else(|| AppError::MissingField("log.entries".to_string()))?;
+
+        let mut requests: Vec<RecordedRequest> = Vec::new();
+        for entry in entries {
+            let request: &Value = &entry["request"];
+            let url: &str = request["url"].as_str().unwrap_or_default();
+            let path: &str = match url.split_once("://") {
+                Some((_, rest)) => rest.find('/').map_or("/", |index| &rest[index..]),
+                None => url,
+            };
+            let body: Option<String> = request["postData"]["text"].as_str().map(str::to_string);
+            let operation_name: Option<String> = match path
+                .split('?')
+                .next()
+                .and_then(|path| path.strip_prefix(GRAPHQL_PATH))
+            {
+                Some(rest) => rest
+                    .split('/')
+                    .find(|segment| !segment.is_empty())
+                    .map(str::to_string)
+                    .or_else(|| {
+                        body.as_deref()
+                            .and_then(|body| from_str::<Value>(body).ok())
+                            .and_then(|body| body["operationName"].as_str().map(str::to_string))
+                    }),
+                None => None,
+            };
+            let Some(operation_name) = operation_name else {
+                continue;
+            };
+            if requests
+                .iter()
+                .any(|recorded| recorded.operation_name == operation_name)
+            {
+                continue;
+            }
+
+            let headers: Vec<(String, String)> = request["headers"]
+                .as_array()
+                .map(|headers| {
+                    headers
+                        .iter()
+                        .filter_map(|header| {
+                            Some((
+                                header["name"].as_str()?.to_string(),
+                                header["value"].as_str()?.to_string(),
+                            ))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let content: &Value = &entry["response"]["content"];
+            let response: Option<String> = match content["encoding"].as_str() {
+                Some("base64") => None,
+                _ => content["text"].as_str().map(str::to_string),
+            };
+
+            requests.push(RecordedRequest {
+                operation_name,
+                method: request["method"].as_str().unwrap_or("GET").to_string(),
+                path: path.to_string(),
+                headers,
+                body,
+                response,
+            });
+        }
+
+        Ok(Self { requests })
+    }
+
+    /// Returns the recorded request of an operation.
+    ///
+    /// # Parameters
+    ///
+    /// - `operation_name`: The name of the operation, such as `contentForPath`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<&RecordedRequest, AppError>`: On success, returns the recorded request. On
+    ///   failure, returns an `AppError::InvalidArgument` if the operation was not recorded.
+    pub fn get(&self, operation_name: &str) -> Result<&RecordedRequest, AppError> {
+        self.requests
+            .iter()
+            .find(|request| request.operation_name == operation_name)
+            .ok_or_else(|| {
+                AppError::InvalidArgument(format!(
+                    "no {} request was recorded, browse a page of the course that sends it",
+                    operation_name
+                ))
+            })
+    }
+}
+
+/// Outcome of a single attempt of a request.
+enum Attempt {
+    /// The request succeeded with this body.
+    Done(String),
+    /// The request failed in a way that may succeed later, such as a rate limit or a server
+    /// error, with the delay requested by the server if any.
+    Retry(AppError, Option<Duration>),
+    /// The request failed in a way that retrying cannot fix, such as an expired session.
+    Fail(AppError),
+}
+
+/// Client of the GraphQL API of the site, sending the requests of a logged-in session.
+///
+/// Requests are spaced by at least the configured delay, so a capture does not hammer the
+/// site. Requests that fail with a transport error, a rate limit, or a server error are
+/// retried with an exponential backoff, honouring the `Retry-After` header when the server
+/// sends one, up to `MAX_RETRY_DELAY`.
+pub struct FetchClient {
+    agent: Agent,
+    base_url: String,
+    cookie: String,
+    delay: Duration,
+    retries: u32,
+    last_request: Option<Instant>,
+}
+
+impl FetchClient {
+    /// Creates a client without delay between requests and without retries.
+    ///
+    /// # Parameters
+    ///
+    /// - `base_url`: The base URL of the site, such as `DEFAULT_BASE_URL`, or the address of
+    ///   a replay server.
+    /// - `cookie`: The `Cookie` header of a logged-in session, copied from the browser.
+    ///
+    /// # Returns
+    ///
+    /// - `FetchClient`: The client.
+    pub fn new(base_url: &str, cookie: &str) -> Self {
+        Self {
+            agent: AgentBuilder::new().timeout(REQUEST_TIMEOUT).build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cookie: cookie.trim().to_string(),
+            delay: Duration::ZERO,
+            retries: 0,
+            last_request: None,
+        }
+    }
+
+    /// Sets the minimum delay between the start of two requests.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the number of times a failed request is retried.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sends a recorded GraphQL request and returns the body of its response.
+    ///
+    /// # Parameters
+    ///
+    /// - `request`: The recorded request, with the values of its variables already replaced.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<String, AppError>`: On success, returns the body of the response, unchanged.
+    ///   On failure, returns an `AppError::Http` if the request still fails after the
+    ///   retries, or if the response holds GraphQL errors without any data.
+    pub fn send(&mut self, request: &RecordedRequest) -> Result<String, AppError> {
+        let mut retry: u32 = 0;
+        loop {
+            self.wait();
+            let (error, retry_after): (AppError, Option<Duration>) = match self.attempt(request) {
+                Attempt::Done(content) => return check_response(&request.operation_name, content),
+                Attempt::Fail(error) => return Err(error),
+                Attempt::Retry(error, retry_after) => (error, retry_after),
+            };
+            if retry >= self.retries {
+                return Err(error);
+            }
+            sleep(retry_delay(retry, retry_after));
+            retry += 1;
+        }
+    }
+
+    /// Waits until the delay since the start of the last request has elapsed.
+    fn wait(&mut self) {
+        if let Some(last_request) = self.last_request {
+            let elapsed: Duration = last_request.elapsed();
+            if elapsed < self.delay {
+                sleep(self.delay - elapsed);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    /// Sends a single attempt of a request.
+    fn attempt(&self, recorded: &RecordedRequest) -> Attempt {
+        let url: String = format!("{}{}", self.base_url, recorded.path);
+        let mut request: ureq::Request = self.agent.request(&recorded.method, &url);
+        for (name, value) in &recorded.headers {
+            let lowercase: String = name.to_lowercase();
+            if !lowercase.starts_with(':') && !SKIPPED_HEADERS.contains(&lowercase.as_str()) {
+                request = request.set(name, value);
+            }
+        }
+        if !self.cookie.is_empty() {
+            request = request.set("Cookie", &self.cookie);
+        }
+        if let Some(fkey) = cookie_value(&self.cookie, "fkey") {
+            request = request.set("X-KA-FKey", fkey);
+        }
+
+        let result: Result<ureq::Response, ureq::Error> = match recorded.body.as_deref() {
+            Some(body) => request.send_string(body),
+            None => request.call(),
+        };
+        match result {
+            Ok(response) => match response.into_string() {
+                Ok(content) => Attempt::Done(content),
+                Err(error) => Attempt::Retry(AppError::Http(format!("{}: {}", url, error)), None),
+            },
+            Err(ureq::Error::Status(status, response)) => {
+                let retry_after: Option<Duration> = response
+                    .header("Retry-After")
+                    .and_then(|value| value.trim().parse::<u64>().ok())
+                    .map(Duration::from_secs);
+                let error: AppError = AppError::Http(match status {
+                    401 | 403 => format!(
+                        "{} returned status {}, check that the session cookie is current",
+                        url, status
+                    ),
+                    _ => format!("{} returned status {}", url, status),
+                });
+                if status == 429 || status >= 500 {
+                    Attempt::Retry(error, retry_after)
+                } else {
+                    Attempt::Fail(error)
+                }
+            }
+            Err(error) => Attempt::Retry(AppError::Http(format!("{}: {}", url, error)), None),
+        }
+    }
+}
+
+/// Returns the delay before a retry of a failed request.
+///
+/// # Parameters
+///
+/// - `retry`: The number of retries already made.
+/// - `retry_after`: The delay requested by the `Retry-After` header of the server, if any.
+///
+/// # Returns
+///
+/// - `Duration`: The requested delay, or the exponential backoff without one, at most
+///   `MAX_RETRY_DELAY`.
+pub fn retry_delay(retry: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after
+        .unwrap_or_else(|| RETRY_DELAY.saturating_mul(2u32.saturating_pow(retry)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Finds the value of a cookie in a `Cookie` header.
+fn cookie_value<'a>(cookie: &'a str, name: &str) -> Option<&'a str> {
+    cookie
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Checks that the body of a response holds data rather than only GraphQL errors.
+fn check_response(operation_name: &str, content: String) -> Result<String, AppError> {
+    let response: Value = from_str(&content)?;
+    if response["data"].is_null() {
+        let messages: Vec<&str> = response["errors"]
+            .as_array()
+            .map(|errors| {
+                errors
+                    .iter()
+                    .filter_map(|error| error["message"].as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Err(AppError::Http(format!(
+            "{} returned no data: {}",
+            operation_name,
+            messages.join("; ")
+        )));
+    }
+
+    Ok(content)
+}
+
+/// Normalises the path of a course to the form the `contentForPath` operation expects.
+///
+/// # Parameters
+///
+/// - `course_path`: The path of the course, such as `/math/algebra`, or its full URL.
+///
+/// # Returns
+///
+/// - `String`: The path without the scheme, the host, the query, and the outer slashes, such
+///   as `math/algebra`.
+pub fn normalise_course_path(course_path: &str) -> String {
+    let path: &str = match course_path.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |index| &rest[index..]),
+        None => course_path,
+    };
+    let path: &str = path.split(['?', '#']).next().unwrap_or(path);
+
+    path.trim_matches('/').to_string()
+}
+
+/// Returns the replacements of a recorded relative URL, with and without its leading slash.
+fn path_replacements(recorded: &str, target: &str) -> Vec<(String, String)> {
+    let recorded: &str = recorded.trim_matches('/');
+    let target: &str = target.trim_matches('/');
+    if recorded.is_empty() {
+        return Vec::new();
+    }
+
+    vec![
+        (format!("/{}", recorded), format!("/{}", target)),
+        (recorded.to_string(), target.to_string()),
+    ]
+}
+
+/// Returns the values identifying a row, with its relative URL with and without its leading
+/// slash.
+fn row_values(row: &DataStruct) -> Vec<String> {
+    let mut values: Vec<String> = vec![row.id.clone(), row.slug.clone()];
+    values.extend(row.progress_key.clone());
+    values.extend(
+        path_replacements(&row.relative_url, "")
+            .into_iter()
+            .map(|(value, _)| value),
+    );
+    values.retain(|value| !value.is_empty());
+
+    values
+}
+
+/// Returns the replacements of the values identifying a recorded row by the ones of a row.
+fn row_replacements(recorded: &DataStruct, target: &DataStruct) -> Vec<(String, String)> {
+    let mut replacements: Vec<(String, String)> = vec![
+        (recorded.id.clone(), target.id.clone()),
+        (recorded.slug.clone(), target.slug.clone()),
+    ];
+    if let (Some(recorded), Some(target)) = (&recorded.progress_key, &target.progress_key) {
+        replacements.push((recorded.clone(), target.clone()));
+    }
+    replacements.extend(path_replacements(
+        &recorded.relative_url,
+        &target.relative_url,
+    ));
+    replacements.retain(|(from, _)| !from.is_empty());
+
+    replacements
+}
+
+/// Operations fetched once for every unit, in the order their files are written.
+const UNIT_OPERATIONS: [&str; 2] = [
+    "getUserInfoForTopicProgressMastery",
+    "quizAndUnitTestAttemptsQuery",
+];
+
+/// Returns the built-in request of an operation, used when no request was recorded.
+///
+/// The request is a `POST` to the endpoint of the operation, whose body holds the name of the
+/// operation and its variables, without the text or hash of the query. A server that knows
+/// the operations by name, such as the replay server, answers it. The variable names have not
+/// been checked against the live site, which may need the recorded requests instead.
+///
+/// # Parameters
+///
+/// - `operation_name`: The name of the operation, such as `contentForPath`.
+/// - `variables`: The variables of the operation.
+///
+/// # Returns
+///
+/// - `RecordedRequest`: The request, without a recorded response.
+pub fn built_in_request(operation_name: &str, variables: Value) -> RecordedRequest {
+    RecordedRequest {
+        operation_name: operation_name.to_string(),
+        method: "POST".to_string(),
+        path: format!("{}/{}", GRAPHQL_PATH, operation_name),
+        headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+        body: Some(json!({ "operationName": operation_name, "variables": variables }).to_string()),
+        response: None,
+    }
+}
+
+/// Requests recorded by the browser, with the rows of the recorded course and the index of
+/// the unit the variables of every unit operation name.
+struct Recording<'a> {
+    content_for_path: &'a RecordedRequest,
+    course_progress: &'a RecordedRequest,
+    rows: Vec<DataStruct>,
+    unit_requests: Vec<(&'a RecordedRequest, usize)>,
+}
+
+impl<'a> Recording<'a> {
+    /// Reads the recorded course from the recorded response of `contentForPath`, and the
+    /// recorded unit of every unit operation from the variables of its request.
+    fn new(requests: &'a RecordedRequests) -> Result<Self, AppError> {
+        let content_for_path: &RecordedRequest = requests.get("contentForPath")?;
+        let rows: Vec<DataStruct> = collect_course(&extract_course_content(
+            content_for_path.response.as_deref().ok_or_else(|| {
+                AppError::MissingField(
+                    "response of the recorded contentForPath request".to_string(),
+                )
+            })?,
+        )?)?;
+        if rows.is_empty() {
+            return Err(AppError::MissingField("course".to_string()));
+        }
+        let mut unit_requests: Vec<(&RecordedRequest, usize)> = Vec::new();
+        for operation_name in UNIT_OPERATIONS {
+            let recorded: &RecordedRequest = requests.get(operation_name)?;
+            let unit_index: usize = rows
+                .iter()
+                .position(|row| {
+                    row.type_name == "Unit"
+                        && row_values(row)
+                            .iter()
+                            .any(|value| recorded.mentions(value))
+                })
+                .ok_or_else(|| {
+                    AppError::InvalidArgument(format!(
+                        "no variable of the recorded {} request names a unit of the recorded course",
+                        operation_name
+                    ))
+                })?;
+            unit_requests.push((recorded, unit_index));
+        }
+
+        Ok(Self {
+            content_for_path,
+            course_progress: requests.get("courseProgressQuery")?,
+            rows,
+            unit_requests,
+        })
+    }
+
+    /// Returns the recorded course.
+    fn course(&self) -> &DataStruct {
+        &self.rows[0]
+    }
+}
+
+/// Removes the numbered capture files of a prefix that a fetch did not write, such as the
+/// files of units the course no longer has, so they are not read with the fetched ones.
+fn remove_stale_files(directory: &Path, prefix: &str, written: &[String]) -> Result<(), AppError> {
+    let files: Vec<String> = read_dir(directory)?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    for operation_name in UNIT_OPERATIONS {
+        for file in find_json_files(&files, prefix, &format!("{}-", operation_name)) {
+            if !written.contains(file) {
+                remove_file(directory.join(file))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Captures the data of a course through the GraphQL API into capture files.
+///
+/// When the browser recorded the requests while a learner browsed a course, they are sent
+/// again with the values naming the recorded course and unit replaced by the ones of the
+/// wanted course and of each of its units. The recorded course is read from the recorded
+/// response of `contentForPath`, and the recorded unit of a request is the unit whose id,
+/// slug, progress key, or relative URL one of its variables holds; the names of the
+/// variables are never guessed. Without a recording, the requests of `built_in_request` are
+/// sent instead.
+///
+/// The operations are `contentForPath` for the structure of the course,
+/// `courseProgressQuery` for its progress, then `getUserInfoForTopicProgressMastery` and
+/// `quizAndUnitTestAttemptsQuery` for every unit, in course order. The responses are written
+/// unchanged, under the names `read_files` expects, the units being numbered from 1. Once
+/// every unit is fetched, the numbered files of the prefix left from an earlier capture are
+/// removed.
+///
+/// # Parameters
+///
+/// - `client`: The client sending the requests.
+/// - `requests`: The requests recorded by the browser, or `None` to send the built-in ones.
+/// - `course_path`: The path of the course, such as `/math/algebra`, or its full URL.
+/// - `prefix`: A string slice with the prefix of the capture files.
+/// - `directory`: The directory where the capture files are written, created if missing.
+///
+/// # Returns
+///
+/// - `Result<Vec<String>, AppError>`: On success, returns the names of the written files. On
+///   failure, returns an `AppError` indicating the type of error that occurred, such as an
+///   HTTP error, an operation missing from the recording, or a recorded request whose
+///   variables name no recorded course or unit.
+pub fn fetch_course(
+    client: &mut FetchClient,
+    requests: Option<&RecordedRequests>,
+    course_path: &str,
+    prefix: &str,
+    directory: &Path,
+) -> Result<Vec<String>, AppError> {
+    let recording: Option<Recording> = requests.map(Recording::new).transpose()?;
+
+    create_dir_all(directory)?;
+    let mut written: Vec<String> = Vec::new();
+    let mut save = |name: String, content: &str| -> Result<(), AppError> {
+        write_file_atomically(directory.join(&name), 0, |temporary| {
+            Ok(write(temporary, content)?)
+        })?;
+        written.push(name);
+        Ok(())
+    };
+
+    let path: String = normalise_course_path(course_path);
+    let request: RecordedRequest = match &recording {
+        Some(recording) => recording
+            .content_for_path
+            .with_values(&path_replacements(&recording.course().relative_url, &path))?,
+        None => built_in_request("contentForPath", json!({ "path": path })),
+    };
+    let content: String = client.send(&request)?;
+    save(format!("{}contentForPath.json", prefix), &content)?;
+    let rows: Vec<DataStruct> = collect_course(&extract_course_content(&content)?)?;
+    let course: &DataStruct = rows
+        .first()
+        .ok_or_else(|| AppError::MissingField("course".to_string()))?;
+
+    let request: RecordedRequest = match &recording {
+        Some(recording) => recording
+            .course_progress
+            .with_values(&row_replacements(recording.course(), course))?,
+        None => built_in_request("courseProgressQuery", json!({ "courseId": course.id })),
+    };
+    let content: String = client.send(&request)?;
+    save(format!("{}courseProgressQuery.json", prefix), &content)?;
+
+    let units: Vec<&DataStruct> = rows.iter().filter(|row| row.type_name == "Unit").collect();
+    for (index, unit) in units.iter().enumerate() {
+        let number: usize = index + 1;
+        for (operation_index, operation_name) in UNIT_OPERATIONS.iter().enumerate() {
+            let request: RecordedRequest = match &recording {
+                Some(recording) => {
+                    let (recorded, unit_index) = recording.unit_requests[operation_index];
+                    let mut replacements: Vec<(String, String)> =
+                        row_replacements(&recording.rows[unit_index], unit);
+                    replacements.extend(row_replacements(recording.course(), course));
+                    recorded.with_values(&replacements)?
+                }
+                None => built_in_request(
+                    operation_name,
+                    json!({ "topicId": unit.id, "courseId": course.id }),
+                ),
+            };
+            let content: String = client.send(&request)?;
+            save(
+                format!("{}{}-{}.json", prefix, operation_name, number),
+                &content,
+            )?;
+        }
+    }
+
+    remove_stale_files(directory, prefix, &written)?;
+
+    Ok(written)
+}
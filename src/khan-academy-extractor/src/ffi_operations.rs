@@ -0,0 +1,268 @@
+use crate::error::AppError;
+use crate::extractor::Extractor;
+use crate::models::EnrichedCourse;
+use serde_json::to_string;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::null_mut;
+use std::slice::from_raw_parts;
+
+thread_local! {
+    /// Message of the last error of the C API on the current thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Extractor handed to C callers as an opaque pointer.
+pub struct KaeExtractor {
+    extractor: Extractor,
+}
+
+/// Buffer of bytes owned by the caller, holding the UTF-8 content of a capture.
+#[repr(C)]
+pub struct KaeBuffer {
+    /// Pointer to the first byte of the buffer.
+    pub data: *const u8,
+    /// Number of bytes of the buffer.
+    pub len: usize,
+}
+
+/// Records the message of an error as the last error of the current thread.
+fn set_last_error(message: String) {
+    let message: CString = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Runs a function of the C API, recording its error or panic as the last error.
+///
+/// # Parameters
+///
+/// - `function`: The body of the C API function.
+///
+/// # Returns
+///
+/// - `Option<T>`: The result of the function, or `None` if it failed or panicked.
+fn guard<T, F: FnOnce() -> Result<T, AppError>>(function: F) -> Option<T> {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(function)) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(error)) => {
+            set_last_error(error.to_string());
+            None
+        }
+        Err(_) => {
+            set_last_error("internal error".to_string());
+            None
+        }
+    }
+}
+
+/// Reads a NUL-terminated string given by a C caller.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string.
+unsafe fn read_c_string(value: *const c_char, name: &str) -> Result<String, AppError> {
+    if value.is_null() {
+        return Err(AppError::InvalidArgument(format!("{} is null", name)));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| AppError::InvalidArgument(format!("{} is not valid UTF-8", name)))
+}
+
+/// Reads a buffer given by a C caller.
+///
+/// # Safety
+///
+/// `buffer.data` must be null with a length of 0, or point to `buffer.len` readable bytes.
+unsafe fn read_buffer(buffer: &KaeBuffer, name: &str) -> Result<String, AppError> {
+    if buffer.data.is_null() {
+        return match buffer.len {
+            0 => Ok(String::new()),
+            _ => Err(AppError::InvalidArgument(format!("{} is null", name))),
+        };
+    }
+    String::from_utf8(from_raw_parts(buffer.data, buffer.len).to_vec())
+        .map_err(|_| AppError::InvalidArgument(format!("{} is not valid UTF-8", name)))
+}
+
+/// Reads an array of buffers given by a C caller.
+///
+/// # Safety
+///
+/// `buffers` must be null with a count of 0, or point to `count` valid buffers.
+unsafe fn read_buffers(
+    buffers: *const KaeBuffer,
+    count: usize,
+    name: &str,
+) -> Result<Vec<String>, AppError> {
+    if buffers.is_null() {
+        return match count {
+            0 => Ok(Vec::new()),
+            _ => Err(AppError::InvalidArgument(format!("{} is null", name))),
+        };
+    }
+    from_raw_parts(buffers, count)
+        .iter()
+        .map(|buffer| read_buffer(buffer, name))
+        .collect()
+}
+
+/// Creates an extractor from the capture files of a directory, an archive, or a bundle file.
+///
+/// # Parameters
+///
+/// - `path`: The path of the captures, as a NUL-terminated UTF-8 string.
+/// - `prefix`: The prefix of the capture files, as a NUL-terminated UTF-8 string, or null
+///   for no prefix.
+///
+/// # Returns
+///
+/// - `*mut KaeExtractor`: The extractor, to be freed with `kae_extractor_free`, or null on
+///   failure, with the message available from `kae_last_error`.
+///
+/// # Safety
+///
+/// `path` must point to a NUL-terminated string, and `prefix` must be null or point to one.
+#[no_mangle]
+pub unsafe extern "C" fn kae_extractor_from_dir(
+    path: *const c_char,
+    prefix: *const c_char,
+) -> *mut KaeExtractor {
+    guard(|| {
+        let path: String = read_c_string(path, "path")?;
+        let prefix: String = if prefix.is_null() {
+            String::new()
+        } else {
+            read_c_string(prefix, "prefix")?
+        };
+        Ok(Box::into_raw(Box::new(KaeExtractor {
+            extractor: Extractor::from_dir(&path, &prefix)?,
+        })))
+    })
+    .unwrap_or(null_mut())
+}
+
+/// Creates an extractor from the contents of the captures held in memory.
+///
+/// The buffers are copied, so they can be released as soon as the function returns.
+///
+/// # Parameters
+///
+/// - `content_for_path`: The content of the `contentForPath` capture.
+/// - `course_progress`: The content of the `courseProgressQuery` capture.
+/// - `unit_progress`: The contents of the `getUserInfoForTopicProgressMastery-N` captures.
+/// - `unit_progress_count`: The number of buffers in `unit_progress`.
+/// - `quiz_test_progress`: The contents of the `quizAndUnitTestAttemptsQuery-N` captures.
+/// - `quiz_test_progress_count`: The number of buffers in `quiz_test_progress`.
+///
+/// # Returns
+///
+/// - `*mut KaeExtractor`: The extractor, to be freed with `kae_extractor_free`, or null on
+///   failure, with the message available from `kae_last_error`.
+///
+/// # Safety
+///
+/// Every buffer must point to its number of readable bytes, and every array of buffers must
+/// be null with a count of 0, or point to its number of buffers.
+#[no_mangle]
+pub unsafe extern "C" fn kae_extractor_from_buffers(
+    content_for_path: KaeBuffer,
+    course_progress: KaeBuffer,
+    unit_progress: *const KaeBuffer,
+    unit_progress_count: usize,
+    quiz_test_progress: *const KaeBuffer,
+    quiz_test_progress_count: usize,
+) -> *mut KaeExtractor {
+    guard(|| {
+        Ok(Box::into_raw(Box::new(KaeExtractor {
+            extractor: Extractor::from_strings(
+                read_buffer(&content_for_path, "content_for_path")?,
+                read_buffer(&course_progress, "course_progress")?,
+                read_buffers(unit_progress, unit_progress_count, "unit_progress")?,
+                read_buffers(
+                    quiz_test_progress,
+                    quiz_test_progress_count,
+                    "quiz_test_progress",
+                )?,
+            ),
+        })))
+    })
+    .unwrap_or(null_mut())
+}
+
+/// Runs the extraction and returns the enriched course as JSON, with its `rows`, `attempts`,
+/// and `unmappedAttempts`.
+///
+/// # Parameters
+///
+/// - `extractor`: The extractor.
+///
+/// # Returns
+///
+/// - `*mut c_char`: The JSON of the course, as a NUL-terminated UTF-8 string to be freed with
+///   `kae_string_free`, or null on failure, with the message available from `kae_last_error`.
+///
+/// # Safety
+///
+/// `extractor` must be null or a pointer returned by one of the `kae_extractor_from_*`
+/// functions that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kae_extractor_extract_json(extractor: *const KaeExtractor) -> *mut c_char {
+    guard(|| {
+        let extractor: &KaeExtractor = extractor
+            .as_ref()
+            .ok_or_else(|| AppError::InvalidArgument("extractor is null".to_string()))?;
+        let course: EnrichedCourse = extractor.extractor.extract()?;
+        let json_content: String = to_string(&course)?;
+        Ok(CString::new(json_content)
+            .map_err(|_| AppError::InvalidArgument("course contains a NUL byte".to_string()))?
+            .into_raw())
+    })
+    .unwrap_or(null_mut())
+}
+
+/// Frees an extractor. Null is ignored.
+///
+/// # Safety
+///
+/// `extractor` must be null or a pointer returned by one of the `kae_extractor_from_*`
+/// functions that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kae_extractor_free(extractor: *mut KaeExtractor) {
+    if !extractor.is_null() {
+        drop(Box::from_raw(extractor));
+    }
+}
+
+/// Frees a string returned by the C API. Null is ignored.
+///
+/// # Safety
+///
+/// `value` must be null or a string returned by `kae_extractor_extract_json` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn kae_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Returns the message of the last error of the C API on the current thread.
+///
+/// # Returns
+///
+/// - `*const c_char`: The message, as a NUL-terminated UTF-8 string owned by the library and
+///   valid until the next call to the C API on the same thread, or null if the last call
+///   succeeded.
+#[no_mangle]
+pub extern "C" fn kae_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
@@ -1,8 +1,9 @@
 use crate::error::AppError;
-use crate::file_utils::{
-    find_and_read_json_file, find_and_read_json_files, list_files_in_directory,
-};
+use crate::file_utils::{find_json_file, find_json_files};
+use crate::source_operations::{open_source, InputSource};
 
+/// Contents of the capture files of a course, as read by `read_files`.
+#[derive(Debug, Clone)]
 pub struct FileContents {
     pub json_content: String,
     pub json_course_progress: String,
@@ -10,14 +11,15 @@ pub struct FileContents {
     pub json_quiz_test_progress_files: Vec<String>,
 }
 
-/// Reads and processes JSON files from a specified directory.
+/// Reads and processes JSON files from a specified directory or bundle file.
 ///
 /// This function searches for specific JSON files in the given directory,
 /// reads their contents, and returns them as a `FileContents` struct.
 ///
 /// # Parameters
 ///
-/// * `path` - A string slice that holds the path to the directory containing the JSON files.
+/// * `path` - A string slice that holds the path to the directory containing the JSON files,
+///   or to a bundle file with all of them.
 /// * `prefix` - A string slice that specifies the prefix for the JSON files to be processed.
 ///
 /// # Returns
@@ -32,20 +34,43 @@ pub struct FileContents {
 /// * Any of the required JSON files are not found
 /// * There are issues reading the contents of the files
 pub fn read_files(path: &str, prefix: &str) -> Result<FileContents, AppError> {
-    let files: Vec<String> = list_files_in_directory(path)?;
+    read_source(open_source(path)?.as_ref(), prefix)
+}
 
-    let json_content: String = find_and_read_json_file(&files, path, prefix, "contentForPath")?;
-    let json_course_progress: String =
-        find_and_read_json_file(&files, path, prefix, "courseProgressQuery")?;
-    let json_unit_progress_files: Vec<String> =
-        find_and_read_json_files(&files, path, prefix, "getUserInfoForTopicProgressMastery-")?;
-    let json_quiz_test_progress_files: Vec<String> =
-        find_and_read_json_files(&files, path, prefix, "quizAndUnitTestAttemptsQuery-")?;
+/// Reads the capture files of a course from a source.
+///
+/// The files are matched by name in the same way for every source: `contentForPath` and
+/// `courseProgressQuery` are required, and the numbered `getUserInfoForTopicProgressMastery-N`
+/// and `quizAndUnitTestAttemptsQuery-N` files are read in the order of their numbers.
+///
+/// # Parameters
+///
+/// - `source`: The source of the capture files.
+/// - `prefix`: A string slice with the prefix of the capture files.
+///
+/// # Returns
+///
+/// - `Result<FileContents, AppError>`: On success, returns the contents of the capture files.
+///   On failure, returns an `AppError` indicating the type of error that occurred, such as a
+///   missing file error.
+pub fn read_source(source: &dyn InputSource, prefix: &str) -> Result<FileContents, AppError> {
+    let files: Vec<String> = source.list_files()?;
+    let read_required = |suffix: &str| -> Result<String, AppError> {
+        let file: &String = find_json_file(&files, prefix, suffix)
+            .ok_or_else(|| AppError::MissingFile(format!("{} file not found", suffix)))?;
+        source.read_file(file)
+    };
+    let read_numbered = |suffix: &str| -> Result<Vec<String>, AppError> {
+        find_json_files(&files, prefix, suffix)
+            .into_iter()
+            .map(|file| source.read_file(file))
+            .collect()
+    };
 
     Ok(FileContents {
-        json_content,
-        json_course_progress,
-        json_unit_progress_files,
-        json_quiz_test_progress_files,
+        json_content: read_required("contentForPath")?,
+        json_course_progress: read_required("courseProgressQuery")?,
+        json_unit_progress_files: read_numbered("getUserInfoForTopicProgressMastery-")?,
+        json_quiz_test_progress_files: read_numbered("quizAndUnitTestAttemptsQuery-")?,
     })
 }
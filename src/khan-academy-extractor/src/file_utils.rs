@@ -1,5 +1,6 @@
 use crate::error::AppError;
-use crate::json_utils::read_json_file;
+use crate::json_utils::{read_json_file, GZIP_EXTENSION};
+use crate::write_utils::LOCK_FILE_NAME;
 use std::fs::{read_dir, DirEntry};
 use std::path::{Path, PathBuf};
 
@@ -7,7 +8,9 @@ use std::path::{Path, PathBuf};
 ///
 /// This function reads the contents of a directory and collects the names of all files
 /// present in that directory into a vector of strings. It does not include directories
-/// or other non-file entries.
+/// or other non-file entries, nor the lock file a run leaves in its output directory.
+/// Compressed `.json.gz` files are listed without their `.gz` extension, so they are matched
+/// like the other capture files and read with `read_json_file`.
 ///
 /// # Parameters
 ///
@@ -27,7 +30,9 @@ pub fn list_files_in_directory<P: AsRef<Path>>(path: P) -> Result<Vec<String>, A
         if path.is_file() {
             if let Some(file_name) = path.file_name() {
                 if let Some(file_name_str) = file_name.to_str() {
-                    file_list.push(file_name_str.to_string());
+                    if file_name_str != LOCK_FILE_NAME {
+                        file_list.push(uncompressed_name(file_name_str).to_string());
+                    }
                 }
             }
         }
@@ -35,6 +40,92 @@ pub fn list_files_in_directory<P: AsRef<Path>>(path: P) -> Result<Vec<String>, A
     Ok(file_list)
 }
 
+/// Returns the name of a capture file without the `.gz` extension of a compressed `.json.gz`
+/// file, or the name itself for any other file.
+///
+/// # Parameters
+///
+/// - `file_name`: A string slice with the name of the file.
+///
+/// # Returns
+///
+/// - `&str`: The name of the file as it is matched with the capture types.
+pub fn uncompressed_name(file_name: &str) -> &str {
+    match file_name.strip_suffix(GZIP_EXTENSION) {
+        Some(name) if name.ends_with(".json") => name,
+        _ => file_name,
+    }
+}
+
+/// Finds the JSON file of a capture type in a list of files.
+///
+/// The file matches when its name is the prefix followed by the suffix, with or without the
+/// `.json` extension.
+///
+/// # Parameters
+///
+/// - `files`: A slice of `String` with the names of the files, without a path.
+/// - `prefix`: A string slice with the prefix of the capture files.
+/// - `suffix`: A string slice with the name of the capture type, such as `contentForPath`.
+///
+/// # Returns
+///
+/// - `Option<&String>`: The name of the file, or `None` if it is not in the list.
+pub fn find_json_file<'a>(files: &'a [String], prefix: &str, suffix: &str) -> Option<&'a String> {
+    let file_name: String = format!("{}{}", prefix, suffix);
+    files
+        .iter()
+        .find(|&file| file == &format!("{}.json", file_name) || file == &file_name)
+}
+
+/// Finds the numbered JSON files of a capture type in a list of files, sorted by their number.
+///
+/// The files match when their name starts with the prefix followed by the suffix and they
+/// have the `.json` extension or no extension at all. A file without a valid number is
+/// sorted as number 0.
+///
+/// # Parameters
+///
+/// - `files`: A slice of `String` with the names of the files, without a path.
+/// - `prefix`: A string slice with the prefix of the capture files.
+/// - `suffix`: A string slice with the name of the capture type, such as
+///   `getUserInfoForTopicProgressMastery-`.
+///
+/// # Returns
+///
+/// - `Vec<&String>`: The names of the matching files, sorted by their number.
+pub fn find_json_files<'a>(files: &'a [String], prefix: &str, suffix: &str) -> Vec<&'a String> {
+    let file_prefix: String = format!("{}{}", prefix, suffix);
+    let mut file_names: Vec<&String> = files
+        .iter()
+        .filter(|&file| {
+            (file.starts_with(&file_prefix) && file.ends_with(".json"))
+                || (file.starts_with(&file_prefix) && !file.contains('.'))
+        })
+        .collect();
+    file_names.sort_by_key(|file| file_number(file));
+
+    file_names
+}
+
+/// Returns the number of a numbered JSON file, the part of its name after the last `-`.
+///
+/// # Parameters
+///
+/// - `file`: A string slice with the name of the file, such as
+///   `getUserInfoForTopicProgressMastery-2.json`.
+///
+/// # Returns
+///
+/// - `u32`: The number of the file, or 0 if the name does not end with a valid number.
+pub fn file_number(file: &str) -> u32 {
+    file.trim_end_matches(".json")
+        .rsplit('-')
+        .next()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(0)
+}
+
 /// Searches for a JSON file in a list of files, constructs its path, and reads its contents.
 ///
 /// This function attempts to find a JSON file in the provided list of file names that matches
@@ -66,10 +157,7 @@ pub fn find_and_read_json_file(
     prefix: &str,
     suffix: &str,
 ) -> Result<String, AppError> {
-    let file_name: String = format!("{}{}", prefix, suffix);
-    let file_path: String = files
-        .iter()
-        .find(|&file| file == &format!("{}.json", file_name) || file == &file_name)
+    let file_path: String = find_json_file(files, prefix, suffix)
         .map(|file| format!("{}/{}", path, file))
         .ok_or_else(|| AppError::MissingFile(format!("{} file not found", suffix)))?;
     read_json_file(file_path)
@@ -108,24 +196,9 @@ pub fn find_and_read_json_files(
     prefix: &str,
     suffix: &str,
 ) -> Result<Vec<String>, AppError> {
-    let file_prefix: String = format!("{}{}", prefix, suffix);
-    let mut file_paths: Vec<String> = files
-        .iter()
-        .filter(|&file| {
-            (file.starts_with(&file_prefix) && file.ends_with(".json"))
-                || (file.starts_with(&file_prefix) && !file.contains('.'))
-        })
-        .map(|file| format!("{}/{}", path, file))
-        .collect();
-    file_paths.sort_by_key(|file| {
-        file.trim_end_matches(".json")
-            .rsplit('-')
-            .next()
-            .and_then(|s| s.parse::<u32>().ok())
-            .unwrap_or(0)
-    });
-    file_paths
+    find_json_files(files, prefix, suffix)
         .into_iter()
+        .map(|file| format!("{}/{}", path, file))
         .map(read_json_file)
         .collect::<Result<Vec<String>, AppError>>()
 }
@@ -0,0 +1,337 @@
+use crate::course_utils::{content_items, find_unit_id, index_rows};
+use crate::error::AppError;
+use crate::models::{ContentItemProgress, DataStruct, Forecast, UnitForecast};
+use crate::review_operations::parse_completed_date;
+use chrono::{Duration, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Width and height of the burn-up chart, in pixels.
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 400.0;
+/// Space reserved around the plot area for the axes and labels.
+const CHART_MARGIN: f64 = 50.0;
+
+/// Fits the daily pace of the learner with a least-squares line over the cumulative number
+/// of finished items.
+///
+/// # Parameters
+///
+/// - `history`: The cumulative number of finished items at the end of each day with completions.
+/// - `today`: The date used as today. The pace is fitted up to this date.
+///
+/// # Returns
+///
+/// - `f64`: The number of items finished per day. It is zero when there is no history.
+fn fit_daily_pace(history: &[(NaiveDate, u32)], today: NaiveDate) -> f64 {
+    let Some(&(first_date, _)) = history.first() else {
+        return 0.0;
+    };
+    let completed: u32 = history.last().map(|&(_, count)| count).unwrap_or(0);
+    let mut points: Vec<(f64, f64)> = history
+        .iter()
+        .map(|&(date, count)| ((date - first_date).num_days() as f64, count as f64))
+        .collect();
+    if today > history[history.len() - 1].0 {
+        points.push(((today - first_date).num_days() as f64, completed as f64));
+    }
+
+    let n: f64 = points.len() as f64;
+    let mean_x: f64 = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y: f64 = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|&(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+
+    if variance > 0.0 && covariance > 0.0 {
+        covariance / variance
+    } else {
+        let elapsed_days: i64 = (today - first_date).num_days().max(1);
+        completed as f64 / elapsed_days as f64
+    }
+}
+
+/// Projects the date on which a number of remaining items is finished.
+fn project_date(today: NaiveDate, remaining: u32, daily_pace: f64) -> Option<NaiveDate> {
+    if remaining == 0 {
+        return Some(today);
+    }
+    if daily_pace <= 0.0 {
+        return None;
+    }
+
+    Some(today + Duration::days((remaining as f64 / daily_pace).ceil() as i64))
+}
+
+/// Forecasts when the course and each of its units will be finished.
+///
+/// The content items of the course are counted per unit, and an item is finished when its
+/// completion status is `COMPLETE`, whether or not it has a best score. The completion dates
+/// of the finished items that have one give the history of finished items, counted from zero.
+/// The pace is fitted on that history and the units are projected in course order, assuming
+/// the learner works through them one after the other.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+/// - `items_progresses`: The progress of the content items of every unit.
+/// - `today`: The date used as today.
+///
+/// # Returns
+///
+/// - `Result<Forecast, AppError>`: On success, returns the forecast of the course. On failure,
+///   returns an `AppError` indicating the type of error that occurred, such as an invalid
+///   completion date.
+pub fn forecast_course(
+    rows: &[DataStruct],
+    items_progresses: &[Vec<ContentItemProgress>],
+    today: NaiveDate,
+) -> Result<Forecast, AppError> {
+    let row_indexes: HashMap<&str, usize> = index_rows(rows);
+    let mut completion_dates: HashMap<&str, Option<NaiveDate>> = HashMap::new();
+    for item_progress in items_progresses.iter().flatten() {
+        if item_progress.completion_status != "COMPLETE" {
+            continue;
+        }
+        let completed_date: Option<NaiveDate> = item_progress
+            .best_score
+            .as_ref()
+            .and_then(|best_score| best_score.completed_date.as_deref())
+            .map(parse_completed_date)
+            .transpose()?;
+        completion_dates.insert(&item_progress.content.progress_key, completed_date);
+    }
+
+    let mut units: Vec<UnitForecast> = rows
+        .iter()
+        .filter(|row| row.type_name == "Unit")
+        .map(|row| UnitForecast {
+            id: row.id.clone(),
+            title: row.title.clone(),
+            total: 0,
+            completed: 0,
+            finish_date: None,
+        })
+        .collect();
+    let mut finished_per_day: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut completed: u32 = 0;
+    let items: Vec<&DataStruct> = content_items(rows);
+    for row in &items {
+        let completion: Option<Option<NaiveDate>> = row
+            .progress_key
+            .as_deref()
+            .and_then(|progress_key| completion_dates.get(progress_key))
+            .copied();
+        if let Some(completed_date) = completion {
+            completed += 1;
+            if let Some(completed_date) = completed_date {
+                *finished_per_day.entry(completed_date).or_insert(0) += 1;
+            }
+        }
+        let unit_id: Option<&str> = find_unit_id(rows, &row_indexes, row);
+        if let Some(unit) = units
+            .iter_mut()
+            .find(|unit| Some(unit.id.as_str()) == unit_id)
+        {
+            unit.total += 1;
+            if completion.is_some() {
+                unit.completed += 1;
+            }
+        }
+    }
+
+    let mut history: Vec<(NaiveDate, u32)> = Vec::new();
+    let mut dated: u32 = 0;
+    for (date, count) in finished_per_day {
+        dated += count;
+        history.push((date, dated));
+    }
+    let total: u32 = items.len() as u32;
+    let daily_pace: f64 = fit_daily_pace(&history, today);
+
+    let mut remaining: u32 = 0;
+    for unit in units.iter_mut() {
+        remaining += unit.total - unit.completed;
+        unit.finish_date = project_date(today, remaining, daily_pace);
+    }
+
+    Ok(Forecast {
+        today,
+        total,
+        completed,
+        weekly_pace: daily_pace * 7.0,
+        finish_date: project_date(today, total - completed, daily_pace),
+        units,
+        history,
+    })
+}
+
+/// Calculates the weekly pace needed to finish the remaining items by a target date.
+///
+/// # Parameters
+///
+/// - `forecast`: The forecast of the course.
+/// - `target`: The date on which the course should be finished.
+///
+/// # Returns
+///
+/// - `Option<f64>`: The number of items per week, or `None` if the target date is not
+///   after the date used as today.
+pub fn required_weekly_pace(forecast: &Forecast, target: NaiveDate) -> Option<f64> {
+    let days: i64 = (target - forecast.today).num_days();
+    if days <= 0 {
+        return None;
+    }
+
+    Some((forecast.total - forecast.completed) as f64 * 7.0 / days as f64)
+}
+
+/// Prints a text summary of the forecast.
+///
+/// # Parameters
+///
+/// - `forecast`: The forecast of the course.
+/// - `target`: An optional date on which the course should be finished.
+pub fn print_forecast(forecast: &Forecast, target: Option<NaiveDate>) {
+    println!(
+        "Completed {} of {} items, {:.1} items per week",
+        forecast.completed, forecast.total, forecast.weekly_pace
+    );
+    for unit in &forecast.units {
+        match unit.finish_date {
+            _ if unit.completed == unit.total => println!("{} is done", unit.title),
+            Some(finish_date) => {
+                println!("At current pace, {} done by {}", unit.title, finish_date)
+            }
+            None => println!("{} has no pace to project", unit.title),
+        }
+    }
+    match forecast.finish_date {
+        Some(finish_date) => println!("At current pace, the course is done by {}", finish_date),
+        None => println!("The course has no pace to project"),
+    }
+    if let Some(target) = target {
+        match required_weekly_pace(forecast, target) {
+            Some(pace) => println!(
+                "To finish by {}, the required pace is {:.1} items per week",
+                target, pace
+            ),
+            None => println!("The target date {} has already passed", target),
+        }
+    }
+}
+
+/// Renders the forecast as a burn-up chart in SVG format.
+///
+/// The chart shows the scope of the course, the cumulative number of finished items, the
+/// projection at the current pace and, if given, the target date. Finished items without a
+/// completion date are counted from the start of the chart.
+///
+/// # Parameters
+///
+/// - `forecast`: The forecast of the course.
+/// - `target`: An optional date on which the course should be finished.
+///
+/// # Returns
+///
+/// - `String`: The SVG document.
+pub fn render_burnup_svg(forecast: &Forecast, target: Option<NaiveDate>) -> String {
+    let start: NaiveDate = forecast
+        .history
+        .first()
+        .map(|&(date, _)| date)
+        .unwrap_or(forecast.today);
+    let end: NaiveDate = [forecast.finish_date, target, Some(forecast.today)]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(forecast.today);
+    let days: f64 = (end - start).num_days().max(1) as f64;
+    let total: f64 = forecast.total.max(1) as f64;
+    let x = |date: NaiveDate| -> f64 {
+        CHART_MARGIN + (date - start).num_days() as f64 / days * (CHART_WIDTH - 2.0 * CHART_MARGIN)
+    };
+    let y = |count: u32| -> f64 {
+        CHART_HEIGHT - CHART_MARGIN - count as f64 / total * (CHART_HEIGHT - 2.0 * CHART_MARGIN)
+    };
+
+    let mut svg: String = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="white"/>
+<line x1="{m}" y1="{b}" x2="{r}" y2="{b}" stroke="black"/>
+<line x1="{m}" y1="{m}" x2="{m}" y2="{b}" stroke="black"/>
+<text x="{m}" y="{l}" font-size="12">{start}</text>
+<text x="{r}" y="{l}" font-size="12" text-anchor="end">{end}</text>
+<text x="{t}" y="{s}" font-size="12" text-anchor="end">{total}</text>
+<text x="{t}" y="{b}" font-size="12" text-anchor="end">0</text>"#,
+        m = CHART_MARGIN,
+        b = CHART_HEIGHT - CHART_MARGIN,
+        r = CHART_WIDTH - CHART_MARGIN,
+        l = CHART_HEIGHT - CHART_MARGIN + 20.0,
+        t = CHART_MARGIN - 5.0,
+        s = y(forecast.total) + 4.0,
+        start = start,
+        end = end,
+        total = forecast.total
+    );
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{x1}" y1="{y}" x2="{x2}" y2="{y}" stroke="gray" stroke-dasharray="2,2"/>"#,
+        x1 = x(start),
+        x2 = x(end),
+        y = y(forecast.total)
+    );
+
+    // Finished items without a completion date cannot be placed, so they raise the whole line
+    let undated: u32 = forecast
+        .completed
+        .saturating_sub(forecast.history.last().map_or(0, |&(_, count)| count));
+    let mut points: Vec<String> = vec![format!("{},{}", x(start), y(undated))];
+    let mut previous: u32 = undated;
+    for &(date, count) in &forecast.history {
+        points.push(format!("{},{}", x(date), y(previous)));
+        points.push(format!("{},{}", x(date), y(undated + count)));
+        previous = undated + count;
+    }
+    points.push(format!("{},{}", x(forecast.today), y(forecast.completed)));
+    let _ = writeln!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="steelblue" stroke-width="2"/>"#,
+        points.join(" ")
+    );
+
+    if let Some(finish_date) = forecast.finish_date {
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="steelblue" stroke-dasharray="6,4"/>"#,
+            x1 = x(forecast.today),
+            y1 = y(forecast.completed),
+            x2 = x(finish_date),
+            y2 = y(forecast.total)
+        );
+    }
+    if let Some(target) = target {
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{x}" y1="{t}" x2="{x}" y2="{b}" stroke="firebrick" stroke-dasharray="4,4"/>
+<text x="{x}" y="{l}" font-size="12" fill="firebrick" text-anchor="middle">target {target}</text>"#,
+            x = x(target),
+            t = CHART_MARGIN,
+            b = CHART_HEIGHT - CHART_MARGIN,
+            l = CHART_MARGIN - 10.0,
+            target = target
+        );
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
@@ -1,16 +1,14 @@
-use crate::csv_utils::append_data_to_csv;
 use crate::error::AppError;
 use crate::extractors::{
-    extract_info, extract_item_progresses, extract_mastery_map, extract_mastery_v2,
-    extract_quiz_attempts, extract_unit_progresses, extract_unit_test_attempts,
+    extract_challenge_attempts, extract_challenge_info, extract_info, extract_item_progresses,
+    extract_mastery_map, extract_mastery_v2, extract_quiz_attempts, extract_unit_progresses,
+    extract_unit_test_attempts, CHALLENGE_FIELDS,
 };
 use crate::models::{
-    ContentItemProgress, DataStruct, MasteryMapItem, MasteryV2, TopicQuizAttempt,
+    ChallengeAttempt, ContentItemProgress, DataStruct, MasteryMapItem, MasteryV2, TopicQuizAttempt,
     TopicUnitTestAttempt, UnitProgress,
 };
-use csv::Writer;
 use serde_json::Value;
-use std::fs::File;
 
 pub type MasteryData = (
     MasteryV2,
@@ -19,60 +17,89 @@ pub type MasteryData = (
     Vec<Vec<ContentItemProgress>>,
     Vec<Vec<TopicQuizAttempt>>,
     Vec<Vec<TopicUnitTestAttempt>>,
+    Vec<Vec<ChallengeAttempt>>,
 );
 
-/// Extracts course information from a JSON value and writes it to a CSV file.
+/// Fields of a node that can hold its children, in the order in which they are followed.
+pub const CHILD_FIELDS: &[&str] = &[
+    "unitChildren",
+    "allOrderedChildren",
+    "curatedChildren",
+    "children",
+];
+
+/// Collects the course and all its descendants from a JSON value.
 ///
-/// This function navigates through the JSON structure representing a course,
-/// extracting relevant information about the course, its units, lessons, and contents.
-/// The extracted information is serialized and appended to a CSV file using the provided writer.
+/// This function walks the JSON structure representing a course recursively, following
+/// whichever child arrays each node has, and returns one `DataStruct` per node, in the same
+/// order in which they appear in the course. Nodes can be nested at any depth, so courses
+/// that do not follow the unit and lesson shape are collected as well. The course and mastery
+/// challenges of a node are collected after its other children, and skipped when they lack
+/// an id.
 ///
 /// # Parameters
 ///
 /// - `course_content`: A reference to a `Value` that contains the JSON structure
-///   of the course. This JSON value is expected to have a specific structure with nested objects
-///   representing units, lessons, and contents.
-/// - `writer`: A mutable reference to a `Writer<File>` that is used to write
-///   the serialized course information to a CSV file.
+///   of the course.
 ///
 /// # Returns
 ///
-/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
-///   indicating the type of error that occurred, such as a missing field error if the expected
-///   structure is not found.
-pub fn extract_course(course_content: &Value, writer: &mut Writer<File>) -> Result<(), AppError> {
-    let course_info: DataStruct = extract_info(course_content, None, 1)?;
-    append_data_to_csv(&course_info, writer)?;
-
-    let units: &Vec<Value> = course_content["unitChildren"]
-        .as_array()
-        .ok_or_else(|| AppError::MissingField("unitChildren".to_string()))?;
+/// - `Result<Vec<DataStruct>, AppError>`: On success, returns the rows of the course.
+///   On failure, returns an `AppError` indicating the type of error that occurred, such as
+///   a missing field error if a node lacks one of its required fields.
+pub fn collect_course(course_content: &Value) -> Result<Vec<DataStruct>, AppError> {
+    let mut rows: Vec<DataStruct> = vec![extract_info(course_content, None, 1)?];
+    collect_children(course_content, 0, &mut rows)?;
 
-    for (unit_order, unit) in units.iter().enumerate() {
-        let unit_info: DataStruct =
-            extract_info(unit, Some(&course_info), (unit_order + 1) as u32)?;
-        append_data_to_csv(&unit_info, writer)?;
-
-        let lessons: &Vec<Value> = unit["allOrderedChildren"]
-            .as_array()
-            .ok_or_else(|| AppError::MissingField("allOrderedChildren".to_string()))?;
+    Ok(rows)
+}
 
-        for (lesson_order, lesson) in lessons.iter().enumerate() {
-            let lesson_info: DataStruct =
-                extract_info(lesson, Some(&unit_info), (lesson_order + 1) as u32)?;
-            append_data_to_csv(&lesson_info, writer)?;
+/// Collects the children of a node, and their descendants, after the rows already collected.
+///
+/// # Parameters
+///
+/// - `node`: A reference to the `Value` of the node whose children are collected.
+/// - `parent_index`: The index in `rows` of the row of the node.
+/// - `rows`: A mutable reference to the rows collected so far.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred, such as a missing field error.
+fn collect_children(
+    node: &Value,
+    parent_index: usize,
+    rows: &mut Vec<DataStruct>,
+) -> Result<(), AppError> {
+    let children: Vec<&Value> = CHILD_FIELDS
+        .iter()
+        .filter_map(|field| node[*field].as_array())
+        .flatten()
+        .collect();
 
-            if lesson["__typename"] == "Lesson" {
-                let contents: &Vec<Value> = lesson["curatedChildren"]
-                    .as_array()
-                    .ok_or_else(|| AppError::MissingField("curatedChildren".to_string()))?;
+    for (order, child) in children.into_iter().enumerate() {
+        let child_info: DataStruct =
+            extract_info(child, Some(&rows[parent_index]), (order + 1) as u32)?;
+        rows.push(child_info);
+        let child_index: usize = rows.len() - 1;
+        collect_children(child, child_index, rows)?;
+    }
 
-                for (content_order, content) in contents.iter().enumerate() {
-                    let content_info: DataStruct =
-                        extract_info(content, Some(&lesson_info), (content_order + 1) as u32)?;
-                    append_data_to_csv(&content_info, writer)?;
-                }
-            }
+    let mut order: u32 = rows
+        .iter()
+        .filter(|row| row.parent_id.as_deref() == Some(&rows[parent_index].id))
+        .count() as u32;
+    // The shape of the challenges has not been checked against a real capture, so a challenge
+    // without an id is skipped rather than failing the whole course
+    for (field, type_name) in CHALLENGE_FIELDS {
+        if !node[*field].is_object() {
+            continue;
+        }
+        if let Ok(challenge_info) =
+            extract_challenge_info(&node[*field], &rows[parent_index], type_name, order + 1)
+        {
+            order += 1;
+            rows.push(challenge_info);
         }
     }
 
@@ -92,12 +119,13 @@ pub fn extract_course(course_content: &Value, writer: &mut Writer<File>) -> Resu
 /// - `json_unit_progress_files`: A slice of strings, each representing the JSON content of unit progress files.
 ///   These JSON files contain information about the progress of individual content items within units.
 /// - `json_quiz_test_progress_files`: A slice of strings, each representing the JSON content of quiz/test progress files.
-///   These JSON files contain information about quiz attempts and unit test attempts.
+///   These JSON files contain information about quiz attempts, unit test attempts, and course
+///   and mastery challenge attempts when they were captured.
 ///
 /// # Returns
 ///
 /// - `Result<MasteryData, AppError>`: On success, returns a tuple containing mastery data, mastery map,
-///   unit progress, item progresses, quiz attempts, and test attempts. On failure, returns an `AppError`
+///   unit progress, item progresses, quiz attempts, test attempts, and challenge attempts. On failure, returns an `AppError`
 ///   indicating the type of error that occurred during the extraction process.
 pub fn process_json_files(
     json_course_progress: &str,
@@ -109,16 +137,20 @@ pub fn process_json_files(
     let unit_progress: Vec<UnitProgress> = extract_unit_progresses(json_course_progress)?;
     let items_progresses: Vec<Vec<ContentItemProgress>> = json_unit_progress_files
         .iter()
-        .map(|json_content| extract_item_progresses(json_content).unwrap())
-        .collect();
+        .map(|json_content| extract_item_progresses(json_content))
+        .collect::<Result<_, _>>()?;
     let quizzes_progresses: Vec<Vec<TopicQuizAttempt>> = json_quiz_test_progress_files
         .iter()
-        .map(|json_content| extract_quiz_attempts(json_content).unwrap())
-        .collect();
+        .map(|json_content| extract_quiz_attempts(json_content))
+        .collect::<Result<_, _>>()?;
     let tests_progresses: Vec<Vec<TopicUnitTestAttempt>> = json_quiz_test_progress_files
         .iter()
-        .map(|json_content| extract_unit_test_attempts(json_content).unwrap())
-        .collect();
+        .map(|json_content| extract_unit_test_attempts(json_content))
+        .collect::<Result<_, _>>()?;
+    let challenges_progresses: Vec<Vec<ChallengeAttempt>> = json_quiz_test_progress_files
+        .iter()
+        .map(|json_content| extract_challenge_attempts(json_content))
+        .collect::<Result<_, _>>()?;
 
     Ok((
         mastery_v2,
@@ -127,5 +159,6 @@ pub fn process_json_files(
         items_progresses,
         quizzes_progresses,
         tests_progresses,
+        challenges_progresses,
     ))
 }
@@ -1,11 +1,19 @@
 use crate::error::AppError;
+use flate2::read::GzDecoder;
 use serde_json::{from_str, Value};
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Extension of the gzip-compressed capture files, added after `.json`.
+pub const GZIP_EXTENSION: &str = ".gz";
 
 /// Reads the contents of a JSON file from the specified path and returns it as a `String`.
 ///
+/// Files ending in `.gz` are decompressed. When the file does not exist but a compressed
+/// copy with the `.gz` extension does, as `list_files_in_directory` reports it without the
+/// extension, the compressed copy is read instead.
+///
 /// # Parameters
 ///
 /// - `path`: A path to the JSON file. It can be any type that implements the `AsRef<Path>` trait.
@@ -15,6 +23,15 @@ use std::path::Path;
 /// - `Result<String, AppError>`: On success, returns the contents of the file as a `String`.
 ///   On failure, returns an `AppError` indicating the type of error that occurred, such as an I/O error.
 pub fn read_json_file<P: AsRef<Path>>(path: P) -> Result<String, AppError> {
+    let path: &Path = path.as_ref();
+    let compressed: PathBuf = PathBuf::from(format!("{}{}", path.display(), GZIP_EXTENSION));
+    if !path.exists() && compressed.is_file() {
+        return read_gzip(File::open(compressed)?);
+    }
+    if path.to_string_lossy().ends_with(GZIP_EXTENSION) {
+        return read_gzip(File::open(path)?);
+    }
+
     let file: File = File::open(path).map_err(AppError::Io)?;
     let mut reader: BufReader<File> = BufReader::new(file);
     let mut contents: String = String::new();
@@ -23,6 +40,25 @@ pub fn read_json_file<P: AsRef<Path>>(path: P) -> Result<String, AppError> {
     Ok(contents)
 }
 
+/// Decompresses gzip-compressed content and returns it as a `String`.
+///
+/// # Parameters
+///
+/// - `reader`: The reader of the compressed content, such as a `.json.gz` file or an entry
+///   of an archive.
+///
+/// # Returns
+///
+/// - `Result<String, AppError>`: On success, returns the decompressed content. On failure,
+///   returns an `AppError` indicating the type of error that occurred, such as an I/O error
+///   if the content is not valid gzip.
+pub fn read_gzip<R: Read>(reader: R) -> Result<String, AppError> {
+    let mut contents: String = String::new();
+    GzDecoder::new(reader).read_to_string(&mut contents)?;
+
+    Ok(contents)
+}
+
 /// Extracts a nested value from a JSON string based on a sequence of keys.
 ///
 /// This function parses a JSON string and navigates through its structure
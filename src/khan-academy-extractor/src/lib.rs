@@ -1,3 +1,65 @@
-pub mod csv_utils;
+//! Extraction of the course content and progress of Khan Academy captures.
+//!
+//! The library API is made of the `extractor`, `models`, `error`, `sink_operations`,
+//! `source_operations`, and `tree_operations` modules. The other modules are shared with the
+//! command-line tool and the tests of the crate; they are hidden from the documentation and
+//! can change in any release.
+
 pub mod error;
+pub mod extractor;
 pub mod models;
+pub mod sink_operations;
+pub mod source_operations;
+pub mod tree_operations;
+
+#[doc(hidden)]
+pub mod annotation_operations;
+#[doc(hidden)]
+pub mod course_utils;
+#[doc(hidden)]
+pub mod csv_operations;
+#[doc(hidden)]
+pub mod csv_utils;
+#[doc(hidden)]
+pub mod diff_operations;
+#[doc(hidden)]
+pub mod extractors;
+#[doc(hidden)]
+pub mod fetch_operations;
+#[doc(hidden)]
+pub mod ffi_operations;
+#[doc(hidden)]
+pub mod file_operations;
+#[doc(hidden)]
+pub mod file_utils;
+#[doc(hidden)]
+pub mod forecast_operations;
+#[doc(hidden)]
+pub mod json_operations;
+#[doc(hidden)]
+pub mod json_utils;
+#[doc(hidden)]
+pub mod matrix_operations;
+#[doc(hidden)]
+pub mod merge_operations;
+#[doc(hidden)]
+pub mod output_utils;
+#[doc(hidden)]
+pub mod progress_operations;
+#[doc(hidden)]
+pub mod pseudonym_operations;
+#[cfg(feature = "python")]
+#[doc(hidden)]
+pub mod python_operations;
+#[doc(hidden)]
+pub mod replay_operations;
+#[doc(hidden)]
+pub mod review_operations;
+#[doc(hidden)]
+pub mod sanitize_operations;
+#[doc(hidden)]
+pub mod server_operations;
+#[doc(hidden)]
+pub mod validate_operations;
+#[doc(hidden)]
+pub mod write_utils;
@@ -1,77 +1,695 @@
 mod args;
-mod csv_operations;
-mod csv_utils;
-mod error;
-mod extractors;
-mod file_operations;
-mod file_utils;
-mod json_operations;
-mod json_utils;
-mod models;
-
-use crate::args::Args;
-use crate::csv_operations::update_csv;
-use crate::csv_utils::create_csv_file;
-use crate::error::AppError;
-use crate::extractors::extract_course_content;
-use crate::file_operations::{read_files, FileContents};
-use crate::json_operations::{extract_course, process_json_files, MasteryData};
-use clap::Parser;
+mod output_operations;
+
+use crate::args::{
+    parse_args, Args, ClientArgs, Command, ExtractArgs, FilterArgs, OutputArgs, PseudonymArgs,
+};
+use crate::output_operations::output_file_path;
+use chrono::{Local, NaiveDate};
 use csv::Writer;
+use khan_academy_extractor::annotation_operations::{
+    annotate_reviews, apply_annotations, filter_rows, load_annotations, Annotations,
+};
+use khan_academy_extractor::csv_utils::create_csv_file;
+use khan_academy_extractor::diff_operations::{
+    diff_courses, match_nodes, print_diff, write_mapping_csv,
+};
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::extractor::Extractor;
+use khan_academy_extractor::extractors::{extract_course_content, extract_item_progresses};
+use khan_academy_extractor::fetch_operations::{fetch_course, FetchClient, RecordedRequests};
+use khan_academy_extractor::file_operations::{read_files, FileContents};
+use khan_academy_extractor::forecast_operations::{
+    forecast_course, print_forecast, render_burnup_svg,
+};
+use khan_academy_extractor::json_operations::{collect_course, process_json_files, MasteryData};
+use khan_academy_extractor::json_utils::read_json_file;
+use khan_academy_extractor::matrix_operations::{build_matrix, parse_learner, write_matrix_csv};
+use khan_academy_extractor::merge_operations::merge_csv;
+use khan_academy_extractor::models::{
+    ContentItemProgress, CurriculumChange, DataStruct, EnrichedCourse, Forecast, NodeMapping,
+    ProgressMatrix, PseudonymMap, ReviewItem, ValidationReport,
+};
+use khan_academy_extractor::pseudonym_operations::{
+    load_pseudonym_map, pseudonymise, save_pseudonym_map,
+};
+use khan_academy_extractor::replay_operations::{replay, ReplayCaptures};
+use khan_academy_extractor::review_operations::{
+    parse_completed_date, print_review, schedule_reviews,
+};
+use khan_academy_extractor::sanitize_operations::sanitize_files;
+use khan_academy_extractor::server_operations::{serve, CourseStore};
+use khan_academy_extractor::sink_operations::{OutputSink, SinkRegistry};
+use khan_academy_extractor::source_operations::open_source;
+use khan_academy_extractor::validate_operations::{
+    count_problems, print_validation, validate_capture,
+};
+use khan_academy_extractor::write_utils::{lock_directory, write_file_atomically};
 use serde_json::Value;
-use std::fs::File;
+use std::fs::{create_dir_all, read_to_string, write, File};
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-/// The main function serves as the entry point for the application, orchestrating the process
-/// of reading JSON files, extracting course and progress data, and writing the results to a CSV file.
+/// The main function serves as the entry point for the application, dispatching the
+/// command-line arguments to the requested command.
 ///
 /// # Returns
 ///
 /// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
-///   indicating the type of error that occurred during the execution of the function.
+///   indicating the type of error that occurred during the execution of the command.
 fn main() -> Result<(), AppError> {
     // Parse command-line arguments
-    let args: Args = Args::parse();
+    let args: Args = parse_args();
 
-    // Read files based on the provided path and prefix
-    let file_contents: FileContents = read_files(&args.path, &args.prefix)?;
+    match &args.command {
+        None => extract(&args, &args.extract),
+        Some(Command::Extract(extract_args)) => extract(&args, extract_args),
+        Some(Command::Review {
+            annotations,
+            today,
+            days,
+        }) => review(
+            &args,
+            annotations.annotations.as_deref(),
+            today.as_deref(),
+            *days,
+        ),
+        Some(Command::Forecast {
+            today,
+            target,
+            svg,
+            output_options,
+            annotations,
+        }) => forecast(
+            &args,
+            output_options,
+            annotations.annotations.as_deref(),
+            today.as_deref(),
+            target.as_deref(),
+            svg.as_deref(),
+        ),
+        Some(Command::Matrix {
+            learners,
+            output,
+            pseudonyms,
+            output_options,
+            annotations,
+            filter,
+        }) => matrix(
+            &args,
+            learners,
+            output.as_deref(),
+            pseudonyms,
+            output_options,
+            annotations.annotations.as_deref(),
+            filter,
+        ),
+        Some(Command::Sanitize { output }) => sanitize(&args, output),
+        Some(Command::Validate) => validate(&args),
+        Some(Command::Diff {
+            old,
+            new,
+            mapping,
+            backups,
+        }) => diff(old, new, mapping.as_deref(), *backups),
+        Some(Command::Serve {
+            courses,
+            address,
+            annotations,
+        }) => serve_courses(&args, courses, address, annotations.annotations.as_deref()),
+        Some(Command::Replay { address }) => replay_captures(&args, address),
+        Some(Command::Fetch {
+            course,
+            requests,
+            client,
+            output_dir,
+        }) => fetch(
+            &args,
+            course,
+            requests.as_deref(),
+            client,
+            output_dir.as_deref(),
+        ),
+    }
+}
+
+/// Orchestrates the process of reading JSON files, extracting course and progress data,
+/// and writing the results and the attempt history in the output directory, or the results
+/// alone to the standard output. The annotations are merged into the results, which can be
+/// filtered by tag and skip flag. In merge mode, the results are merged into the previous
+/// output, keeping the columns added to it; the filters are rejected in this mode, since the
+/// rows they leave out would be flagged as removed.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `options`: The output, annotation, and filter options of the extraction.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the execution of the function.
+fn extract(args: &Args, options: &ExtractArgs) -> Result<(), AppError> {
+    // Read the files based on the provided path and prefix, and extract the course data
+    // with its progress
+    let mut course: EnrichedCourse = Extractor::from_dir(&args.path, &args.prefix)?.extract()?;
+    for unmapped_attempt in &course.unmapped_attempts {
+        eprintln!("Could not map {}", unmapped_attempt);
+    }
+    annotate_rows(options.annotations.annotations.as_deref(), &mut course.rows)?;
 
-    // Define the output CSV file path
-    let output_csv_file: String = format!("{}/{}information.csv", args.path, args.prefix);
+    let course_slug: String = course
+        .rows
+        .first()
+        .map_or(String::new(), |row| row.slug.clone());
+    let rows: Vec<DataStruct> = filter_rows(
+        course.rows,
+        options.filter.tag.as_deref(),
+        options.filter.hide_skipped,
+    );
+    let sinks: SinkRegistry = SinkRegistry::new();
+    let sink: &dyn OutputSink = sinks.get(&options.format)?;
+    let extension: &str = sink.extension();
+    let output_file: PathBuf = output_file_path(
+        args,
+        &options.output,
+        &course_slug,
+        "information",
+        extension,
+    )?;
+    let _lock: Option<File> = if options.stdout {
+        None
+    } else {
+        Some(lock_output(&output_file)?)
+    };
 
-    // Extract course content from JSON
-    let course_content: Value = extract_course_content(&file_contents.json_content)?;
+    // Write the course data, merged into the previous output when asked
+    if options.merge {
+        if options.format != "csv" {
+            return Err(AppError::InvalidArgument(
+                "the merge mode needs the csv format".to_string(),
+            ));
+        }
+        let previous_file: PathBuf = options
+            .previous
+            .as_ref()
+            .map_or_else(|| output_file.clone(), PathBuf::from);
+        let mut extracted: Vec<u8> = Vec::new();
+        sink.write_rows(&rows, &mut extracted)?;
+        let merged: Vec<u8> = merge_course(&previous_file, &extracted)?;
+        if options.stdout {
+            stdout().lock().write_all(&merged)?;
+        } else {
+            write_file_atomically(&output_file, options.output.backups, |temporary| {
+                Ok(write(temporary, merged)?)
+            })?;
+        }
+    } else if options.stdout {
+        sink.write_rows(&rows, &mut stdout().lock())?;
+    } else {
+        write_file_atomically(&output_file, options.output.backups, |temporary| {
+            sink.write_rows(&rows, &mut File::create(temporary)?)
+        })?;
+    }
+
+    // Write the attempt history next to the course data
+    if !options.stdout {
+        let attempts_file: PathBuf =
+            output_file_path(args, &options.output, &course_slug, "attempts", extension)?;
+        write_file_atomically(&attempts_file, options.output.backups, |temporary| {
+            sink.write_attempts(&course.attempts, &mut File::create(temporary)?)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Merges the extracted course data into a previous output, if it exists, and reports the
+/// rows flagged as removed.
+///
+/// # Parameters
+///
+/// - `previous_file`: The path of the previous output.
+/// - `extracted`: The extracted course data in CSV format.
+///
+/// # Returns
+///
+/// - `Result<Vec<u8>, AppError>`: On success, returns the merged course data in CSV format,
+///   or the extracted one when there is no previous output. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the merge.
+fn merge_course(previous_file: &Path, extracted: &[u8]) -> Result<Vec<u8>, AppError> {
+    if !previous_file.exists() {
+        return Ok(extracted.to_vec());
+    }
+
+    let mut merged: Vec<u8> = Vec::new();
+    let removed: usize = merge_csv(File::open(previous_file)?, extracted, &mut merged)?;
+    if removed > 0 {
+        eprintln!(
+            "{} rows of {} no longer exist in the course and are flagged as removed",
+            removed,
+            previous_file.display()
+        );
+    }
+
+    Ok(merged)
+}
+
+/// Lists the finished exercises and assessments that are due for review, using the history
+/// of their scores found in the JSON files. Skipped content is left out and annotated due
+/// dates replace the scheduled ones.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `annotations`: The path of the annotation file, if any.
+/// - `today`: The date used as today in YYYY-MM-DD format, or `None` to use the current date.
+/// - `days`: The number of days after today to include in the upcoming reviews.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the execution of the function.
+fn review(
+    args: &Args,
+    annotations: Option<&str>,
+    today: Option<&str>,
+    days: i64,
+) -> Result<(), AppError> {
+    let today: NaiveDate = parse_today(today)?;
+
+    let extractor: Extractor = Extractor::from_dir(&args.path, &args.prefix)?;
+    let mut course: EnrichedCourse = extractor.extract()?;
+    annotate_rows(annotations, &mut course.rows)?;
+    let items_progresses: Vec<Vec<ContentItemProgress>> = extractor
+        .file_contents()
+        .json_unit_progress_files
+        .iter()
+        .map(|json_content| extract_item_progresses(json_content))
+        .collect::<Result<_, _>>()?;
+
+    let review_items: Vec<ReviewItem> = annotate_reviews(
+        schedule_reviews(&course.rows, &items_progresses, &course.attempts)?,
+        &course.rows,
+    );
+    print_review(&review_items, today, days);
+
+    Ok(())
+}
 
-    // Create a CSV writer
-    let mut writer: Writer<File> = create_csv_file(&output_csv_file)?;
-
-    // Extract course data and write to CSV
-    extract_course(&course_content, &mut writer)?;
-    writer.flush()?;
-
-    // Process JSON files to extract mastery data
-    let (
-        mastery_v2,
-        mastery_map,
-        unit_progress,
-        items_progresses,
-        quizzes_progresses,
-        tests_progresses,
-    ): MasteryData = process_json_files(
+/// Forecasts the finish date of the course and each of its units from the completion dates
+/// found in the JSON files, prints a summary, and writes a burn-up chart in SVG format.
+/// Skipped content is left out of the scope of the course.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `output_options`: The output options of the command.
+/// - `annotations`: The path of the annotation file, if any.
+/// - `today`: The date used as today in YYYY-MM-DD format, or `None` to use the current date.
+/// - `target`: An optional date in YYYY-MM-DD format on which the course should be finished.
+/// - `svg`: The path of the SVG file, or `None` to write it in the output directory.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the execution of the function.
+fn forecast(
+    args: &Args,
+    output_options: &OutputArgs,
+    annotations: Option<&str>,
+    today: Option<&str>,
+    target: Option<&str>,
+    svg: Option<&str>,
+) -> Result<(), AppError> {
+    let today: NaiveDate = parse_today(today)?;
+    let target: Option<NaiveDate> = target.map(parse_completed_date).transpose()?;
+
+    let file_contents: FileContents = read_files(&args.path, &args.prefix)?;
+    let course_content: Value = extract_course_content(&file_contents.json_content)?;
+    let mut rows: Vec<DataStruct> = collect_course(&course_content)?;
+    annotate_rows(annotations, &mut rows)?;
+    let rows: Vec<DataStruct> = filter_rows(rows, None, true);
+    let output_svg_file: PathBuf = match svg {
+        Some(svg) => PathBuf::from(svg),
+        None => output_file_path(
+            args,
+            output_options,
+            rows.first().map_or("", |row| row.slug.as_str()),
+            "burnup",
+            "svg",
+        )?,
+    };
+    let _lock: File = lock_output(&output_svg_file)?;
+    let (_, _, _, items_progresses, _, _, _): MasteryData = process_json_files(
         &file_contents.json_course_progress,
         &file_contents.json_unit_progress_files,
         &file_contents.json_quiz_test_progress_files,
     )?;
 
-    // Update the CSV file with the extracted mastery data
-    update_csv(
-        output_csv_file,
-        mastery_v2,
-        mastery_map,
-        unit_progress,
-        items_progresses,
-        quizzes_progresses,
-        tests_progresses,
-    )?;
+    let forecast: Forecast = forecast_course(&rows, &items_progresses, today)?;
+    print_forecast(&forecast, target);
+    write_file_atomically(&output_svg_file, output_options.backups, |temporary| {
+        Ok(write(temporary, render_burnup_svg(&forecast, target))?)
+    })?;
+
+    Ok(())
+}
+
+/// Merges an annotation file, if any, into the rows of a course, and reports the keys that
+/// do not match any row.
+///
+/// # Parameters
+///
+/// - `annotations`: The path of the annotation file, if any.
+/// - `rows`: A mutable slice of `DataStruct` with the rows of the course.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred while reading or applying the annotations.
+fn annotate_rows(annotations: Option<&str>, rows: &mut [DataStruct]) -> Result<(), AppError> {
+    let Some(path) = annotations else {
+        return Ok(());
+    };
+
+    let annotations: Annotations = load_annotations(path)?;
+    for key in apply_annotations(rows, &annotations)? {
+        eprintln!("No content matches the annotation {} of {}", key, path);
+    }
+
+    Ok(())
+}
+
+/// Locks the directory of an output file for the rest of the run.
+///
+/// # Parameters
+///
+/// - `output_file`: The path of the output file.
+///
+/// # Returns
+///
+/// - `Result<File, AppError>`: On success, returns the lock file, which holds the lock until
+///   it is dropped. On failure, returns an `AppError::Locked` if another run holds the lock.
+fn lock_output(output_file: &Path) -> Result<File, AppError> {
+    match output_file.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => lock_directory(directory),
+        _ => lock_directory("."),
+    }
+}
+
+/// Parses the date used as today, falling back to the current local date.
+///
+/// # Parameters
+///
+/// - `today`: The date in YYYY-MM-DD format, or `None` to use the current date.
+///
+/// # Returns
+///
+/// - `Result<NaiveDate, AppError>`: On success, returns the date. On failure, returns an
+///   `AppError::InvalidDate` with the offending value.
+fn parse_today(today: Option<&str>) -> Result<NaiveDate, AppError> {
+    match today {
+        Some(today) => NaiveDate::parse_from_str(today, "%Y-%m-%d")
+            .map_err(|_| AppError::InvalidDate(today.to_string())),
+        None => Ok(Local::now().date_naive()),
+    }
+}
+
+/// Runs the extraction on the capture directory of every learner and writes a matrix of
+/// course content by learner, with the completion rate of each item across the group. The
+/// annotations are merged into the rows of every learner, which are filtered by tag and skip
+/// flag.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the prefix of the JSON files.
+/// - `learners`: The learners in `label=directory` form.
+/// - `output`: The path of the CSV file, or `None` to write it in the output directory.
+/// - `pseudonyms`: The options of the replacement of the learner labels with stable aliases.
+/// - `output_options`: The output options of the command.
+/// - `annotations`: The path of the annotation file, if any.
+/// - `filter`: The filters of the rows on their annotations.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the execution of the function.
+fn matrix(
+    args: &Args,
+    learners: &[String],
+    output: Option<&str>,
+    pseudonyms: &PseudonymArgs,
+    output_options: &OutputArgs,
+    annotations: Option<&str>,
+    filter: &FilterArgs,
+) -> Result<(), AppError> {
+    let mut pseudonym_map: Option<PseudonymMap> = if pseudonyms.pseudonymise {
+        Some(load_pseudonym_map(
+            &pseudonyms.pseudonym_map,
+            pseudonyms.pseudonym_salt.as_deref(),
+        )?)
+    } else {
+        None
+    };
+
+    let mut learner_rows: Vec<(String, Vec<DataStruct>)> = Vec::new();
+    for learner in learners {
+        let (mut label, directory): (String, String) = parse_learner(learner);
+        if let Some(pseudonym_map) = pseudonym_map.as_mut() {
+            label = pseudonymise(pseudonym_map, &label);
+        }
+        let mut course: EnrichedCourse =
+            Extractor::from_dir(&directory, &args.prefix)?.extract()?;
+        annotate_rows(annotations, &mut course.rows)?;
+        let rows: Vec<DataStruct> =
+            filter_rows(course.rows, filter.tag.as_deref(), filter.hide_skipped);
+        learner_rows.push((label, rows));
+    }
+    if let Some(pseudonym_map) = pseudonym_map.as_ref() {
+        save_pseudonym_map(&pseudonyms.pseudonym_map, pseudonym_map)?;
+    }
+
+    let course_slug: &str = learner_rows
+        .first()
+        .and_then(|(_, rows)| rows.first())
+        .map_or("", |row| row.slug.as_str());
+    let output_csv_file: PathBuf = match output {
+        Some(output) => PathBuf::from(output),
+        None => output_file_path(args, output_options, course_slug, "matrix", "csv")?,
+    };
+
+    let _lock: File = lock_output(&output_csv_file)?;
+
+    let matrix: ProgressMatrix = build_matrix(&learner_rows);
+    write_file_atomically(&output_csv_file, output_options.backups, |temporary| {
+        let mut writer: Writer<File> = create_csv_file(temporary)?;
+        write_matrix_csv(&matrix, &mut writer)
+    })?;
+
+    Ok(())
+}
+
+/// Writes a redacted copy of the capture files to another directory, so they can be shared
+/// without the personal data of the account.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `output`: The directory where the sanitized files are written.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the execution of the function.
+fn sanitize(args: &Args, output: &str) -> Result<(), AppError> {
+    create_dir_all(output)?;
+    let _lock: File = lock_directory(output)?;
+
+    for file_name in sanitize_files(&args.path, &args.prefix, output)? {
+        println!("{}/{}", output, file_name);
+    }
+
+    Ok(())
+}
+
+/// Checks that the capture files are complete and lists the unit pages that still need to
+/// be captured.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred, or `AppError::IncompleteCapture` when
+///   problems are found in the capture files.
+fn validate(args: &Args) -> Result<(), AppError> {
+    let report: ValidationReport =
+        validate_capture(open_source(&args.path)?.as_ref(), &args.prefix)?;
+    print_validation(&report);
+
+    match count_problems(&report) {
+        0 => Ok(()),
+        problems => Err(AppError::IncompleteCapture(format!(
+            "{} problems found",
+            problems
+        ))),
+    }
+}
+
+/// Compares two `contentForPath` captures, prints the changes of the curriculum, and
+/// optionally writes the mapping of the old nodes to the new ones.
+///
+/// # Parameters
+///
+/// - `old`: The path of the old `contentForPath` capture.
+/// - `new`: The path of the new `contentForPath` capture.
+/// - `mapping`: An optional path of the CSV file with the mapping of the nodes.
+/// - `backups`: The number of previous versions of the mapping to keep.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred during the execution of the function.
+fn diff(old: &str, new: &str, mapping: Option<&str>, backups: usize) -> Result<(), AppError> {
+    let old_rows: Vec<DataStruct> =
+        collect_course(&extract_course_content(&read_json_file(old)?)?)?;
+    let new_rows: Vec<DataStruct> =
+        collect_course(&extract_course_content(&read_json_file(new)?)?)?;
+
+    let mappings: Vec<NodeMapping> = match_nodes(&old_rows, &new_rows);
+    let changes: Vec<CurriculumChange> = diff_courses(&old_rows, &new_rows, &mappings);
+    print_diff(&changes);
+
+    if let Some(mapping) = mapping {
+        let _lock: File = lock_output(Path::new(mapping))?;
+        write_file_atomically(mapping, backups, |temporary| {
+            let mut writer: Writer<File> = create_csv_file(temporary)?;
+            write_mapping_csv(&mappings, &mut writer)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Serves the courses as a local JSON API until the process is stopped. Each course is given
+/// in the `name=path` form; without any, the captures of the path are served as `course`.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `courses`: The courses to serve, in `name=path` form.
+/// - `address`: The address the server listens on.
+/// - `annotations`: The path of the annotation file, if any.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On failure, returns an `AppError` indicating the type of error
+///   that occurred, such as an address the server cannot listen on.
+fn serve_courses(
+    args: &Args,
+    courses: &[String],
+    address: &str,
+    annotations: Option<&str>,
+) -> Result<(), AppError> {
+    let sources: Vec<(String, String)> = if courses.is_empty() {
+        vec![("course".to_string(), args.path.clone())]
+    } else {
+        courses.iter().map(|course| parse_learner(course)).collect()
+    };
+
+    serve(
+        address,
+        CourseStore::new(sources, &args.prefix, annotations.map(String::from)),
+    )
+}
+
+/// Answers GraphQL requests with the captured responses of the path until the process is
+/// stopped.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `address`: The address the server listens on.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On failure, returns an `AppError` indicating the type of error
+///   that occurred, such as an invalid capture file or an address the server cannot listen on.
+fn replay_captures(args: &Args, address: &str) -> Result<(), AppError> {
+    let captures: ReplayCaptures =
+        ReplayCaptures::load(open_source(&args.path)?.as_ref(), &args.prefix)?;
+
+    replay(address, &captures)
+}
+
+/// Reads the `Cookie` header of a logged-in session.
+///
+/// # Parameters
+///
+/// - `cookie`: The `Cookie` header, if given on the command line.
+/// - `cookie_file`: The path of a file holding the `Cookie` header, if given instead.
+///
+/// # Returns
+///
+/// - `Result<String, AppError>`: On success, returns the `Cookie` header, empty if neither is
+///   given. On failure, returns an `AppError::Io` if the cookie file cannot be read.
+fn read_cookie(cookie: Option<&str>, cookie_file: Option<&str>) -> Result<String, AppError> {
+    Ok(match (cookie, cookie_file) {
+        (Some(cookie), _) => cookie.to_string(),
+        (None, Some(cookie_file)) => read_to_string(cookie_file)?,
+        (None, None) => String::new(),
+    })
+}
+
+/// Captures the data of a course through the GraphQL API into the output directory, or the
+/// directory of the path, by sending again the requests recorded in a HAR file, or the
+/// built-in requests without one.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `course`: The path of the course, or its full URL.
+/// - `requests`: The path of the HAR file with the requests recorded by the browser, or `None`
+///   to send the built-in requests.
+/// - `client`: The session and pacing options of the requests.
+/// - `output_dir`: The directory where the capture files are written, or `None` to write them
+///   in the directory of the path.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On failure, returns an `AppError` indicating the type of error
+///   that occurred, such as an HTTP error or an operation missing from the HAR file.
+fn fetch(
+    args: &Args,
+    course: &str,
+    requests: Option<&str>,
+    client: &ClientArgs,
+    output_dir: Option<&str>,
+) -> Result<(), AppError> {
+    let requests: Option<RecordedRequests> = requests
+        .map(|requests| RecordedRequests::from_har(&read_to_string(requests)?))
+        .transpose()?;
+    let directory: &str = output_dir.unwrap_or(&args.path);
+    create_dir_all(directory)?;
+    let _lock: File = lock_directory(directory)?;
+    let cookie: String = read_cookie(client.cookie.as_deref(), client.cookie_file.as_deref())?;
+    let mut fetch_client: FetchClient = FetchClient::new(&client.base_url, &cookie)
+        .with_delay(Duration::from_millis(client.delay_ms))
+        .with_retries(client.retries);
+
+    for file_name in fetch_course(
+        &mut fetch_client,
+        requests.as_ref(),
+        course,
+        &args.prefix,
+        Path::new(directory),
+    )? {
+        println!("{}/{}", directory, file_name);
+    }
 
     Ok(())
 }
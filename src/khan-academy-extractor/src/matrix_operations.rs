@@ -0,0 +1,142 @@
+use crate::error::AppError;
+use crate::models::{DataStruct, MatrixRow, ProgressMatrix};
+use csv::Writer;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Parses a learner argument in the `label=directory` form.
+///
+/// When no label is given, the last component of the directory is used as the label.
+///
+/// # Parameters
+///
+/// - `value`: A string slice with the learner argument.
+///
+/// # Returns
+///
+/// - `(String, String)`: The label and the directory of the learner.
+pub fn parse_learner(value: &str) -> (String, String) {
+    match value.split_once('=') {
+        Some((label, directory)) => (label.to_string(), directory.to_string()),
+        None => {
+            let label: &str = value
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or(value);
+            (label.to_string(), value.to_string())
+        }
+    }
+}
+
+/// Summarises the progress of a row in a single cell of the matrix.
+///
+/// Courses and units show their mastery percentage, and the rest of the content shows its
+/// mastery status or, when there is none, its completion status.
+fn progress_cell(row: &DataStruct) -> String {
+    row.percentage
+        .as_ref()
+        .map(|percentage| format!("{}%", percentage))
+        .or_else(|| row.status.clone())
+        .or_else(|| row.completion_status.clone())
+        .unwrap_or_default()
+}
+
+/// Builds a matrix with the course content as rows and the learners as columns.
+///
+/// The rows of the first learner give the structure of the matrix, and the rows of the other
+/// learners are matched by `id`. Each row has the share of the learners having it that
+/// completed it, counted only for the rows that have a completion status, so a learner whose
+/// captures miss a row does not lower its rate.
+///
+/// # Parameters
+///
+/// - `learners`: A slice of tuples with the label of each learner and the rows of the course
+///   with their progress, as returned by `build_course`.
+///
+/// # Returns
+///
+/// - `ProgressMatrix`: The matrix of the course content by learner.
+pub fn build_matrix(learners: &[(String, Vec<DataStruct>)]) -> ProgressMatrix {
+    let rows_by_id: Vec<HashMap<&str, &DataStruct>> = learners
+        .iter()
+        .map(|(_, rows)| rows.iter().map(|row| (row.id.as_str(), row)).collect())
+        .collect();
+    let structure: &[DataStruct] = learners
+        .first()
+        .map(|(_, rows)| rows.as_slice())
+        .unwrap_or_default();
+
+    let rows: Vec<MatrixRow> = structure
+        .iter()
+        .map(|row| {
+            let learner_rows: Vec<Option<&&DataStruct>> = rows_by_id
+                .iter()
+                .map(|rows| rows.get(row.id.as_str()))
+                .collect();
+            let with_row: usize = learner_rows.iter().flatten().count();
+            let with_status: Vec<&&DataStruct> = learner_rows
+                .iter()
+                .flatten()
+                .filter(|row| row.completion_status.is_some())
+                .copied()
+                .collect();
+            let completed: usize = with_status
+                .iter()
+                .filter(|row| row.completion_status.as_deref() == Some("COMPLETE"))
+                .count();
+
+            MatrixRow {
+                id: row.id.clone(),
+                type_name: row.type_name.clone(),
+                title: row.title.clone(),
+                cells: learner_rows
+                    .iter()
+                    .map(|row| row.map(|row| progress_cell(row)).unwrap_or_default())
+                    .collect(),
+                completion_rate: (!with_status.is_empty())
+                    .then(|| (completed * 100 / with_row) as u32),
+            }
+        })
+        .collect();
+
+    ProgressMatrix {
+        learners: learners.iter().map(|(label, _)| label.clone()).collect(),
+        rows,
+    }
+}
+
+/// Writes a progress matrix in CSV format.
+///
+/// # Parameters
+///
+/// - `matrix`: A reference to the `ProgressMatrix` to be written.
+/// - `writer`: A mutable reference to the CSV `Writer` used to write the matrix.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred, such as an I/O error or CSV serialization error.
+pub fn write_matrix_csv<W: Write>(
+    matrix: &ProgressMatrix,
+    writer: &mut Writer<W>,
+) -> Result<(), AppError> {
+    let mut header: Vec<&str> = vec!["id", "typeName", "title"];
+    header.extend(matrix.learners.iter().map(|label| label.as_str()));
+    header.push("completionRate");
+    writer.write_record(&header)?;
+
+    for row in &matrix.rows {
+        let completion_rate: String = row
+            .completion_rate
+            .map(|rate| rate.to_string())
+            .unwrap_or_default();
+        let mut record: Vec<&str> = vec![&row.id, &row.type_name, &row.title];
+        record.extend(row.cells.iter().map(|cell| cell.as_str()));
+        record.push(&completion_rate);
+        writer.write_record(&record)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
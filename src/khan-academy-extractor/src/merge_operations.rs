@@ -0,0 +1,116 @@
+use crate::error::AppError;
+use csv::{Reader, StringRecord, Writer};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+
+/// Column added by the merge to flag the rows of items that no longer exist in the course.
+pub const REMOVED_COLUMN: &str = "removed";
+
+/// Merges freshly extracted course rows with a previous output, keeping the columns added by
+/// the user.
+///
+/// The rows are matched by their `id` column. Every column of the previous output that is not
+/// generated by the extraction, such as notes or target dates, is kept after the generated
+/// columns with its values. The rows of the previous output whose id is no longer in the course
+/// are kept at the end with their previous values and `true` in the `removed` column.
+///
+/// # Parameters
+///
+/// - `previous`: The previous output in CSV format, with the user columns.
+/// - `current`: The freshly extracted rows in CSV format.
+/// - `output`: The destination of the merged rows in CSV format.
+///
+/// # Returns
+///
+/// - `Result<usize, AppError>`: On success, returns the number of rows flagged as removed.
+///   On failure, returns an `AppError` indicating the type of error that occurred, such as a
+///   CSV error or a missing `id` column.
+pub fn merge_csv<P: Read, C: Read, W: Write>(
+    previous: P,
+    current: C,
+    output: W,
+) -> Result<usize, AppError> {
+    let mut previous_reader: Reader<P> = Reader::from_reader(previous);
+    let previous_headers: StringRecord = previous_reader.headers()?.clone();
+    let previous_records: Vec<StringRecord> =
+        previous_reader.records().collect::<Result<_, _>>()?;
+    let mut current_reader: Reader<C> = Reader::from_reader(current);
+    let current_headers: StringRecord = current_reader.headers()?.clone();
+    let current_records: Vec<StringRecord> = current_reader.records().collect::<Result<_, _>>()?;
+
+    let previous_columns: HashMap<&str, usize> = column_indexes(&previous_headers);
+    let previous_id: usize = *previous_columns
+        .get("id")
+        .ok_or_else(|| AppError::MissingField("id".to_string()))?;
+    let current_id: usize = *column_indexes(&current_headers)
+        .get("id")
+        .ok_or_else(|| AppError::MissingField("id".to_string()))?;
+    let user_columns: Vec<usize> = previous_headers
+        .iter()
+        .enumerate()
+        .filter(|(_, header)| *header != REMOVED_COLUMN)
+        .filter(|(_, header)| !current_headers.iter().any(|current| current == *header))
+        .map(|(index, _)| index)
+        .collect();
+    let previous_rows: HashMap<&str, &StringRecord> = previous_records
+        .iter()
+        .map(|record| (record.get(previous_id).unwrap_or_default(), record))
+        .collect();
+
+    let mut writer: Writer<W> = Writer::from_writer(output);
+    let mut headers: Vec<&str> = current_headers.iter().collect();
+    headers.push(REMOVED_COLUMN);
+    headers.extend(user_columns.iter().map(|index| &previous_headers[*index]));
+    writer.write_record(&headers)?;
+
+    let mut current_ids: HashSet<&str> = HashSet::new();
+    for record in &current_records {
+        let id: &str = record.get(current_id).unwrap_or_default();
+        current_ids.insert(id);
+        let previous_row: Option<&&StringRecord> = previous_rows.get(id);
+        let mut fields: Vec<&str> = record.iter().collect();
+        fields.push("");
+        fields.extend(user_columns.iter().map(|index| {
+            previous_row
+                .and_then(|row| row.get(*index))
+                .unwrap_or_default()
+        }));
+        writer.write_record(&fields)?;
+    }
+
+    let mut removed: usize = 0;
+    for record in &previous_records {
+        if current_ids.contains(record.get(previous_id).unwrap_or_default()) {
+            continue;
+        }
+        removed += 1;
+        let mut fields: Vec<&str> = current_headers
+            .iter()
+            .map(|header| {
+                previous_columns
+                    .get(header)
+                    .and_then(|index| record.get(*index))
+                    .unwrap_or_default()
+            })
+            .collect();
+        fields.push("true");
+        fields.extend(
+            user_columns
+                .iter()
+                .map(|index| record.get(*index).unwrap_or_default()),
+        );
+        writer.write_record(&fields)?;
+    }
+    writer.flush()?;
+
+    Ok(removed)
+}
+
+/// Maps the name of every column to its index.
+fn column_indexes(headers: &StringRecord) -> HashMap<&str, usize> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| (header, index))
+        .collect()
+}
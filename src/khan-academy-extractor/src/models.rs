@@ -1,6 +1,8 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DataStruct {
     pub id: String,
     #[serde(rename = "typeName")]
@@ -36,6 +38,24 @@ pub struct DataStruct {
     pub num_correct: Option<String>,
     #[serde(rename = "numIncorrect")]
     pub num_incorrect: Option<String>,
+    pub depth: u32,
+    pub path: String,
+    #[serde(rename = "bestScore")]
+    pub best_score: Option<String>,
+    #[serde(rename = "latestScore")]
+    pub latest_score: Option<String>,
+    #[serde(rename = "firstScore")]
+    pub first_score: Option<String>,
+    #[serde(rename = "annotationTags")]
+    pub tags: Option<String>,
+    #[serde(rename = "annotationNotes")]
+    pub notes: Option<String>,
+    #[serde(rename = "annotationPriority")]
+    pub priority: Option<u32>,
+    #[serde(rename = "annotationSkip")]
+    pub skip: Option<bool>,
+    #[serde(rename = "annotationDue")]
+    pub due: Option<NaiveDate>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -102,8 +122,12 @@ pub struct TopicQuizAttempt {
     pub num_correct: u32,
     #[serde(rename = "positionKey")]
     pub position_key: String,
+    #[serde(rename = "completedDate", default)]
+    pub completed_date: Option<String>,
     #[serde(skip)]
     pub parent_id: String,
+    #[serde(skip)]
+    pub position: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -117,6 +141,215 @@ pub struct TopicUnitTestAttempt {
     pub num_attempted: u32,
     #[serde(rename = "numCorrect")]
     pub num_correct: u32,
+    #[serde(rename = "completedDate", default)]
+    pub completed_date: Option<String>,
     #[serde(skip)]
     pub parent_id: String,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeAttempt {
+    #[serde(rename = "__typename")]
+    pub type_name: String,
+    pub id: String,
+    #[serde(rename = "isCompleted")]
+    pub is_completed: bool,
+    #[serde(rename = "numAttempted")]
+    pub num_attempted: u32,
+    #[serde(rename = "numCorrect")]
+    pub num_correct: u32,
+    #[serde(rename = "completedDate", default)]
+    pub completed_date: Option<String>,
+    #[serde(skip)]
+    pub challenge_type: String,
+    #[serde(skip)]
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "parentTitle")]
+    pub parent_title: Option<String>,
+    #[serde(rename = "relativeUrl")]
+    pub relative_url: String,
+    pub status: Option<String>,
+    pub accuracy: Option<u32>,
+    #[serde(rename = "reviewBox")]
+    pub review_box: u32,
+    #[serde(rename = "completedDate")]
+    pub completed_date: NaiveDate,
+    #[serde(rename = "dueDate")]
+    pub due_date: NaiveDate,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnitForecast {
+    pub id: String,
+    pub title: String,
+    pub total: u32,
+    pub completed: u32,
+    #[serde(rename = "finishDate")]
+    pub finish_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Forecast {
+    pub today: NaiveDate,
+    pub total: u32,
+    pub completed: u32,
+    #[serde(rename = "weeklyPace")]
+    pub weekly_pace: f64,
+    #[serde(rename = "finishDate")]
+    pub finish_date: Option<NaiveDate>,
+    pub units: Vec<UnitForecast>,
+    pub history: Vec<(NaiveDate, u32)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatrixRow {
+    pub id: String,
+    #[serde(rename = "typeName")]
+    pub type_name: String,
+    pub title: String,
+    pub cells: Vec<String>,
+    #[serde(rename = "completionRate")]
+    pub completion_rate: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgressMatrix {
+    pub learners: Vec<String>,
+    pub rows: Vec<MatrixRow>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PseudonymMap {
+    pub salt: String,
+    pub aliases: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnitCoverage {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "relativeUrl")]
+    pub relative_url: String,
+    pub covered: u32,
+    pub total: u32,
+    #[serde(rename = "captureNumber")]
+    pub capture_number: Option<u32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ValidationReport {
+    #[serde(rename = "missingFiles")]
+    pub missing_files: Vec<String>,
+    #[serde(rename = "sequenceGaps")]
+    pub sequence_gaps: Vec<String>,
+    #[serde(rename = "incompleteUnits")]
+    pub incomplete_units: Vec<UnitCoverage>,
+    #[serde(rename = "missingAttempts")]
+    pub missing_attempts: Vec<String>,
+    pub unattempted: Vec<String>,
+    #[serde(rename = "pagesToCapture")]
+    pub pages_to_capture: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurriculumChange {
+    pub change: String,
+    #[serde(rename = "typeName")]
+    pub type_name: String,
+    pub title: String,
+    #[serde(rename = "oldId")]
+    pub old_id: Option<String>,
+    #[serde(rename = "newId")]
+    pub new_id: Option<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeMapping {
+    #[serde(rename = "oldId")]
+    pub old_id: String,
+    #[serde(rename = "newId")]
+    pub new_id: String,
+    #[serde(rename = "typeName")]
+    pub type_name: String,
+    #[serde(rename = "oldTitle")]
+    pub old_title: String,
+    #[serde(rename = "newTitle")]
+    pub new_title: String,
+    #[serde(rename = "matchedBy")]
+    pub matched_by: String,
+    #[serde(skip)]
+    pub old_index: usize,
+    #[serde(skip)]
+    pub new_index: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    #[serde(rename = "contentId")]
+    pub content_id: String,
+    #[serde(rename = "typeName")]
+    pub type_name: String,
+    pub title: String,
+    #[serde(rename = "attemptNumber")]
+    pub attempt_number: u32,
+    #[serde(rename = "isCompleted")]
+    pub is_completed: bool,
+    #[serde(rename = "numAttempted")]
+    pub num_attempted: u32,
+    #[serde(rename = "numCorrect")]
+    pub num_correct: u32,
+    pub score: u32,
+    #[serde(rename = "completedDate")]
+    pub completed_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnrichedCourse {
+    pub rows: Vec<DataStruct>,
+    pub attempts: Vec<AttemptRecord>,
+    #[serde(rename = "unmappedAttempts")]
+    pub unmapped_attempts: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub priority: Option<u32>,
+    #[serde(default)]
+    pub skip: bool,
+    pub due: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnitSummary {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    #[serde(rename = "relativeUrl")]
+    pub relative_url: String,
+    pub percentage: Option<String>,
+    #[serde(rename = "pointsEarned")]
+    pub points_earned: Option<String>,
+    pub total: u32,
+    pub completed: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CourseSummary {
+    pub name: String,
+    pub path: String,
+    pub title: Option<String>,
+    pub slug: Option<String>,
+    pub rows: usize,
+    pub attempts: usize,
+    pub error: Option<String>,
+}
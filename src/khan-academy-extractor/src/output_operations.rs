@@ -0,0 +1,56 @@
+use crate::args::{Args, OutputArgs};
+use chrono::{Local, NaiveDate};
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::output_utils::render_file_name;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+
+/// Builds the path of an output file from the output directory and file name template of a
+/// command, creating its directory if it does not exist. Without an output
+/// directory, the file goes next to the captures: in their directory, or in the directory of
+/// the bundle file.
+///
+/// # Parameters
+///
+/// - `args`: The command-line arguments with the path and prefix of the JSON files.
+/// - `output`: The output options of the command, with the output directory and file name
+///   template.
+/// - `course`: The slug of the course.
+/// - `name`: The name of the output, such as `information` or `burnup`.
+/// - `format`: The extension of the output file.
+///
+/// # Returns
+///
+/// - `Result<PathBuf, AppError>`: On success, returns the path of the output file. On failure,
+///   returns an `AppError` indicating the type of error that occurred, such as an invalid
+///   template or an I/O error.
+pub fn output_file_path(
+    args: &Args,
+    output: &OutputArgs,
+    course: &str,
+    name: &str,
+    format: &str,
+) -> Result<PathBuf, AppError> {
+    let today: NaiveDate = Local::now().date_naive();
+    let file_name: String = render_file_name(
+        &output.output_template,
+        course,
+        &args.prefix,
+        name,
+        today,
+        format,
+    )?;
+    let directory: &Path = match output.output_dir.as_deref() {
+        Some(output_dir) => Path::new(output_dir),
+        None if Path::new(&args.path).is_file() => {
+            Path::new(&args.path).parent().unwrap_or(Path::new("."))
+        }
+        None => Path::new(&args.path),
+    };
+    let path: PathBuf = directory.join(file_name);
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,63 @@
+use crate::error::AppError;
+use chrono::NaiveDate;
+
+/// Placeholders that can be used in the template of the output file names.
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &["course", "prefix", "name", "date", "format"];
+
+/// Renders the name of an output file from a template.
+///
+/// The template can contain the `{course}`, `{prefix}`, `{name}`, `{date}` and `{format}`
+/// placeholders, which are replaced with the slug of the course, the prefix of the capture
+/// files, the name of the output, such as `information` or `attempts`, the date in YYYY-MM-DD
+/// format, and the extension of the output format.
+///
+/// # Parameters
+///
+/// - `template`: The template of the file name, such as `{prefix}{name}.{format}`.
+/// - `course`: The slug of the course.
+/// - `prefix`: The prefix of the capture files.
+/// - `name`: The name of the output.
+/// - `date`: The date of the run.
+/// - `format`: The extension of the output format, such as `csv` or `json`.
+///
+/// # Returns
+///
+/// - `Result<String, AppError>`: On success, returns the file name. On failure, returns an
+///   `AppError::InvalidArgument` if the template contains an unknown or unclosed placeholder.
+pub fn render_file_name(
+    template: &str,
+    course: &str,
+    prefix: &str,
+    name: &str,
+    date: NaiveDate,
+    format: &str,
+) -> Result<String, AppError> {
+    let mut file_name: String = String::new();
+    let mut rest: &str = template;
+
+    while let Some(start) = rest.find('{') {
+        file_name.push_str(&rest[..start]);
+        let end: usize = rest[start..].find('}').ok_or_else(|| {
+            AppError::InvalidArgument(format!("unclosed placeholder in {}", template))
+        })? + start;
+        let value: String = match &rest[start + 1..end] {
+            "course" => course.to_string(),
+            "prefix" => prefix.to_string(),
+            "name" => name.to_string(),
+            "date" => date.format("%Y-%m-%d").to_string(),
+            "format" => format.to_string(),
+            placeholder => {
+                return Err(AppError::InvalidArgument(format!(
+                    "unknown placeholder {{{}}}, expected one of {}",
+                    placeholder,
+                    TEMPLATE_PLACEHOLDERS.join(", ")
+                )))
+            }
+        };
+        file_name.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    file_name.push_str(rest);
+
+    Ok(file_name)
+}
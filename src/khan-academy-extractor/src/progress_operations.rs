@@ -0,0 +1,354 @@
+use crate::course_utils::find_quiz_index;
+use crate::error::AppError;
+use crate::extractors::extract_course_content;
+use crate::file_operations::FileContents;
+use crate::json_operations::{collect_course, process_json_files, MasteryData};
+use crate::models::{AttemptRecord, BestScore, ChallengeAttempt, DataStruct, EnrichedCourse};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Updates the rows of a course with the provided progress data.
+///
+/// This function fills the progress columns of the rows with the mastery, unit, item, quiz,
+/// test, and challenge progress data. The first row is expected to be the course itself.
+/// Every quiz, unit test, and challenge attempt seen across the files is kept, numbered per
+/// row, and used to fill the best, latest, and first score columns.
+///
+/// # Parameters
+///
+/// - `rows`: A mutable slice of `DataStruct` with the rows of the course, as returned by
+///   `collect_course`. The rows are updated in place.
+///
+/// - `mastery_data`: A `MasteryData` tuple with the mastery, mastery map, unit progress, item
+///   progress, quiz attempts, test attempts, and challenge attempts, as returned by
+///   `process_json_files`.
+///
+/// # Returns
+///
+/// - `(Vec<AttemptRecord>, Vec<String>)`: The history of attempts of the course, in course
+///   order, and a description of every attempt that could not be mapped to a row.
+pub fn apply_progress(
+    rows: &mut [DataStruct],
+    mastery_data: MasteryData,
+) -> (Vec<AttemptRecord>, Vec<String>) {
+    let (
+        mastery_v2,
+        mastery_map,
+        unit_progress,
+        items_progresses,
+        quizzes_progresses,
+        tests_progresses,
+        challenges_progresses,
+    ): MasteryData = mastery_data;
+
+    if let Some(row) = rows.get_mut(0) {
+        row.percentage = Some(mastery_v2.percentage.to_string());
+        row.points_earned = Some(mastery_v2.points_earned.to_string());
+    }
+
+    for mastery_map_item in mastery_map {
+        if let Some(row) = rows
+            .iter_mut()
+            .find(|row| row.progress_key.as_deref() == Some(&mastery_map_item.progress_key))
+        {
+            row.status = Some(mastery_map_item.status);
+        }
+    }
+
+    for unit_progress_item in unit_progress {
+        if let Some(row) = rows
+            .iter_mut()
+            .find(|row| row.id == unit_progress_item.unit_id)
+        {
+            row.percentage = Some(unit_progress_item.current_mastery_v2.percentage.to_string());
+            row.points_earned = Some(
+                unit_progress_item
+                    .current_mastery_v2
+                    .points_earned
+                    .to_string(),
+            );
+        }
+    }
+
+    for item_progresses in items_progresses {
+        for item_progress in item_progresses {
+            if let Some(row) = rows.iter_mut().find(|row| {
+                row.progress_key.as_deref() == Some(&item_progress.content.progress_key)
+            }) {
+                let best_score: Option<&BestScore> = item_progress.best_score.as_ref();
+                let num_attempted: Option<u32> = best_score.and_then(|bs| bs.num_attempted);
+                let num_correct: Option<u32> = best_score.and_then(|bs| bs.num_correct);
+                row.completion_status = Some(item_progress.completion_status);
+                row.num_attempted = num_attempted.map(|v| v.to_string());
+                row.num_correct = num_correct.map(|v| v.to_string());
+                row.num_incorrect = num_attempted
+                    .zip(num_correct)
+                    .map(|(na, nc)| na.saturating_sub(nc).to_string());
+            }
+        }
+    }
+
+    let mut seen: Vec<(usize, String, AttemptRecord)> = Vec::new();
+    let mut unmapped: Vec<String> = Vec::new();
+    for quiz_attempt in quizzes_progresses.into_iter().flatten() {
+        let Some(index) = find_quiz_index(rows, &quiz_attempt) else {
+            unmapped.push(format!(
+                "Quiz attempt in {} at position {} ({} of {} correct)",
+                quiz_attempt.parent_id,
+                quiz_attempt
+                    .position
+                    .map_or("unknown".to_string(), |position| position.to_string()),
+                quiz_attempt.num_correct,
+                quiz_attempt.num_attempted
+            ));
+            continue;
+        };
+        let record: AttemptRecord = new_attempt_record(
+            &rows[index],
+            quiz_attempt.is_completed,
+            quiz_attempt.num_attempted,
+            quiz_attempt.num_correct,
+            quiz_attempt.completed_date,
+        );
+        seen.push((index, quiz_attempt.position_key.clone(), record));
+    }
+
+    for test_attempt in tests_progresses.into_iter().flatten() {
+        let Some(index) = rows.iter().position(|row| {
+            row.parent_id.as_deref() == Some(&test_attempt.parent_id)
+                && row.type_name == "TopicUnitTest"
+        }) else {
+            unmapped.push(format!(
+                "Unit test attempt in {} ({} of {} correct)",
+                test_attempt.parent_id, test_attempt.num_correct, test_attempt.num_attempted
+            ));
+            continue;
+        };
+        let record: AttemptRecord = new_attempt_record(
+            &rows[index],
+            test_attempt.is_completed,
+            test_attempt.num_attempted,
+            test_attempt.num_correct,
+            test_attempt.completed_date,
+        );
+        seen.push((index, test_attempt.id.clone(), record));
+    }
+
+    for challenge_attempt in challenges_progresses.into_iter().flatten() {
+        let Some(index) = find_challenge_index(rows, &challenge_attempt) else {
+            unmapped.push(format!(
+                "{} attempt {} ({} of {} correct)",
+                challenge_attempt.challenge_type,
+                challenge_attempt.id,
+                challenge_attempt.num_correct,
+                challenge_attempt.num_attempted
+            ));
+            continue;
+        };
+        let record: AttemptRecord = new_attempt_record(
+            &rows[index],
+            challenge_attempt.is_completed,
+            challenge_attempt.num_attempted,
+            challenge_attempt.num_correct,
+            challenge_attempt.completed_date,
+        );
+        seen.push((index, challenge_attempt.id.clone(), record));
+    }
+
+    (number_attempts(rows, seen), unmapped)
+}
+
+/// Finds the row of the course or mastery challenge of an attempt.
+///
+/// The attempt is matched to a challenge of the same type whose parent is the decoded parent
+/// of the attempt. When the parent could not be decoded, the attempt is matched to the only
+/// challenge of its type, if there is just one.
+fn find_challenge_index(
+    rows: &[DataStruct],
+    challenge_attempt: &ChallengeAttempt,
+) -> Option<usize> {
+    let candidates: Vec<usize> = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.type_name == challenge_attempt.challenge_type)
+        .filter(|(_, row)| match challenge_attempt.parent_id.as_deref() {
+            Some(parent_id) => row.parent_id.as_deref() == Some(parent_id),
+            None => true,
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    match candidates[..] {
+        [index] => Some(index),
+        _ => None,
+    }
+}
+
+/// Creates the record of an attempt at the content of a row, before it is numbered.
+fn new_attempt_record(
+    row: &DataStruct,
+    is_completed: bool,
+    num_attempted: u32,
+    num_correct: u32,
+    completed_date: Option<String>,
+) -> AttemptRecord {
+    AttemptRecord {
+        content_id: row.id.clone(),
+        type_name: row.type_name.clone(),
+        title: row.title.clone(),
+        attempt_number: 0,
+        is_completed,
+        num_attempted,
+        num_correct,
+        score: score(num_attempted, num_correct),
+        completed_date,
+    }
+}
+
+/// Calculates the score of an attempt as a whole percentage of correct answers.
+fn score(num_attempted: u32, num_correct: u32) -> u32 {
+    num_correct
+        .saturating_mul(100)
+        .checked_div(num_attempted)
+        .unwrap_or(0)
+}
+
+/// Checks whether two records of an attempt with the same id are the same attempt, seen in
+/// several files.
+///
+/// Records with the same state are the same attempt. A record of an attempt in progress is
+/// also the same attempt as a completed record whose counts it does not exceed, since it was
+/// captured before that attempt was completed. Quiz attempts are only identified by their
+/// quiz, so a quiz in progress whose counts do not exceed those of a completed attempt at the
+/// same quiz is taken as that attempt.
+fn is_same_attempt(record: &AttemptRecord, other: &AttemptRecord) -> bool {
+    let same_state: bool = record.is_completed == other.is_completed
+        && record.num_attempted == other.num_attempted
+        && record.num_correct == other.num_correct
+        && record.completed_date == other.completed_date;
+    let completed_later: bool = !record.is_completed
+        && other.is_completed
+        && record.num_attempted <= other.num_attempted
+        && record.num_correct <= other.num_correct;
+
+    same_state || completed_later
+}
+
+/// Numbers the attempts seen for every row and fills the attempt columns of the rows.
+///
+/// Attempts seen more than once, because they appear in several files or were captured
+/// before and after being completed, are kept once, in their most advanced state. The
+/// attempts of a row are numbered with the completed attempts without a date first, since
+/// when they happened is unknown, then the completed attempts in the order of their dates,
+/// then the attempt in progress. The completion and score columns of a row come from its
+/// latest attempt, the last one numbered, and never from an undated attempt when a dated or
+/// in-progress one exists.
+///
+/// # Parameters
+///
+/// - `rows`: A mutable slice of `DataStruct` with the rows of the course.
+/// - `seen`: The index of the row, the id, and the record of every attempt seen.
+///
+/// # Returns
+///
+/// - `Vec<AttemptRecord>`: The numbered attempts, in course order.
+fn number_attempts(
+    rows: &mut [DataStruct],
+    seen: Vec<(usize, String, AttemptRecord)>,
+) -> Vec<AttemptRecord> {
+    let mut row_attempts: BTreeMap<usize, Vec<(String, AttemptRecord)>> = BTreeMap::new();
+    for (index, id, record) in seen {
+        let records: &mut Vec<(String, AttemptRecord)> = row_attempts.entry(index).or_default();
+        if records
+            .iter()
+            .any(|(other_id, other)| *other_id == id && is_same_attempt(&record, other))
+        {
+            continue;
+        }
+        records.retain(|(other_id, other)| *other_id != id || !is_same_attempt(other, &record));
+        records.push((id, record));
+    }
+
+    let mut attempts: Vec<AttemptRecord> = Vec::new();
+    for (index, records) in row_attempts {
+        let mut records: Vec<AttemptRecord> =
+            records.into_iter().map(|(_, record)| record).collect();
+        records.sort_by_key(|record| {
+            (
+                !record.is_completed,
+                record.completed_date.is_some(),
+                record.completed_date.clone(),
+            )
+        });
+        for (number, record) in records.iter_mut().enumerate() {
+            record.attempt_number = (number + 1) as u32;
+        }
+
+        let row: &mut DataStruct = &mut rows[index];
+        if let (Some(first), Some(latest)) = (records.first(), records.last()) {
+            update_attempt(
+                row,
+                latest.is_completed,
+                latest.num_attempted,
+                latest.num_correct,
+            );
+            row.first_score = Some(first.score.to_string());
+            row.latest_score = Some(latest.score.to_string());
+        }
+        row.best_score = records
+            .iter()
+            .map(|record| record.score)
+            .max()
+            .map(|score| score.to_string());
+        attempts.extend(records);
+    }
+
+    attempts
+}
+
+/// Fills the completion and score columns of a row with a quiz, unit test, or challenge attempt.
+fn update_attempt(row: &mut DataStruct, is_completed: bool, num_attempted: u32, num_correct: u32) {
+    let completed: &str = if is_completed {
+        "COMPLETE"
+    } else {
+        "UNCOMPLETED"
+    };
+    row.completion_status = Some(completed.to_string());
+    row.num_attempted = Some(num_attempted.to_string());
+    row.num_correct = Some(num_correct.to_string());
+    row.num_incorrect = Some(num_attempted.saturating_sub(num_correct).to_string());
+}
+
+/// Builds the rows of a course with their progress and its attempt history from the contents
+/// of the JSON files.
+///
+/// This function extracts the course structure, processes the progress files, and merges
+/// both into a single list of rows, ready to be written, along with the attempts at its
+/// quizzes, unit tests, and challenges.
+///
+/// # Parameters
+///
+/// - `file_contents`: A reference to the `FileContents` read by `read_files`.
+///
+/// # Returns
+///
+/// - `Result<EnrichedCourse, AppError>`: On success, returns the rows of the course with
+///   their progress and its attempts. On failure, returns an `AppError` indicating the type of error that
+///   occurred during the extraction.
+pub fn build_course(file_contents: &FileContents) -> Result<EnrichedCourse, AppError> {
+    let course_content: Value = extract_course_content(&file_contents.json_content)?;
+    let mut rows: Vec<DataStruct> = collect_course(&course_content)?;
+    let mastery_data: MasteryData = process_json_files(
+        &file_contents.json_course_progress,
+        &file_contents.json_unit_progress_files,
+        &file_contents.json_quiz_test_progress_files,
+    )?;
+    let (attempts, unmapped_attempts): (Vec<AttemptRecord>, Vec<String>) =
+        apply_progress(&mut rows, mastery_data);
+
+    Ok(EnrichedCourse {
+        rows,
+        attempts,
+        unmapped_attempts,
+    })
+}
@@ -0,0 +1,134 @@
+use crate::error::AppError;
+use crate::json_utils::read_json_file;
+use crate::models::PseudonymMap;
+use crate::write_utils::write_file_atomically;
+use sha2::{Digest, Sha256};
+use std::fs::write;
+use std::path::Path;
+
+/// Prefix of every alias, followed by the first characters of the salted hash.
+const ALIAS_PREFIX: &str = "learner-";
+/// Number of hexadecimal characters of the salted hash kept in the alias.
+const ALIAS_LENGTH: usize = 10;
+
+/// Calculates the salted SHA-256 hash of a value as a hexadecimal string.
+///
+/// # Parameters
+///
+/// - `salt`: The salt prepended to the value.
+/// - `value`: The value to be hashed.
+///
+/// # Returns
+///
+/// - `String`: The hash in lowercase hexadecimal.
+pub fn salted_hash(salt: &str, value: &str) -> String {
+    let mut hasher: Sha256 = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(b":");
+    hasher.update(value.as_bytes());
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Number of random bytes of a generated salt.
+const SALT_LENGTH: usize = 32;
+
+/// Generates a new salt from the random number generator of the operating system, so the
+/// aliases cannot be reversed by guessing when the salt was made.
+fn generate_salt() -> Result<String, AppError> {
+    let mut bytes: [u8; SALT_LENGTH] = [0; SALT_LENGTH];
+    getrandom::getrandom(&mut bytes).map_err(std::io::Error::from)?;
+
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Loads the mapping of learner identifiers to aliases from a JSON file.
+///
+/// If the file does not exist, a new mapping with the given salt is returned, or with a
+/// random salt when none is given. A salt given for an existing mapping must match the
+/// stored one, since a different salt would produce different aliases.
+///
+/// # Parameters
+///
+/// - `path`: A path to the JSON file with the mapping.
+/// - `salt`: An optional salt for the hashes.
+///
+/// # Returns
+///
+/// - `Result<PseudonymMap, AppError>`: On success, returns the mapping. On failure, returns an
+///   `AppError` indicating the type of error that occurred, such as an I/O or JSON error.
+pub fn load_pseudonym_map<P: AsRef<Path>>(
+    path: P,
+    salt: Option<&str>,
+) -> Result<PseudonymMap, AppError> {
+    if !path.as_ref().exists() {
+        return Ok(PseudonymMap {
+            salt: match salt {
+                Some(salt) => salt.to_string(),
+                None => generate_salt()?,
+            },
+            aliases: Default::default(),
+        });
+    }
+
+    let pseudonym_map: PseudonymMap = serde_json::from_str(&read_json_file(path)?)?;
+    if salt.is_some_and(|salt| salt != pseudonym_map.salt) {
+        return Err(AppError::InvalidArgument(
+            "the salt does not match the one of the pseudonym map".to_string(),
+        ));
+    }
+
+    Ok(pseudonym_map)
+}
+
+/// Saves the mapping of learner identifiers to aliases in a JSON file, replacing it atomically
+/// so an interrupted run never loses the existing aliases.
+///
+/// # Parameters
+///
+/// - `path`: A path to the JSON file with the mapping.
+/// - `pseudonym_map`: A reference to the mapping to be saved.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the type of error that occurred, such as an I/O error.
+pub fn save_pseudonym_map<P: AsRef<Path>>(
+    path: P,
+    pseudonym_map: &PseudonymMap,
+) -> Result<(), AppError> {
+    let content: String = serde_json::to_string_pretty(pseudonym_map)?;
+    write_file_atomically(path, 0, |temporary| Ok(write(temporary, content)?))?;
+
+    Ok(())
+}
+
+/// Returns the stable alias of a learner identifier, adding it to the mapping if it is new.
+///
+/// # Parameters
+///
+/// - `pseudonym_map`: A mutable reference to the mapping of identifiers to aliases.
+/// - `identifier`: The learner identifier, such as a label or a user id.
+///
+/// # Returns
+///
+/// - `String`: The alias of the learner.
+pub fn pseudonymise(pseudonym_map: &mut PseudonymMap, identifier: &str) -> String {
+    let salt: &str = &pseudonym_map.salt;
+
+    pseudonym_map
+        .aliases
+        .entry(identifier.to_string())
+        .or_insert_with(|| {
+            format!(
+                "{}{}",
+                ALIAS_PREFIX,
+                &salted_hash(salt, identifier)[..ALIAS_LENGTH]
+            )
+        })
+        .clone()
+}
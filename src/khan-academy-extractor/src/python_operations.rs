@@ -0,0 +1,241 @@
+use crate::annotation_operations::{load_annotations, Annotations};
+use crate::error::AppError;
+use crate::extractor::Extractor;
+use crate::models::EnrichedCourse;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyModule;
+use serde_json::to_string;
+
+create_exception!(
+    khan_academy_extractor,
+    ExtractorError,
+    PyException,
+    "Base class of the errors raised by the extractor."
+);
+create_exception!(
+    khan_academy_extractor,
+    IoError,
+    ExtractorError,
+    "A file could not be read or written."
+);
+create_exception!(
+    khan_academy_extractor,
+    MissingFileError,
+    ExtractorError,
+    "A required capture file was not found."
+);
+create_exception!(
+    khan_academy_extractor,
+    MissingFieldError,
+    ExtractorError,
+    "A capture file lacks one of its required fields."
+);
+create_exception!(
+    khan_academy_extractor,
+    ParseError,
+    ExtractorError,
+    "A capture or annotations file could not be parsed."
+);
+create_exception!(
+    khan_academy_extractor,
+    InvalidDateError,
+    ExtractorError,
+    "A date could not be parsed."
+);
+create_exception!(
+    khan_academy_extractor,
+    InvalidArgumentError,
+    ExtractorError,
+    "An argument is not valid."
+);
+create_exception!(
+    khan_academy_extractor,
+    LockedError,
+    ExtractorError,
+    "The output directory is locked by another run."
+);
+create_exception!(
+    khan_academy_extractor,
+    IncompleteCaptureError,
+    ExtractorError,
+    "The capture set is incomplete."
+);
+
+/// Converts an error to the Python exception of its kind, so every error of the extractor can
+/// be caught as `ExtractorError`.
+impl From<AppError> for PyErr {
+    fn from(error: AppError) -> Self {
+        let message: String = error.to_string();
+        match error {
+            AppError::Io(_) => IoError::new_err(message),
+            AppError::Json(_) | AppError::Csv(_) | AppError::Toml(_) | AppError::Archive(_) => {
+                ParseError::new_err(message)
+            }
+            AppError::MissingField(_) => MissingFieldError::new_err(message),
+            AppError::MissingFile(_) => MissingFileError::new_err(message),
+            AppError::InvalidDate(_) => InvalidDateError::new_err(message),
+            AppError::InvalidArgument(_) => InvalidArgumentError::new_err(message),
+            AppError::Locked(_) => LockedError::new_err(message),
+            AppError::Http(_) => ExtractorError::new_err(message),
+            AppError::IncompleteCapture(_) => IncompleteCaptureError::new_err(message),
+        }
+    }
+}
+
+/// Converts an enriched course to Python objects.
+///
+/// The course is serialized to JSON and loaded with the `json` module of Python, so the rows
+/// and attempts become dicts with the same keys, in the same order, as the columns of the CSV
+/// outputs.
+///
+/// # Parameters
+///
+/// - `py`: The Python interpreter.
+/// - `course`: The enriched course.
+/// - `records`: Whether to return only the rows, as a list of dicts ready for
+///   `pandas.DataFrame`, rather than a dict with the `rows`, `attempts`, and
+///   `unmappedAttempts` of the course.
+///
+/// # Returns
+///
+/// - `PyResult<PyObject>`: On success, returns the Python objects of the course. On failure,
+///   returns the Python exception of the error.
+fn to_python(py: Python<'_>, course: &EnrichedCourse, records: bool) -> PyResult<PyObject> {
+    let content: String = if records {
+        to_string(&course.rows)
+    } else {
+        to_string(course)
+    }
+    .map_err(AppError::from)?;
+
+    Ok(py
+        .import("json")?
+        .call_method1("loads", (content,))?
+        .unbind())
+}
+
+/// Loads the annotations file given to an extraction, if any.
+fn read_annotations(annotations: Option<&str>) -> Result<Option<Annotations>, AppError> {
+    annotations.map(load_annotations).transpose()
+}
+
+/// Runs an extraction, with the annotations if any, and converts the course to Python objects.
+fn extract(
+    py: Python<'_>,
+    extractor: Extractor,
+    annotations: Option<&str>,
+    records: bool,
+) -> PyResult<PyObject> {
+    let extractor: Extractor = match read_annotations(annotations)? {
+        Some(annotations) => extractor.with_annotations(annotations),
+        None => extractor,
+    };
+    let course: EnrichedCourse = py.allow_threads(|| extractor.extract())?;
+
+    to_python(py, &course, records)
+}
+
+/// Extracts a course from the capture files of a directory, an archive, or a bundle file.
+///
+/// # Parameters
+///
+/// - `path`: The path of the capture directory, archive, or bundle file.
+/// - `prefix`: The prefix of the capture files.
+/// - `annotations`: The path of an annotations file merged into the rows, if any.
+/// - `records`: Whether to return only the rows as a list of records.
+///
+/// # Returns
+///
+/// - `PyResult<PyObject>`: A dict with the `rows`, `attempts`, and `unmappedAttempts` of the
+///   course, or the list of rows when `records` is true.
+#[pyfunction]
+#[pyo3(signature = (path, prefix = "", annotations = None, records = false))]
+fn extract_dir(
+    py: Python<'_>,
+    path: &str,
+    prefix: &str,
+    annotations: Option<&str>,
+    records: bool,
+) -> PyResult<PyObject> {
+    let extractor: Extractor = Extractor::from_dir(path, prefix)?;
+
+    extract(py, extractor, annotations, records)
+}
+
+/// Extracts a course from the contents of the captures.
+///
+/// # Parameters
+///
+/// - `content_for_path`: The content of the `contentForPath` capture.
+/// - `course_progress`: The content of the `courseProgressQuery` capture.
+/// - `unit_progress`: The contents of the `getUserInfoForTopicProgressMastery-N` captures.
+/// - `quiz_test_progress`: The contents of the `quizAndUnitTestAttemptsQuery-N` captures.
+/// - `annotations`: The path of an annotations file merged into the rows, if any.
+/// - `records`: Whether to return only the rows as a list of records.
+///
+/// # Returns
+///
+/// - `PyResult<PyObject>`: A dict with the `rows`, `attempts`, and `unmappedAttempts` of the
+///   course, or the list of rows when `records` is true.
+#[pyfunction]
+#[pyo3(signature = (
+    content_for_path,
+    course_progress,
+    unit_progress = Vec::new(),
+    quiz_test_progress = Vec::new(),
+    annotations = None,
+    records = false
+))]
+fn extract_strings(
+    py: Python<'_>,
+    content_for_path: String,
+    course_progress: String,
+    unit_progress: Vec<String>,
+    quiz_test_progress: Vec<String>,
+    annotations: Option<&str>,
+    records: bool,
+) -> PyResult<PyObject> {
+    let extractor: Extractor = Extractor::from_strings(
+        content_for_path,
+        course_progress,
+        unit_progress,
+        quiz_test_progress,
+    );
+
+    extract(py, extractor, annotations, records)
+}
+
+/// Python module of the extractor, built with the `python` feature.
+///
+/// ```python
+/// import pandas
+/// import khan_academy_extractor
+///
+/// rows = khan_academy_extractor.extract_dir("captures", records=True)
+/// frame = pandas.DataFrame(rows)
+/// ```
+#[pymodule]
+fn khan_academy_extractor(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py: Python<'_> = module.py();
+    module.add_function(wrap_pyfunction!(extract_dir, module)?)?;
+    module.add_function(wrap_pyfunction!(extract_strings, module)?)?;
+    module.add("ExtractorError", py.get_type::<ExtractorError>())?;
+    module.add("IoError", py.get_type::<IoError>())?;
+    module.add("MissingFileError", py.get_type::<MissingFileError>())?;
+    module.add("MissingFieldError", py.get_type::<MissingFieldError>())?;
+    module.add("ParseError", py.get_type::<ParseError>())?;
+    module.add("InvalidDateError", py.get_type::<InvalidDateError>())?;
+    module.add(
+        "InvalidArgumentError",
+        py.get_type::<InvalidArgumentError>(),
+    )?;
+    module.add("LockedError", py.get_type::<LockedError>())?;
+    module.add(
+        "IncompleteCaptureError",
+        py.get_type::<IncompleteCaptureError>(),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use crate::course_utils::{find_unit_id, index_rows};
+use crate::error::AppError;
+use crate::extractors::{
+    extract_course_content, extract_item_progresses, extract_quiz_attempts,
+    extract_unit_test_attempts,
+};
+use crate::file_utils::{file_number, find_json_file, find_json_files};
+use crate::json_operations::collect_course;
+use crate::models::DataStruct;
+use crate::server_operations::{decode_component, respond_json};
+use crate::source_operations::InputSource;
+use serde_json::{from_str, json, Value};
+use std::collections::{HashMap, HashSet};
+use tiny_http::{Method, Request, Server};
+
+/// Operations answered with a single capture file.
+pub const SINGLE_OPERATIONS: &[&str] = &["contentForPath", "courseProgressQuery"];
+
+/// Operations answered with one of their numbered capture files, selected by the variables.
+pub const NUMBERED_OPERATIONS: &[&str] = &[
+    "getUserInfoForTopicProgressMastery",
+    "quizAndUnitTestAttemptsQuery",
+];
+
+/// Numbered capture file of an operation, with the identifiers of the unit it was captured
+/// from.
+struct NumberedCapture {
+    number: u32,
+    keys: HashSet<String>,
+    content: String,
+}
+
+/// Captured GraphQL responses, by operation name.
+///
+/// The numbered files of an operation are captured from different unit pages. Each one is
+/// indexed by the identifiers of its unit: the id, slug, and relative URL of the unit, and the
+/// ids and progress keys of the content it covers. A request selects the file that shares the
+/// most identifiers with the string values of its variables. The quiz and unit test attempts
+/// file with a number shares the identifiers of the unit progress file with the same number,
+/// since both are captured from the same unit page.
+pub struct ReplayCaptures {
+    single: HashMap<String, String>,
+    numbered: HashMap<String, Vec<NumberedCapture>>,
+}
+
+/// Normalises an identifier so that slugs and URLs match with or without their slashes.
+fn normalise_key(value: &str) -> String {
+    value.trim_matches('/').to_lowercase()
+}
+
+/// Adds the identifiers of the unit of a row, and of the row itself, to a set of keys.
+fn add_unit_keys(
+    keys: &mut HashSet<String>,
+    rows: &[DataStruct],
+    row_indexes: &HashMap<&str, usize>,
+    row: &DataStruct,
+) {
+    keys.insert(normalise_key(&row.id));
+    if let Some(unit) = find_unit_id(rows, row_indexes, row)
+        .and_then(|unit_id| row_indexes.get(unit_id))
+        .map(|&index| &rows[index])
+    {
+        keys.insert(normalise_key(&unit.id));
+        keys.insert(normalise_key(&unit.slug));
+        keys.insert(normalise_key(&unit.relative_url));
+    }
+}
+
+impl ReplayCaptures {
+    /// Loads the captured responses of a source.
+    ///
+    /// # Parameters
+    ///
+    /// - `source`: The source of the capture files.
+    /// - `prefix`: A string slice with the prefix of the capture files.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ReplayCaptures, AppError>`: On success, returns the captured responses. On
+    ///   failure, returns an `AppError` indicating the type of error that occurred, such as a
+    ///   JSON error in a numbered capture file.
+    pub fn load(source: &dyn InputSource, prefix: &str) -> Result<Self, AppError> {
+        let files: Vec<String> = source.list_files()?;
+        let mut single: HashMap<String, String> = HashMap::new();
+        for operation in SINGLE_OPERATIONS {
+            if let Some(file) = find_json_file(&files, prefix, operation) {
+                single.insert(operation.to_string(), source.read_file(file)?);
+            }
+        }
+
+        let rows: Vec<DataStruct> = match single.get("contentForPath") {
+            Some(json_content) => collect_course(&extract_course_content(json_content)?)?,
+            None => Vec::new(),
+        };
+        let row_indexes: HashMap<&str, usize> = index_rows(&rows);
+        let find_row = |key: &str| -> Option<&DataStruct> {
+            rows.iter()
+                .find(|row| row.id == key || row.progress_key.as_deref() == Some(key))
+        };
+
+        let mut unit_keys: HashMap<u32, HashSet<String>> = HashMap::new();
+        let mut unit_captures: Vec<NumberedCapture> = Vec::new();
+        for file in find_json_files(&files, prefix, "getUserInfoForTopicProgressMastery-") {
+            let number: u32 = file_number(file);
+            let content: String = source.read_file(file)?;
+            let mut keys: HashSet<String> = HashSet::new();
+            for item_progress in extract_item_progresses(&content)? {
+                keys.insert(normalise_key(&item_progress.content.progress_key));
+                match find_row(&item_progress.content.progress_key) {
+                    Some(row) => add_unit_keys(&mut keys, &rows, &row_indexes, row),
+                    None => {
+                        keys.insert(normalise_key(&item_progress.content.id));
+                    }
+                }
+            }
+            unit_keys.insert(number, keys.clone());
+            unit_captures.push(NumberedCapture {
+                number,
+                keys,
+                content,
+            });
+        }
+
+        let mut attempt_captures: Vec<NumberedCapture> = Vec::new();
+        for file in find_json_files(&files, prefix, "quizAndUnitTestAttemptsQuery-") {
+            let number: u32 = file_number(file);
+            let content: String = source.read_file(file)?;
+            let mut keys: HashSet<String> = unit_keys.get(&number).cloned().unwrap_or_default();
+            let parent_ids: Vec<String> = extract_quiz_attempts(&content)?
+                .into_iter()
+                .map(|attempt| attempt.parent_id)
+                .chain(
+                    extract_unit_test_attempts(&content)?
+                        .into_iter()
+                        .map(|attempt| attempt.parent_id),
+                )
+                .collect();
+            for parent_id in parent_ids {
+                match find_row(&parent_id) {
+                    Some(row) => add_unit_keys(&mut keys, &rows, &row_indexes, row),
+                    None => {
+                        keys.insert(normalise_key(&parent_id));
+                    }
+                }
+            }
+            attempt_captures.push(NumberedCapture {
+                number,
+                keys,
+                content,
+            });
+        }
+
+        let numbered: HashMap<String, Vec<NumberedCapture>> = NUMBERED_OPERATIONS
+            .iter()
+            .map(|operation| operation.to_string())
+            .zip([unit_captures, attempt_captures])
+            .collect();
+
+        Ok(Self { single, numbered })
+    }
+
+    /// Lists the number of captured responses of every operation.
+    pub fn operations(&self) -> Vec<(String, usize)> {
+        SINGLE_OPERATIONS
+            .iter()
+            .map(|operation| {
+                (
+                    operation.to_string(),
+                    self.single.contains_key(*operation) as usize,
+                )
+            })
+            .chain(NUMBERED_OPERATIONS.iter().map(|operation| {
+                (
+                    operation.to_string(),
+                    self.numbered.get(*operation).map_or(0, Vec::len),
+                )
+            }))
+            .collect()
+    }
+
+    /// Finds the captured response of a GraphQL operation.
+    ///
+    /// # Parameters
+    ///
+    /// - `operation_name`: The name of the operation.
+    /// - `variables`: The variables of the request, used to select a numbered file.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<&str, String>`: On success, returns the captured response. On failure, returns
+    ///   the reason why no response matches the request.
+    pub fn answer(&self, operation_name: &str, variables: &Value) -> Result<&str, String> {
+        if let Some(content) = self.single.get(operation_name) {
+            return Ok(content);
+        }
+        let captures: &Vec<NumberedCapture> = self
+            .numbered
+            .get(operation_name)
+            .filter(|captures| !captures.is_empty())
+            .ok_or_else(|| format!("No capture for operation {}", operation_name))?;
+
+        let mut values: HashSet<String> = HashSet::new();
+        collect_strings(variables, &mut values);
+        let best: Option<(usize, &NumberedCapture)> = captures
+            .iter()
+            .map(|capture| (capture.keys.intersection(&values).count(), capture))
+            .filter(|(count, _)| *count > 0)
+            .max_by_key(|(count, capture)| (*count, u32::MAX - capture.number));
+
+        match (best, &captures[..]) {
+            (Some((_, capture)), _) => Ok(&capture.content),
+            (None, [capture]) => Ok(&capture.content),
+            (None, _) => Err(format!(
+                "No capture of {} matches the variables {}",
+                operation_name, variables
+            )),
+        }
+    }
+}
+
+/// Collects the normalised string values of a JSON value, at any depth.
+fn collect_strings(value: &Value, values: &mut HashSet<String>) {
+    match value {
+        Value::String(value) => {
+            values.insert(normalise_key(value));
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, values)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| collect_strings(field, values)),
+        _ => {}
+    }
+}
+
+/// Answers a GraphQL request with the matching captured response.
+///
+/// The operation name is read from the `operationName` field of the body, or from the last
+/// segment of the URL path, as in `/api/internal/graphql/contentForPath`. The variables are
+/// read from the `variables` field of the body, or from the `variables` parameter of the URL
+/// of a persisted query.
+///
+/// # Parameters
+///
+/// - `captures`: The captured responses.
+/// - `url`: The URL of the request.
+/// - `body`: The body of the request.
+///
+/// # Returns
+///
+/// - `(u16, String)`: The HTTP status and the body of the response. Errors are GraphQL error
+///   objects.
+pub fn handle_graphql(captures: &ReplayCaptures, url: &str, body: &str) -> (u16, String) {
+    let request: Value = if body.trim().is_empty() {
+        Value::Null
+    } else {
+        match from_str(body) {
+            Ok(request) => request,
+            Err(error) => {
+                return (
+                    400,
+                    json!({ "errors": [{ "message": format!("Invalid body: {}", error) }] })
+                        .to_string(),
+                )
+            }
+        }
+    };
+    let (path, query): (&str, &str) = url.split_once('?').unwrap_or((url, ""));
+    let operation_name: &str = request["operationName"]
+        .as_str()
+        .or_else(|| path.rsplit('/').find(|segment| !segment.is_empty()))
+        .unwrap_or_default();
+
+    let variables: Value = match &request["variables"] {
+        Value::Null => query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "variables")
+            .and_then(|(_, value)| from_str(&decode_component(value)).ok())
+            .unwrap_or(Value::Null),
+        variables => variables.clone(),
+    };
+
+    match captures.answer(operation_name, &variables) {
+        Ok(content) => (200, content.to_string()),
+        Err(message) => (
+            404,
+            json!({ "errors": [{ "message": message }] }).to_string(),
+        ),
+    }
+}
+
+/// Answers a request of the replay server.
+fn respond(captures: &ReplayCaptures, mut request: Request) -> Result<(), AppError> {
+    if *request.method() != Method::Post && *request.method() != Method::Get {
+        let body: String =
+            json!({ "errors": [{ "message": "Only GET and POST requests are supported" }] })
+                .to_string();
+        return respond_json(request, 405, body);
+    }
+    let mut body: String = String::new();
+    request.as_reader().read_to_string(&mut body)?;
+    let (status, body): (u16, String) = handle_graphql(captures, request.url(), &body);
+
+    respond_json(request, status, body)
+}
+
+/// Runs the replay server until the process is stopped.
+///
+/// # Parameters
+///
+/// - `address`: The address the server listens on, such as `127.0.0.1:8081`.
+/// - `captures`: The captured responses.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On failure, returns an `AppError` if the server cannot listen on
+///   the address.
+pub fn replay(address: &str, captures: &ReplayCaptures) -> Result<(), AppError> {
+    let server: Server = Server::http(address)
+        .map_err(|error| AppError::InvalidArgument(format!("{}: {}", address, error)))?;
+    for (operation, count) in captures.operations() {
+        eprintln!("{}: {} captures", operation, count);
+    }
+    println!("Replaying on http://{}", address);
+
+    for request in server.incoming_requests() {
+        if let Err(error) = respond(captures, request) {
+            eprintln!("Error: {}", error);
+        }
+    }
+
+    Ok(())
+}
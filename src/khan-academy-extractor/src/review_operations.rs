@@ -0,0 +1,217 @@
+use crate::error::AppError;
+use crate::models::{AttemptRecord, ContentItemProgress, DataStruct, ReviewItem};
+use chrono::{DateTime, Duration, NaiveDate};
+
+/// Review intervals, in days, for each Leitner box. Box 1 is reviewed the next day and
+/// box 5 a month later.
+const REVIEW_INTERVALS: [i64; 5] = [1, 3, 7, 14, 30];
+
+/// Lowest percentage of correct answers of an attempt that moves content one box up.
+const PASSING_SCORE: u32 = 70;
+
+/// Parses a completion date as reported by Khan Academy.
+///
+/// The dates in `bestScore.completedDate` are RFC 3339 timestamps, but plain `YYYY-MM-DD`
+/// dates are accepted as well so that hand-edited captures keep working.
+///
+/// # Parameters
+///
+/// - `value`: A string slice containing the date to be parsed.
+///
+/// # Returns
+///
+/// - `Result<NaiveDate, AppError>`: On success, returns the calendar date of the timestamp.
+///   On failure, returns an `AppError::InvalidDate` with the offending value.
+pub fn parse_completed_date(value: &str) -> Result<NaiveDate, AppError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date_time| date_time.date_naive())
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .map_err(|_| AppError::InvalidDate(value.to_string()))
+}
+
+/// Places content in a Leitner box by replaying the history of its scores.
+///
+/// The content starts in box 1. Every score of at least `PASSING_SCORE` moves it one box up,
+/// up to box 5, and every lower score sends it back to box 1.
+///
+/// # Parameters
+///
+/// - `scores`: The percentages of correct answers of the attempts, oldest first.
+///
+/// # Returns
+///
+/// - `u32`: The box of the content after its last attempt, between 1 and 5.
+pub fn review_box(scores: &[u32]) -> u32 {
+    scores.iter().fold(1, |review_box, &score| {
+        if score >= PASSING_SCORE {
+            (review_box + 1).min(REVIEW_INTERVALS.len() as u32)
+        } else {
+            1
+        }
+    })
+}
+
+/// Builds the review item of content from the history of its scores.
+///
+/// # Parameters
+///
+/// - `row`: The row of the content.
+/// - `history`: The completion date and percentage of correct answers of every attempt,
+///   oldest first. It must not be empty.
+fn review_item(row: &DataStruct, history: &[(NaiveDate, u32)]) -> ReviewItem {
+    let scores: Vec<u32> = history.iter().map(|&(_, score)| score).collect();
+    let review_box: u32 = review_box(&scores);
+    let (completed_date, accuracy): (NaiveDate, u32) = history[history.len() - 1];
+
+    ReviewItem {
+        id: row.id.clone(),
+        title: row.title.clone(),
+        parent_title: row.parent_title.clone(),
+        relative_url: row.relative_url.clone(),
+        status: row.status.clone(),
+        accuracy: Some(accuracy),
+        review_box,
+        completed_date,
+        due_date: completed_date + Duration::days(REVIEW_INTERVALS[review_box as usize - 1]),
+    }
+}
+
+/// Builds the review schedule of the finished exercises and assessments of a course.
+///
+/// Every piece of content is placed in a Leitner box with `review_box`, from the history of
+/// its scores, and its next review is due after the interval of that box from its last
+/// attempt. The history of a quiz, unit test, or challenge is its completed attempts. The
+/// captures only hold the best score of an exercise, so its history is the distinct best
+/// scores with a completion date found in the unit progress files, oldest first.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course, with their mastery status, used to resolve the content.
+/// - `items_progresses`: The progress of the content items of every unit.
+/// - `attempts`: The attempts at the quizzes, unit tests, and challenges of the course.
+///
+/// # Returns
+///
+/// - `Result<Vec<ReviewItem>, AppError>`: On success, returns the scheduled content sorted by
+///   due date. On failure, returns an `AppError` indicating the type of error that occurred,
+///   such as an invalid completion date.
+pub fn schedule_reviews(
+    rows: &[DataStruct],
+    items_progresses: &[Vec<ContentItemProgress>],
+    attempts: &[AttemptRecord],
+) -> Result<Vec<ReviewItem>, AppError> {
+    let mut histories: Vec<(&DataStruct, Vec<(NaiveDate, u32)>)> = Vec::new();
+
+    for item_progress in items_progresses.iter().flatten() {
+        if item_progress.content.type_name != "Exercise" {
+            continue;
+        }
+        let Some(best_score) = item_progress.best_score.as_ref() else {
+            continue;
+        };
+        let Some(completed_date) = best_score.completed_date.as_deref() else {
+            continue;
+        };
+        let Some(row) = rows
+            .iter()
+            .find(|row| row.progress_key.as_deref() == Some(&item_progress.content.progress_key))
+        else {
+            continue;
+        };
+        let score: u32 = match (best_score.num_attempted, best_score.num_correct) {
+            (Some(num_attempted), Some(num_correct)) if num_attempted > 0 => {
+                num_correct * 100 / num_attempted
+            }
+            _ => 0,
+        };
+        let record: (NaiveDate, u32) = (parse_completed_date(completed_date)?, score);
+        add_to_history(&mut histories, row, record);
+    }
+
+    for attempt in attempts.iter().filter(|attempt| attempt.is_completed) {
+        let Some(completed_date) = attempt.completed_date.as_deref() else {
+            continue;
+        };
+        let Some(row) = rows.iter().find(|row| row.id == attempt.content_id) else {
+            continue;
+        };
+        let record: (NaiveDate, u32) = (parse_completed_date(completed_date)?, attempt.score);
+        add_to_history(&mut histories, row, record);
+    }
+
+    let mut review_items: Vec<ReviewItem> = histories
+        .into_iter()
+        .map(|(row, mut history)| {
+            history.sort_by_key(|&(completed_date, _)| completed_date);
+            review_item(row, &history)
+        })
+        .collect();
+    review_items.sort_by_key(|item| item.due_date);
+
+    Ok(review_items)
+}
+
+/// Adds a score to the history of a row, unless the history already has it.
+fn add_to_history<'a>(
+    histories: &mut Vec<(&'a DataStruct, Vec<(NaiveDate, u32)>)>,
+    row: &'a DataStruct,
+    record: (NaiveDate, u32),
+) {
+    match histories
+        .iter_mut()
+        .find(|(history_row, _)| history_row.id == row.id)
+    {
+        Some((_, history)) if history.contains(&record) => {}
+        Some((_, history)) => history.push(record),
+        None => histories.push((row, vec![record])),
+    }
+}
+
+/// Prints the content due today and the content due in the following days.
+///
+/// Content whose review date has already passed is listed as due today.
+///
+/// # Parameters
+///
+/// - `review_items`: The scheduled content, as returned by `schedule_reviews`.
+/// - `today`: The date used as today.
+/// - `days`: The number of days, after today, to list in the upcoming section.
+pub fn print_review(review_items: &[ReviewItem], today: NaiveDate, days: i64) {
+    let until: NaiveDate = today + Duration::days(days);
+    let due_today: Vec<&ReviewItem> = review_items
+        .iter()
+        .filter(|item| item.due_date <= today)
+        .collect();
+    let due_soon: Vec<&ReviewItem> = review_items
+        .iter()
+        .filter(|item| item.due_date > today && item.due_date <= until)
+        .collect();
+
+    println!("Due today ({})", due_today.len());
+    for item in due_today {
+        println!("  {}", format_review_item(item));
+    }
+    println!("Due in the next {} days ({})", days, due_soon.len());
+    for item in due_soon {
+        println!("  {}", format_review_item(item));
+    }
+}
+
+/// Formats scheduled content as a single line of the review list.
+fn format_review_item(item: &ReviewItem) -> String {
+    let accuracy: String = item
+        .accuracy
+        .map(|accuracy| format!("{}%", accuracy))
+        .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "{}  {} ({}) - box {}, {} correct, {} - {}",
+        item.due_date,
+        item.title,
+        item.parent_title.as_deref().unwrap_or(""),
+        item.review_box,
+        accuracy,
+        item.status.as_deref().unwrap_or("UNKNOWN"),
+        item.relative_url,
+    )
+}
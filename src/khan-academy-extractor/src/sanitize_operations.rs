@@ -0,0 +1,337 @@
+use crate::error::AppError;
+use crate::extractors::{decode_base64_bytes, CHALLENGE_FIELDS};
+use crate::file_utils::list_files_in_directory;
+use crate::json_operations::CHILD_FIELDS;
+use crate::json_utils::read_json_file;
+use crate::write_utils::write_file_atomically;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{from_str, to_string_pretty, Map, Value};
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+/// Fields of the `contentForPath` capture read by `extract_course_content`, `extract_info` and
+/// `extract_challenge_info`, besides the fields holding children and challenges, which are
+/// taken from `CHILD_FIELDS` and `CHALLENGE_FIELDS`.
+const CONTENT_FOR_PATH_FIELDS: &[&str] = &[
+    "data",
+    "contentRoute",
+    "listedPathData",
+    "course",
+    "id",
+    "__typename",
+    "translatedTitle",
+    "slug",
+    "relativeUrl",
+    "urlWithinCurationNode",
+    "progressKey",
+    "parentTopic",
+];
+
+/// Fields of the `courseProgressQuery` capture read by `extract_mastery_v2`,
+/// `extract_mastery_map` and `extract_unit_progresses`.
+const COURSE_PROGRESS_FIELDS: &[&str] = &[
+    "data",
+    "user",
+    "courseProgress",
+    "currentMasteryV2",
+    "percentage",
+    "pointsEarned",
+    "masteryMap",
+    "progressKey",
+    "status",
+    "unitProgresses",
+    "unitId",
+];
+
+/// Fields of the `getUserInfoForTopicProgressMastery` captures read by `extract_item_progresses`.
+const UNIT_PROGRESS_FIELDS: &[&str] = &[
+    "data",
+    "user",
+    "contentItemProgresses",
+    "__typename",
+    "bestScore",
+    "completedDate",
+    "numAttempted",
+    "numCorrect",
+    "completionStatus",
+    "content",
+    "id",
+    "progressKey",
+];
+
+/// Fields of the `quizAndUnitTestAttemptsQuery` captures read by `extract_quiz_attempts`,
+/// `extract_unit_test_attempts` and `extract_challenge_attempts`.
+const QUIZ_TEST_PROGRESS_FIELDS: &[&str] = &[
+    "data",
+    "user",
+    "latestQuizAttempts",
+    "latestUnitTestAttempts",
+    "latestCourseChallengeAttempts",
+    "latestMasteryChallengeAttempts",
+    "__typename",
+    "id",
+    "isCompleted",
+    "numAttempted",
+    "numCorrect",
+    "positionKey",
+    "completedDate",
+];
+
+/// Fields that identify the learner. They are removed at any depth, even when their name is
+/// in the kept fields of the capture.
+const IDENTIFYING_FIELDS: &[&str] = &["kaid", "nickname", "username", "email"];
+
+/// Fields that identify the learner in a user object, an object under a `user` field or of
+/// type `User`, since elsewhere they identify content.
+const USER_IDENTIFYING_FIELDS: &[&str] = &["id"];
+
+/// Shortest learner identifier that is redacted, so a very short nickname does not redact
+/// parts of unrelated values.
+const MIN_IDENTIFIER_LENGTH: usize = 4;
+
+/// Byte replacing every byte of a learner identifier found in a value.
+const REDACTED_BYTE: u8 = b'x';
+
+/// Returns the fields kept for a capture file, based on its name.
+///
+/// # Parameters
+///
+/// - `file_name`: The name of the capture file, without a path.
+/// - `prefix`: The prefix of the capture files.
+///
+/// # Returns
+///
+/// - `Option<Vec<&str>>`: The fields kept for the capture type, or `None` if the file is not a
+///   capture file.
+pub fn capture_fields(file_name: &str, prefix: &str) -> Option<Vec<&'static str>> {
+    let name: &str = file_name.strip_prefix(prefix)?;
+    let is_json: bool = name.ends_with(".json") || !name.contains('.');
+
+    if !is_json {
+        None
+    } else if name == "contentForPath" || name == "contentForPath.json" {
+        Some(
+            CONTENT_FOR_PATH_FIELDS
+                .iter()
+                .chain(CHILD_FIELDS)
+                .chain(CHALLENGE_FIELDS.iter().map(|(field, _)| field))
+                .copied()
+                .collect(),
+        )
+    } else if name == "courseProgressQuery" || name == "courseProgressQuery.json" {
+        Some(COURSE_PROGRESS_FIELDS.to_vec())
+    } else if name.starts_with("getUserInfoForTopicProgressMastery-") {
+        Some(UNIT_PROGRESS_FIELDS.to_vec())
+    } else if name.starts_with("quizAndUnitTestAttemptsQuery-") {
+        Some(QUIZ_TEST_PROGRESS_FIELDS.to_vec())
+    } else {
+        None
+    }
+}
+
+/// Removes every field of a JSON value that is not in the list of kept fields.
+///
+/// Objects keep only the listed fields, at any depth, and arrays are sanitized item by item.
+/// The fields that identify the learner, such as its kaid or nickname, are always removed, at
+/// any depth, as is the id of user objects.
+///
+/// # Parameters
+///
+/// - `value`: A reference to the JSON value to be sanitized.
+/// - `fields`: The names of the fields to keep.
+///
+/// # Returns
+///
+/// - `Value`: The sanitized copy of the value.
+pub fn sanitize_value(value: &Value, fields: &[&str]) -> Value {
+    sanitize_field(None, value, fields)
+}
+
+/// Sanitizes a JSON value found under the given key of its parent object.
+fn sanitize_field(key: Option<&str>, value: &Value, fields: &[&str]) -> Value {
+    match value {
+        Value::Object(object) => {
+            let is_user: bool = is_user_object(key, value);
+            Value::Object(
+                object
+                    .iter()
+                    .filter(|(field, _)| fields.contains(&field.as_str()))
+                    .filter(|(field, _)| !IDENTIFYING_FIELDS.contains(&field.as_str()))
+                    .filter(|(field, _)| {
+                        !is_user || !USER_IDENTIFYING_FIELDS.contains(&field.as_str())
+                    })
+                    .map(|(field, value)| {
+                        (field.clone(), sanitize_field(Some(field), value, fields))
+                    })
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| sanitize_field(key, item, fields))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Checks whether an object found under the given key describes a user.
+fn is_user_object(key: Option<&str>, value: &Value) -> bool {
+    key == Some("user") || value["__typename"] == "User"
+}
+
+/// Collects the values that identify the learner in a capture.
+///
+/// The values are the ones of the identifying fields at any depth, and of the id of user
+/// objects, such as the kaid in `data.user.id`.
+///
+/// # Parameters
+///
+/// - `value`: A reference to the JSON value of the capture.
+/// - `identifiers`: The identifiers found so far, extended with the new ones.
+pub fn collect_learner_identifiers(value: &Value, identifiers: &mut Vec<String>) {
+    collect_identifiers(None, value, identifiers);
+}
+
+/// Collects the learner identifiers of a JSON value found under the given key.
+fn collect_identifiers(key: Option<&str>, value: &Value, identifiers: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            let is_user: bool = is_user_object(key, value);
+            for (field, value) in object {
+                let is_identifying: bool = IDENTIFYING_FIELDS.contains(&field.as_str())
+                    || (is_user && USER_IDENTIFYING_FIELDS.contains(&field.as_str()));
+                match value.as_str() {
+                    Some(identifier)
+                        if is_identifying
+                            && identifier.len() >= MIN_IDENTIFIER_LENGTH
+                            && !identifiers.iter().any(|known| known == identifier) =>
+                    {
+                        identifiers.push(identifier.to_string())
+                    }
+                    _ => collect_identifiers(Some(field), value, identifiers),
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_identifiers(key, item, identifiers)),
+        _ => {}
+    }
+}
+
+/// Replaces every occurrence of the learner identifiers in the string values of a JSON value.
+///
+/// Identifiers are replaced byte for byte, so the value keeps its length. A value that is
+/// Base64, such as the `id` of an attempt or a `positionKey`, is also decoded, and encoded
+/// again with the identifiers replaced in its bytes, so the other bytes the extractors decode,
+/// such as the parent id and the position, are kept.
+///
+/// # Parameters
+///
+/// - `value`: A mutable reference to the JSON value to be redacted.
+/// - `identifiers`: The identifiers of the learner, as returned by
+///   `collect_learner_identifiers`.
+pub fn redact_learner_identifiers(value: &mut Value, identifiers: &[String]) {
+    match value {
+        Value::String(string) => {
+            if let Some(redacted) = redact_bytes(string.as_bytes(), identifiers) {
+                *string = String::from_utf8_lossy(&redacted).to_string();
+            } else if let Some(redacted) = decode_base64_bytes(string)
+                .ok()
+                .and_then(|decoded| redact_bytes(&decoded, identifiers))
+            {
+                let encoded: String = STANDARD.encode(redacted);
+                *string = if string.ends_with('=') {
+                    encoded
+                } else {
+                    encoded.trim_end_matches('=').to_string()
+                };
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_learner_identifiers(item, identifiers)),
+        Value::Object(object) => object
+            .values_mut()
+            .for_each(|value| redact_learner_identifiers(value, identifiers)),
+        _ => {}
+    }
+}
+
+/// Replaces the identifiers found in bytes, or returns `None` if there is none.
+fn redact_bytes(bytes: &[u8], identifiers: &[String]) -> Option<Vec<u8>> {
+    let mut redacted: Vec<u8> = bytes.to_vec();
+    let mut found: bool = false;
+    for identifier in identifiers {
+        let identifier: &[u8] = identifier.as_bytes();
+        let mut start: usize = 0;
+        while let Some(offset) = redacted[start..]
+            .windows(identifier.len())
+            .position(|window| window == identifier)
+        {
+            let index: usize = start + offset;
+            redacted[index..index + identifier.len()].fill(REDACTED_BYTE);
+            start = index + identifier.len();
+            found = true;
+        }
+    }
+
+    found.then_some(redacted)
+}
+
+/// Sanitizes the capture files of a directory into another directory.
+///
+/// Every capture file matching the prefix is rewritten with `sanitize_value` under the same
+/// name, so the output directory can be read with `read_files` like the original one. The
+/// learner identifiers found in any of the captures are then redacted from every kept value
+/// with `redact_learner_identifiers`.
+///
+/// # Parameters
+///
+/// - `path`: A string slice with the directory of the capture files.
+/// - `prefix`: A string slice with the prefix of the capture files.
+/// - `output`: A string slice with the directory where the sanitized files are written. It is
+///   created if it does not exist.
+///
+/// # Returns
+///
+/// - `Result<Vec<String>, AppError>`: On success, returns the names of the sanitized files.
+///   On failure, returns an `AppError` indicating the type of error that occurred, such as an
+///   I/O error or a JSON error.
+pub fn sanitize_files(path: &str, prefix: &str, output: &str) -> Result<Vec<String>, AppError> {
+    if Path::new(path).canonicalize()? == Path::new(output).canonicalize().unwrap_or_default() {
+        return Err(AppError::InvalidArgument(
+            "the output directory must be different from the capture directory".to_string(),
+        ));
+    }
+    create_dir_all(output)?;
+
+    let mut file_names: Vec<String> = list_files_in_directory(path)?;
+    file_names.sort();
+    let mut captures: Vec<(String, Vec<&str>, Value)> = Vec::new();
+    let mut identifiers: Vec<String> = Vec::new();
+    for file_name in file_names {
+        let Some(fields) = capture_fields(&file_name, prefix) else {
+            continue;
+        };
+        let parsed: Value = from_str(&read_json_file(format!("{}/{}", path, file_name))?)?;
+        collect_learner_identifiers(&parsed, &mut identifiers);
+        captures.push((file_name, fields, parsed));
+    }
+
+    let mut sanitized: Vec<String> = Vec::new();
+    for (file_name, fields, parsed) in captures {
+        let mut value: Value = sanitize_value(&parsed, &fields);
+        redact_learner_identifiers(&mut value, &identifiers);
+        let content: String = to_string_pretty(&value)?;
+        write_file_atomically(format!("{}/{}", output, file_name), 0, |temporary| {
+            Ok(write(temporary, content)?)
+        })?;
+        sanitized.push(file_name);
+    }
+
+    Ok(sanitized)
+}
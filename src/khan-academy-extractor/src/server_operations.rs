@@ -0,0 +1,507 @@
+use crate::annotation_operations::load_annotations;
+use crate::course_utils::{content_items, find_unit_id, index_rows};
+use crate::error::AppError;
+use crate::extractor::Extractor;
+use crate::models::{CourseSummary, DataStruct, EnrichedCourse, UnitSummary};
+use crate::tree_operations::{CourseTree, NodeKind, TreeNode};
+use crate::write_utils::LOCK_FILE_NAME;
+use serde_json::{json, to_value, Value};
+use std::collections::HashMap;
+use std::fs::{metadata, read_dir, Metadata};
+use std::panic::catch_unwind;
+use std::path::Path;
+use std::time::SystemTime;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Number of items returned by the next-up endpoint when no limit is given.
+pub const DEFAULT_NEXT_LIMIT: usize = 10;
+
+/// Size and modification time of every capture file, used to detect changes.
+type Fingerprint = Vec<(String, u64, Option<SystemTime>)>;
+
+/// Course served by the API, extracted from its captures and reloaded when they change.
+struct ServedCourse {
+    name: String,
+    path: String,
+    fingerprint: Option<Fingerprint>,
+    course: Option<EnrichedCourse>,
+    error: Option<String>,
+}
+
+/// Courses served by the API.
+///
+/// Every course is extracted from a capture directory, archive, or bundle file. `refresh`
+/// extracts again the courses whose capture files, or annotations file, changed since they
+/// were last loaded. When an extraction fails, the course keeps the data of its last
+/// successful extraction and reports the error.
+pub struct CourseStore {
+    courses: Vec<ServedCourse>,
+    prefix: String,
+    annotations: Option<String>,
+}
+
+impl CourseStore {
+    /// Creates the store of the served courses, without loading them.
+    ///
+    /// # Parameters
+    ///
+    /// - `sources`: The name and the capture path of every course.
+    /// - `prefix`: The prefix of the capture files.
+    /// - `annotations`: The path of an annotations file merged into the rows, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `CourseStore`: The store, to be loaded with `refresh`.
+    pub fn new(sources: Vec<(String, String)>, prefix: &str, annotations: Option<String>) -> Self {
+        Self {
+            courses: sources
+                .into_iter()
+                .map(|(name, path)| ServedCourse {
+                    name,
+                    path,
+                    fingerprint: None,
+                    course: None,
+                    error: None,
+                })
+                .collect(),
+            prefix: prefix.to_string(),
+            annotations,
+        }
+    }
+
+    /// Extracts again the courses whose capture files changed since they were last loaded.
+    ///
+    /// A capture that cannot be extracted, such as a file still being written, leaves the
+    /// course with the data of its last successful extraction and the error, even if the
+    /// extraction panics.
+    ///
+    /// # Returns
+    ///
+    /// - `Vec<String>`: The names of the courses that were reloaded.
+    pub fn refresh(&mut self) -> Vec<String> {
+        let mut reloaded: Vec<String> = Vec::new();
+        for served in &mut self.courses {
+            let mut fingerprint: Fingerprint = capture_fingerprint(&served.path);
+            if let Some(annotations) = &self.annotations {
+                fingerprint.extend(capture_fingerprint(annotations));
+            }
+            if served.fingerprint.as_ref() == Some(&fingerprint) {
+                continue;
+            }
+
+            let extraction: Result<EnrichedCourse, String> = catch_unwind(|| {
+                extract_course(&served.path, &self.prefix, self.annotations.as_deref())
+            })
+            .map_err(|_| "internal error while extracting the course".to_string())
+            .and_then(|result| result.map_err(|error| error.to_string()));
+            match extraction {
+                Ok(course) => {
+                    served.course = Some(course);
+                    served.error = None;
+                }
+                Err(error) => served.error = Some(error),
+            }
+            served.fingerprint = Some(fingerprint);
+            reloaded.push(served.name.clone());
+        }
+
+        reloaded
+    }
+
+    /// Returns the summary of every served course.
+    pub fn summaries(&self) -> Vec<CourseSummary> {
+        self.courses
+            .iter()
+            .map(|served| {
+                let course_row: Option<&DataStruct> = served
+                    .course
+                    .as_ref()
+                    .and_then(|course| course.rows.first());
+                CourseSummary {
+                    name: served.name.clone(),
+                    path: served.path.clone(),
+                    title: course_row.map(|row| row.title.clone()),
+                    slug: course_row.map(|row| row.slug.clone()),
+                    rows: served.course.as_ref().map_or(0, |course| course.rows.len()),
+                    attempts: served
+                        .course
+                        .as_ref()
+                        .map_or(0, |course| course.attempts.len()),
+                    error: served.error.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Finds the enriched course of a served course by name.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The name of the course.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<&EnrichedCourse, (u16, String)>`: On success, returns the course. On failure,
+    ///   returns the HTTP status and the message of the error: 404 when there is no course
+    ///   with this name, and 503 when it could never be extracted.
+    fn course(&self, name: &str) -> Result<&EnrichedCourse, (u16, String)> {
+        let served: &ServedCourse = self
+            .courses
+            .iter()
+            .find(|served| served.name == name)
+            .ok_or_else(|| (404, format!("No course named {}", name)))?;
+
+        served.course.as_ref().ok_or_else(|| {
+            (
+                503,
+                served
+                    .error
+                    .clone()
+                    .unwrap_or_else(|| format!("Course {} is not loaded", name)),
+            )
+        })
+    }
+}
+
+/// Extracts a served course, with the annotations if any.
+fn extract_course(
+    path: &str,
+    prefix: &str,
+    annotations: Option<&str>,
+) -> Result<EnrichedCourse, AppError> {
+    let mut extractor: Extractor = Extractor::from_dir(path, prefix)?;
+    if let Some(annotations) = annotations {
+        extractor = extractor.with_annotations(load_annotations(annotations)?);
+    }
+
+    extractor.extract()
+}
+
+/// Lists the size and modification time of the capture files at a path: the files of a
+/// directory, except the lock file of a run, or the archive or bundle file itself. Missing
+/// files give an empty fingerprint.
+///
+/// # Parameters
+///
+/// - `path`: The path of the captures.
+///
+/// # Returns
+///
+/// - `Fingerprint`: The name, size, and modification time of every file, sorted by name.
+fn capture_fingerprint(path: &str) -> Fingerprint {
+    let file_fingerprint =
+        |name: String, metadata: Metadata| -> (String, u64, Option<SystemTime>) {
+            (name, metadata.len(), metadata.modified().ok())
+        };
+    let mut fingerprint: Fingerprint = match read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name() != LOCK_FILE_NAME)
+            .filter_map(|entry| {
+                let metadata: Metadata = entry.metadata().ok()?;
+                metadata.is_file().then(|| {
+                    file_fingerprint(entry.file_name().to_string_lossy().to_string(), metadata)
+                })
+            })
+            .collect(),
+        Err(_) => metadata(Path::new(path))
+            .map(|metadata| vec![file_fingerprint(path.to_string(), metadata)])
+            .unwrap_or_default(),
+    };
+    fingerprint.sort();
+
+    fingerprint
+}
+
+/// Builds the tree of a course as nested JSON objects, each row with its `kind` and its
+/// `children`.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+///
+/// # Returns
+///
+/// - `Result<Value, AppError>`: On success, returns the list of the roots of the tree, which is
+///   only the course for a whole course. On failure, returns an `AppError` if a row cannot be
+///   serialized.
+pub fn course_tree_json(rows: &[DataStruct]) -> Result<Value, AppError> {
+    let tree: CourseTree = CourseTree::new(rows);
+    let order: Vec<TreeNode> = tree.depth_first().collect();
+    let mut nodes: Vec<Option<Value>> = vec![None; rows.len()];
+    for node in order.iter().rev() {
+        let mut value: Value = to_value(node.row)?;
+        let children: Vec<Value> = tree
+            .child_indexes(node.index)
+            .iter()
+            .filter_map(|&child| nodes[child].take())
+            .collect();
+        value["kind"] = json!(format!("{:?}", node.kind));
+        value["children"] = Value::Array(children);
+        nodes[node.index] = Some(value);
+    }
+
+    Ok(Value::Array(
+        order
+            .iter()
+            .filter(|node| node.depth() == 0)
+            .filter_map(|node| nodes[node.index].take())
+            .collect(),
+    ))
+}
+
+/// Summarises the progress of every unit of a course.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+///
+/// # Returns
+///
+/// - `Vec<UnitSummary>`: The mastery of every unit, with the number of its content items and
+///   of the completed ones, in course order.
+pub fn unit_summaries(rows: &[DataStruct]) -> Vec<UnitSummary> {
+    let row_indexes: HashMap<&str, usize> = index_rows(rows);
+    let mut counts: HashMap<&str, (u32, u32)> = HashMap::new();
+    for row in content_items(rows) {
+        if let Some(unit_id) = find_unit_id(rows, &row_indexes, row) {
+            let count: &mut (u32, u32) = counts.entry(unit_id).or_default();
+            count.0 += 1;
+            if row.completion_status.as_deref() == Some("COMPLETE") {
+                count.1 += 1;
+            }
+        }
+    }
+
+    rows.iter()
+        .filter(|row| row.type_name == "Unit")
+        .map(|row| {
+            let (total, completed): (u32, u32) =
+                counts.get(row.id.as_str()).copied().unwrap_or_default();
+            UnitSummary {
+                id: row.id.clone(),
+                title: row.title.clone(),
+                slug: row.slug.clone(),
+                relative_url: row.relative_url.clone(),
+                percentage: row.percentage.clone(),
+                points_earned: row.points_earned.clone(),
+                total,
+                completed,
+            }
+        })
+        .collect()
+}
+
+/// Lists the next content items and assessments to work on.
+///
+/// The items and assessments that are not complete are taken in course order, leaving out
+/// the ones annotated with `skip` and the content of skipped units and lessons.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+/// - `limit`: The maximum number of rows returned.
+///
+/// # Returns
+///
+/// - `Vec<&DataStruct>`: The next rows to work on, in course order.
+pub fn next_up(rows: &[DataStruct], limit: usize) -> Vec<&DataStruct> {
+    CourseTree::new(rows)
+        .depth_first()
+        .filter(|node| matches!(node.kind, NodeKind::Item | NodeKind::Assessment))
+        .filter(|node| node.row.completion_status.as_deref() != Some("COMPLETE"))
+        .filter(|node| {
+            node.row.skip != Some(true) && node.ancestors.iter().all(|row| row.skip != Some(true))
+        })
+        .map(|node| node.row)
+        .take(limit)
+        .collect()
+}
+
+/// Searches the rows of a course whose slug or title contains a text, ignoring case.
+///
+/// # Parameters
+///
+/// - `rows`: The rows of the course.
+/// - `query`: The text searched.
+///
+/// # Returns
+///
+/// - `Vec<&DataStruct>`: The matching rows, in course order.
+pub fn search_rows<'a>(rows: &'a [DataStruct], query: &str) -> Vec<&'a DataStruct> {
+    let query: String = query.to_lowercase();
+
+    rows.iter()
+        .filter(|row| {
+            row.slug.to_lowercase().contains(&query) || row.title.to_lowercase().contains(&query)
+        })
+        .collect()
+}
+
+/// Decodes a percent-encoded component of a URL, with `+` standing for a space.
+pub fn decode_component(value: &str) -> String {
+    let bytes: &[u8] = value.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut index: usize = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[index + 1..index + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        index += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Splits a request URL into its path segments and its query parameters.
+fn parse_url(url: &str) -> (Vec<String>, HashMap<String, String>) {
+    let (path, query): (&str, &str) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(decode_component)
+        .collect();
+    let parameters: HashMap<String, String> = query
+        .split('&')
+        .filter(|parameter| !parameter.is_empty())
+        .map(|parameter| {
+            let (key, value): (&str, &str) = parameter.split_once('=').unwrap_or((parameter, ""));
+            (decode_component(key), decode_component(value))
+        })
+        .collect();
+
+    (segments, parameters)
+}
+
+/// Answers a GET request of the API.
+///
+/// The routes are:
+///
+/// - `/courses`: the summary of every served course.
+/// - `/courses/{name}/tree`: the tree of the course, as returned by `course_tree_json`.
+/// - `/courses/{name}/units`: the progress of every unit, as returned by `unit_summaries`.
+/// - `/courses/{name}/next?limit=N`: the next rows to work on, as returned by `next_up`.
+/// - `/courses/{name}/search?q=TEXT`: the rows whose slug or title contains the text.
+///
+/// # Parameters
+///
+/// - `store`: The served courses.
+/// - `url`: The URL of the request, with its query.
+///
+/// # Returns
+///
+/// - `(u16, Value)`: The HTTP status and the JSON body of the response. Errors are objects
+///   with an `error` message.
+pub fn handle_request(store: &CourseStore, url: &str) -> (u16, Value) {
+    let (segments, parameters): (Vec<String>, HashMap<String, String>) = parse_url(url);
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let response: Result<Value, (u16, String)> = match segments[..] {
+        ["courses"] => to_value(store.summaries()).map_err(|error| (500, error.to_string())),
+        ["courses", name, endpoint] => store.course(name).and_then(|course| {
+            let rows: &[DataStruct] = &course.rows;
+            match endpoint {
+                "tree" => course_tree_json(rows).map_err(|error| (500, error.to_string())),
+                "units" => Ok(json!(unit_summaries(rows))),
+                "next" => {
+                    let limit: usize = match parameters.get("limit") {
+                        Some(limit) => limit
+                            .parse()
+                            .map_err(|_| (400, format!("Invalid limit: {}", limit)))?,
+                        None => DEFAULT_NEXT_LIMIT,
+                    };
+                    Ok(json!(next_up(rows, limit)))
+                }
+                "search" => match parameters.get("q") {
+                    Some(query) => Ok(json!(search_rows(rows, query))),
+                    None => Err((400, "Missing q parameter".to_string())),
+                },
+                _ => Err((404, format!("Unknown endpoint: {}", url))),
+            }
+        }),
+        _ => Err((404, format!("Unknown endpoint: {}", url))),
+    };
+
+    match response {
+        Ok(body) => (200, body),
+        Err((status, message)) => (status, json!({ "error": message })),
+    }
+}
+
+/// Answers a request of a local server with a JSON body.
+///
+/// # Parameters
+///
+/// - `request`: The request to answer.
+/// - `status`: The HTTP status of the response.
+/// - `body`: The JSON body of the response.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On failure, returns an `AppError` if the response cannot be sent.
+pub fn respond_json(request: Request, status: u16, body: String) -> Result<(), AppError> {
+    let header: Header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .map_err(|_| AppError::InvalidArgument("invalid header".to_string()))?;
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header);
+
+    Ok(request.respond(response)?)
+}
+
+/// Answers a request of the server with the JSON response of `handle_request`.
+fn respond(store: &CourseStore, request: Request) -> Result<(), AppError> {
+    let (status, body): (u16, Value) = if *request.method() == Method::Get {
+        handle_request(store, request.url())
+    } else {
+        (405, json!({ "error": "Only GET requests are supported" }))
+    };
+
+    respond_json(request, status, body.to_string())
+}
+
+/// Runs the local API server until the process is stopped.
+///
+/// The courses whose capture files changed are reloaded before every request, so the
+/// responses always reflect the latest captures.
+///
+/// # Parameters
+///
+/// - `address`: The address the server listens on, such as `127.0.0.1:8080`.
+/// - `store`: The served courses.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On failure, returns an `AppError` if the server cannot listen on
+///   the address.
+pub fn serve(address: &str, mut store: CourseStore) -> Result<(), AppError> {
+    let server: Server = Server::http(address)
+        .map_err(|error| AppError::InvalidArgument(format!("{}: {}", address, error)))?;
+    for name in store.refresh() {
+        eprintln!("Loaded {}", name);
+    }
+    println!("Serving on http://{}", address);
+
+    for request in server.incoming_requests() {
+        for name in store.refresh() {
+            eprintln!("Reloaded {}", name);
+        }
+        if let Err(error) = respond(&store, request) {
+            eprintln!("Error: {}", error);
+        }
+    }
+
+    Ok(())
+}
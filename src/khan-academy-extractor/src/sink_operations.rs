@@ -0,0 +1,191 @@
+use crate::csv_operations::{write_attempts_csv, write_course_csv};
+use crate::error::AppError;
+use crate::models::{AttemptRecord, DataStruct, EnrichedCourse};
+use serde::Serialize;
+use serde_json::to_writer_pretty;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Destination format of an extracted course.
+///
+/// A sink turns the rows and the attempt history of a course into bytes written to any
+/// `Write`, such as a file, the standard output, or a `Vec<u8>`. The CSV and JSON formats are
+/// built in, and other crates can implement this trait and register their sink in a
+/// `SinkRegistry` to add their own formats.
+pub trait OutputSink {
+    /// Returns the file extension of the outputs of the sink, without a dot.
+    fn extension(&self) -> &str;
+
+    /// Writes the rows of a course.
+    ///
+    /// # Parameters
+    ///
+    /// - `rows`: The rows of the course, in course order.
+    /// - `output`: The destination of the rows.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+    ///   indicating the type of error that occurred, such as an I/O error.
+    fn write_rows(&self, rows: &[DataStruct], output: &mut dyn Write) -> Result<(), AppError>;
+
+    /// Writes the attempt history of a course.
+    ///
+    /// # Parameters
+    ///
+    /// - `attempts`: The attempts of the course, in course order.
+    /// - `output`: The destination of the attempts.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+    ///   indicating the type of error that occurred, such as an I/O error.
+    fn write_attempts(
+        &self,
+        attempts: &[AttemptRecord],
+        output: &mut dyn Write,
+    ) -> Result<(), AppError>;
+
+    /// Writes a whole course into two destinations, one for its rows and one for its attempt
+    /// history.
+    ///
+    /// # Parameters
+    ///
+    /// - `course`: The extracted course.
+    /// - `rows_output`: The destination of the rows.
+    /// - `attempts_output`: The destination of the attempts.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+    ///   indicating the type of error that occurred, such as an I/O error.
+    fn write_course(
+        &self,
+        course: &EnrichedCourse,
+        rows_output: &mut dyn Write,
+        attempts_output: &mut dyn Write,
+    ) -> Result<(), AppError> {
+        self.write_rows(&course.rows, rows_output)?;
+        self.write_attempts(&course.attempts, attempts_output)
+    }
+}
+
+/// Sink writing CSV files with a header line, as the command-line tool does by default.
+pub struct CsvSink;
+
+impl OutputSink for CsvSink {
+    fn extension(&self) -> &str {
+        "csv"
+    }
+
+    fn write_rows(&self, rows: &[DataStruct], output: &mut dyn Write) -> Result<(), AppError> {
+        write_course_csv(output, rows)
+    }
+
+    fn write_attempts(
+        &self,
+        attempts: &[AttemptRecord],
+        output: &mut dyn Write,
+    ) -> Result<(), AppError> {
+        write_attempts_csv(output, attempts)
+    }
+}
+
+/// Sink writing pretty-printed JSON arrays.
+pub struct JsonSink;
+
+impl JsonSink {
+    /// Writes records as a pretty-printed JSON array, followed by a new line.
+    fn write_json<T: Serialize>(records: &[T], output: &mut dyn Write) -> Result<(), AppError> {
+        to_writer_pretty(&mut *output, records)?;
+        writeln!(output)?;
+        output.flush()?;
+
+        Ok(())
+    }
+}
+
+impl OutputSink for JsonSink {
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn write_rows(&self, rows: &[DataStruct], output: &mut dyn Write) -> Result<(), AppError> {
+        Self::write_json(rows, output)
+    }
+
+    fn write_attempts(
+        &self,
+        attempts: &[AttemptRecord],
+        output: &mut dyn Write,
+    ) -> Result<(), AppError> {
+        Self::write_json(attempts, output)
+    }
+}
+
+/// Sinks available by format name.
+pub struct SinkRegistry {
+    sinks: BTreeMap<String, Box<dyn OutputSink>>,
+}
+
+impl SinkRegistry {
+    /// Creates a registry with the built-in `csv` and `json` sinks.
+    pub fn new() -> Self {
+        let mut registry: SinkRegistry = Self::empty();
+        registry.register("csv", Box::new(CsvSink));
+        registry.register("json", Box::new(JsonSink));
+
+        registry
+    }
+
+    /// Creates a registry without any sink, for callers that only want their own formats.
+    pub fn empty() -> Self {
+        Self {
+            sinks: BTreeMap::new(),
+        }
+    }
+
+    /// Registers a sink under a format name, replacing any sink with the same name.
+    ///
+    /// # Parameters
+    ///
+    /// - `format`: The name of the format, such as `csv`.
+    /// - `sink`: The sink of the format.
+    pub fn register(&mut self, format: &str, sink: Box<dyn OutputSink>) {
+        self.sinks.insert(format.to_string(), sink);
+    }
+
+    /// Returns the sink of a format.
+    ///
+    /// # Parameters
+    ///
+    /// - `format`: The name of the format.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<&dyn OutputSink, AppError>`: On success, returns the sink. On failure, returns
+    ///   an `AppError::InvalidArgument` listing the registered formats.
+    pub fn get(&self, format: &str) -> Result<&dyn OutputSink, AppError> {
+        self.sinks
+            .get(format)
+            .map(|sink| sink.as_ref())
+            .ok_or_else(|| {
+                AppError::InvalidArgument(format!(
+                    "unknown format {}, expected one of {}",
+                    format,
+                    self.formats().join(", ")
+                ))
+            })
+    }
+
+    /// Returns the names of the registered formats, in alphabetical order.
+    pub fn formats(&self) -> Vec<&str> {
+        self.sinks.keys().map(|format| format.as_str()).collect()
+    }
+}
+
+impl Default for SinkRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
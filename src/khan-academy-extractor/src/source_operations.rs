@@ -0,0 +1,329 @@
+use crate::error::AppError;
+use crate::file_utils::{list_files_in_directory, uncompressed_name};
+use crate::json_utils::{read_gzip, read_json_file, GZIP_EXTENSION};
+use flate2::read::GzDecoder;
+use serde_json::{from_str, Value};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use tar::Archive;
+use zip::ZipArchive;
+
+/// Place the capture files of a course are loaded from.
+///
+/// A source lists the names of its capture files and reads them by name, so the extraction
+/// works the same way on a directory, on captures held in memory, on a bundle file, or on any
+/// other storage that implements this trait.
+pub trait InputSource {
+    /// Lists the names of the files of the source, without a path.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Vec<String>, AppError>`: On success, returns the names of the files. On
+    ///   failure, returns an `AppError` indicating the type of error that occurred.
+    fn list_files(&self) -> Result<Vec<String>, AppError>;
+
+    /// Reads the content of a file of the source.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The name of the file, as returned by `list_files`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<String, AppError>`: On success, returns the content of the file. On failure,
+    ///   returns an `AppError` indicating the type of error that occurred, such as a missing file.
+    fn read_file(&self, name: &str) -> Result<String, AppError>;
+}
+
+/// Source reading the capture files of a directory.
+pub struct DirectorySource {
+    path: PathBuf,
+}
+
+impl DirectorySource {
+    /// Creates a source for the capture files of a directory.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl InputSource for DirectorySource {
+    fn list_files(&self) -> Result<Vec<String>, AppError> {
+        list_files_in_directory(&self.path)
+    }
+
+    fn read_file(&self, name: &str) -> Result<String, AppError> {
+        read_json_file(self.path.join(name))
+    }
+}
+
+/// Source holding the capture files in memory, by name.
+#[derive(Default)]
+pub struct MemorySource {
+    files: BTreeMap<String, String>,
+}
+
+impl MemorySource {
+    /// Creates a source without any file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file to the source, replacing any file with the same name.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The name of the file, such as `contentForPath.json`.
+    /// - `content`: The content of the file.
+    ///
+    /// # Returns
+    ///
+    /// - `MemorySource`: The source with the file.
+    pub fn with_file(mut self, name: impl Into<String>, content: impl Into<String>) -> Self {
+        self.files.insert(name.into(), content.into());
+        self
+    }
+}
+
+impl InputSource for MemorySource {
+    fn list_files(&self) -> Result<Vec<String>, AppError> {
+        Ok(self.files.keys().cloned().collect())
+    }
+
+    fn read_file(&self, name: &str) -> Result<String, AppError> {
+        self.files
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AppError::MissingFile(format!("{} file not found", name)))
+    }
+}
+
+/// Source reading the capture files from a single bundle file.
+///
+/// A bundle is a JSON object mapping the name of every capture file to its content, either
+/// as the captured JSON value itself or as a string, such as
+/// `{"contentForPath.json": {"data": ...}, "courseProgressQuery.json": {"data": ...}}`.
+pub struct BundleSource {
+    files: MemorySource,
+}
+
+impl BundleSource {
+    /// Parses the content of a bundle.
+    ///
+    /// # Parameters
+    ///
+    /// - `content`: The content of the bundle file.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<BundleSource, AppError>`: On success, returns the source. On failure, returns an
+    ///   `AppError` if the content is not a JSON object.
+    pub fn parse(content: &str) -> Result<Self, AppError> {
+        let parsed: Value = from_str(content)?;
+        let entries = parsed
+            .as_object()
+            .ok_or_else(|| AppError::MissingField("bundle object".to_string()))?;
+        let mut files: MemorySource = MemorySource::new();
+        for (name, value) in entries {
+            let content: String = match value {
+                Value::String(content) => content.clone(),
+                value => value.to_string(),
+            };
+            files = files.with_file(name.clone(), content);
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Reads and parses a bundle file.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the bundle file.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<BundleSource, AppError>`: On success, returns the source. On failure, returns an
+    ///   `AppError` indicating the type of error that occurred, such as an I/O error.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AppError> {
+        Self::parse(&read_json_file(path)?)
+    }
+}
+
+impl InputSource for BundleSource {
+    fn list_files(&self) -> Result<Vec<String>, AppError> {
+        self.files.list_files()
+    }
+
+    fn read_file(&self, name: &str) -> Result<String, AppError> {
+        self.files.read_file(name)
+    }
+}
+
+/// Source reading the capture files from the entries of a zip or tar.gz archive.
+///
+/// The entries are read once, when the archive is opened. Their directories inside the
+/// archive are ignored, so an archive of a whole capture directory works as well as one of
+/// its files. Compressed `.json.gz` entries are decompressed and named without their `.gz`
+/// extension, and hidden entries, such as the `._` files added by macOS, are skipped.
+pub struct ArchiveSource {
+    files: MemorySource,
+}
+
+impl ArchiveSource {
+    /// Reads the entries of a zip archive.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: The reader of the archive.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ArchiveSource, AppError>`: On success, returns the source. On failure, returns
+    ///   an `AppError` indicating the type of error that occurred, such as an invalid archive.
+    pub fn from_zip<R: Read + Seek>(reader: R) -> Result<Self, AppError> {
+        let mut archive: ZipArchive<R> = ZipArchive::new(reader)?;
+        let mut files: MemorySource = MemorySource::new();
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let entry_name: String = entry.name().to_string();
+            if let Some((name, content)) = read_entry(&entry_name, entry)? {
+                files = files.with_file(name, content);
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Reads the entries of a gzip-compressed tar archive.
+    ///
+    /// # Parameters
+    ///
+    /// - `reader`: The reader of the archive.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ArchiveSource, AppError>`: On success, returns the source. On failure, returns
+    ///   an `AppError` indicating the type of error that occurred, such as an I/O error.
+    pub fn from_tar_gz<R: Read>(reader: R) -> Result<Self, AppError> {
+        let mut archive: Archive<GzDecoder<R>> = Archive::new(GzDecoder::new(reader));
+        let mut files: MemorySource = MemorySource::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let entry_name: String = entry.path()?.to_string_lossy().to_string();
+            if let Some((name, content)) = read_entry(&entry_name, entry)? {
+                files = files.with_file(name, content);
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Opens a zip or tar.gz archive, choosing the format from its extension.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the archive, ending in `.zip`, `.tar.gz`, or `.tgz`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ArchiveSource, AppError>`: On success, returns the source. On failure, returns
+    ///   an `AppError` indicating the type of error that occurred, such as an unsupported
+    ///   extension or an invalid archive.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, AppError> {
+        let path: &Path = path.as_ref();
+        match archive_format(path) {
+            Some(ArchiveFormat::Zip) => Self::from_zip(File::open(path)?),
+            Some(ArchiveFormat::TarGz) => Self::from_tar_gz(File::open(path)?),
+            None => Err(AppError::InvalidArgument(format!(
+                "{} is not a zip or tar.gz archive",
+                path.display()
+            ))),
+        }
+    }
+}
+
+impl InputSource for ArchiveSource {
+    fn list_files(&self) -> Result<Vec<String>, AppError> {
+        self.files.list_files()
+    }
+
+    fn read_file(&self, name: &str) -> Result<String, AppError> {
+        self.files.read_file(name)
+    }
+}
+
+/// Formats of the archives a source can be read from.
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+/// Finds the format of an archive from the extension of its path.
+fn archive_format(path: &Path) -> Option<ArchiveFormat> {
+    let name: String = path.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveFormat::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveFormat::TarGz)
+    } else {
+        None
+    }
+}
+
+/// Reads an entry of an archive, returning its file name and its content, or `None` when the
+/// entry is hidden.
+fn read_entry<R: Read>(
+    entry_name: &str,
+    mut reader: R,
+) -> Result<Option<(String, String)>, AppError> {
+    let file_name: &str = entry_name.rsplit('/').next().unwrap_or(entry_name);
+    if file_name.is_empty() || file_name.starts_with('.') {
+        return Ok(None);
+    }
+
+    let content: String = if uncompressed_name(file_name) != file_name {
+        read_gzip(reader)?
+    } else if file_name.ends_with(GZIP_EXTENSION) {
+        return Ok(None);
+    } else {
+        let mut content: String = String::new();
+        reader.read_to_string(&mut content)?;
+        content
+    };
+
+    Ok(Some((uncompressed_name(file_name).to_string(), content)))
+}
+
+/// Opens the source of the captures at a path: the directory itself, a zip or tar.gz archive,
+/// or a bundle file.
+///
+/// # Parameters
+///
+/// - `path`: The path of a capture directory, of an archive, or of a bundle file.
+///
+/// # Returns
+///
+/// - `Result<Box<dyn InputSource>, AppError>`: On success, returns the source. On failure,
+///   returns an `AppError` indicating the type of error that occurred while opening it.
+pub fn open_source(path: &str) -> Result<Box<dyn InputSource>, AppError> {
+    if Path::new(path).is_dir() {
+        Ok(Box::new(DirectorySource::new(path)))
+    } else if archive_format(Path::new(path)).is_some() {
+        Ok(Box::new(ArchiveSource::open(path)?))
+    } else if Path::new(path).is_file() {
+        Ok(Box::new(BundleSource::from_file(path)?))
+    } else {
+        Ok(Box::new(DirectorySource::new(path)))
+    }
+}
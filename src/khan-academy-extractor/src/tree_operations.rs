@@ -0,0 +1,342 @@
+use crate::course_utils::index_rows;
+use crate::models::DataStruct;
+use std::collections::{HashMap, VecDeque};
+
+/// Kind of a node of the course tree, used to choose the hooks of a `CourseVisitor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// The course itself.
+    Course,
+    /// A unit of the course.
+    Unit,
+    /// A lesson of a unit.
+    Lesson,
+    /// Any other node with children, such as the topics of courses that do not follow the
+    /// unit and lesson shape.
+    Group,
+    /// A quiz, unit test, course challenge, or mastery challenge.
+    Assessment,
+    /// A content item, such as an exercise, a video, or an article.
+    Item,
+}
+
+impl NodeKind {
+    /// Finds the kind of a row from its type, and whether it has children.
+    ///
+    /// # Parameters
+    ///
+    /// - `row`: The row of the node.
+    /// - `has_children`: Whether the node has children in the tree.
+    ///
+    /// # Returns
+    ///
+    /// - `NodeKind`: The kind of the node.
+    pub fn of(row: &DataStruct, has_children: bool) -> Self {
+        match row.type_name.as_str() {
+            "Course" => NodeKind::Course,
+            "Unit" => NodeKind::Unit,
+            "Lesson" => NodeKind::Lesson,
+            "TopicQuiz" | "TopicUnitTest" | "CourseChallenge" | "MasteryChallenge" => {
+                NodeKind::Assessment
+            }
+            _ if has_children => NodeKind::Group,
+            _ => NodeKind::Item,
+        }
+    }
+}
+
+/// Node of the course tree yielded by the traversals, with the chain of its ancestors.
+#[derive(Debug, Clone)]
+pub struct TreeNode<'a> {
+    /// The index of the row of the node in the rows of the tree.
+    pub index: usize,
+    /// The row of the node.
+    pub row: &'a DataStruct,
+    /// The kind of the node.
+    pub kind: NodeKind,
+    /// The ancestors of the node, from the root of its tree down to its parent.
+    pub ancestors: Vec<&'a DataStruct>,
+}
+
+impl TreeNode<'_> {
+    /// Returns the depth of the node in the tree, the root being at depth 0.
+    pub fn depth(&self) -> usize {
+        self.ancestors.len()
+    }
+}
+
+/// Tree of the rows of a course, built from the parent of every row.
+///
+/// The rows returned by `collect_course` list the course and its descendants in the order in
+/// which the course is walked, each one pointing to its parent. The tree restores that
+/// structure so it can be walked again with a `CourseVisitor` or iterated in depth-first or
+/// breadth-first order. Rows whose parent is not among the rows, such as the rows left by
+/// `filter_rows`, become roots of their own.
+///
+/// The parent of a row is the row `collect_course` was walking when it collected it: the
+/// last row before it one level up, provided that row has the id of its parent. Content
+/// reused in several places of a course shares its id, so the parent is never looked up by id
+/// alone.
+pub struct CourseTree<'a> {
+    rows: &'a [DataStruct],
+    row_indexes: HashMap<&'a str, usize>,
+    roots: Vec<usize>,
+    children: Vec<Vec<usize>>,
+}
+
+impl<'a> CourseTree<'a> {
+    /// Builds the tree of the rows of a course.
+    ///
+    /// # Parameters
+    ///
+    /// - `rows`: The rows of the course, in course order.
+    ///
+    /// # Returns
+    ///
+    /// - `CourseTree`: The tree of the rows, keeping the course order among siblings.
+    pub fn new(rows: &'a [DataStruct]) -> Self {
+        let row_indexes: HashMap<&str, usize> = index_rows(rows);
+        let mut roots: Vec<usize> = Vec::new();
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); rows.len()];
+        // The index of the last row at every depth, down to the depth of the current row
+        let mut open_rows: Vec<Option<usize>> = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            let depth: usize = row.depth as usize;
+            let parent_index: Option<usize> = depth
+                .checked_sub(1)
+                .and_then(|parent_depth| open_rows.get(parent_depth).copied().flatten())
+                .filter(|&parent_index| {
+                    row.parent_id.as_deref() == Some(rows[parent_index].id.as_str())
+                });
+            match parent_index {
+                Some(parent_index) => children[parent_index].push(index),
+                None => roots.push(index),
+            }
+            open_rows.resize(depth, None);
+            open_rows.push(Some(index));
+        }
+
+        Self {
+            rows,
+            row_indexes,
+            roots,
+            children,
+        }
+    }
+
+    /// Returns the roots of the tree, which is only the course for a whole course.
+    pub fn roots(&self) -> Vec<&'a DataStruct> {
+        self.roots.iter().map(|&index| &self.rows[index]).collect()
+    }
+
+    /// Returns the children of a row, in course order.
+    ///
+    /// Content reused in several places of a course shares its id, so the row is the one
+    /// `index_rows` finds for the id; `child_indexes` gives the children of any row.
+    ///
+    /// # Parameters
+    ///
+    /// - `id`: The id of the row.
+    ///
+    /// # Returns
+    ///
+    /// - `Vec<&DataStruct>`: The children of the row, empty if it has none or is not in the tree.
+    pub fn children(&self, id: &str) -> Vec<&'a DataStruct> {
+        self.row_indexes
+            .get(id)
+            .map(|&index| {
+                self.children[index]
+                    .iter()
+                    .map(|&child| &self.rows[child])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the indexes of the children of a row, in course order.
+    ///
+    /// # Parameters
+    ///
+    /// - `index`: The index of the row in the rows of the tree.
+    ///
+    /// # Returns
+    ///
+    /// - `&[usize]`: The indexes of the children, empty if the row has none or is out of range.
+    pub fn child_indexes(&self, index: usize) -> &[usize] {
+        self.children.get(index).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterates over the nodes in depth-first order, each node before its children, which is
+    /// the course order.
+    pub fn depth_first(&self) -> DepthFirst<'_, 'a> {
+        DepthFirst {
+            tree: self,
+            stack: self
+                .roots
+                .iter()
+                .rev()
+                .map(|&index| (index, Vec::new()))
+                .collect(),
+        }
+    }
+
+    /// Iterates over the nodes in breadth-first order, level by level.
+    pub fn breadth_first(&self) -> BreadthFirst<'_, 'a> {
+        BreadthFirst {
+            tree: self,
+            queue: self
+                .roots
+                .iter()
+                .map(|&index| (index, Vec::new()))
+                .collect(),
+        }
+    }
+
+    /// Walks the tree in depth-first order, calling the enter hook of every node before its
+    /// children and its leave hook after them.
+    ///
+    /// # Parameters
+    ///
+    /// - `visitor`: The visitor whose hooks are called.
+    pub fn walk<V: CourseVisitor + ?Sized>(&self, visitor: &mut V) {
+        let mut ancestors: Vec<&'a DataStruct> = Vec::new();
+        for &root in &self.roots {
+            self.walk_node(root, &mut ancestors, visitor);
+        }
+    }
+
+    /// Walks a node and its descendants, keeping the chain of ancestors up to date.
+    fn walk_node<V: CourseVisitor + ?Sized>(
+        &self,
+        index: usize,
+        ancestors: &mut Vec<&'a DataStruct>,
+        visitor: &mut V,
+    ) {
+        let row: &'a DataStruct = &self.rows[index];
+        let kind: NodeKind = self.kind(index);
+        visitor.enter(kind, row, ancestors);
+        ancestors.push(row);
+        for &child in &self.children[index] {
+            self.walk_node(child, ancestors, visitor);
+        }
+        ancestors.pop();
+        visitor.leave(kind, row, ancestors);
+    }
+
+    /// Builds the node yielded for a row from the indexes of its ancestors.
+    fn node(&self, index: usize, ancestors: &[usize]) -> TreeNode<'a> {
+        TreeNode {
+            index,
+            row: &self.rows[index],
+            kind: self.kind(index),
+            ancestors: ancestors.iter().map(|&index| &self.rows[index]).collect(),
+        }
+    }
+
+    /// Finds the kind of the node of a row.
+    fn kind(&self, index: usize) -> NodeKind {
+        NodeKind::of(&self.rows[index], !self.children[index].is_empty())
+    }
+}
+
+/// Depth-first iterator over the nodes of a `CourseTree`.
+pub struct DepthFirst<'t, 'a> {
+    tree: &'t CourseTree<'a>,
+    stack: Vec<(usize, Vec<usize>)>,
+}
+
+impl<'a> Iterator for DepthFirst<'_, 'a> {
+    type Item = TreeNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, ancestors): (usize, Vec<usize>) = self.stack.pop()?;
+        let node: TreeNode<'a> = self.tree.node(index, &ancestors);
+        let mut child_ancestors: Vec<usize> = ancestors;
+        child_ancestors.push(index);
+        for &child in self.tree.children[index].iter().rev() {
+            self.stack.push((child, child_ancestors.clone()));
+        }
+
+        Some(node)
+    }
+}
+
+/// Breadth-first iterator over the nodes of a `CourseTree`.
+pub struct BreadthFirst<'t, 'a> {
+    tree: &'t CourseTree<'a>,
+    queue: VecDeque<(usize, Vec<usize>)>,
+}
+
+impl<'a> Iterator for BreadthFirst<'_, 'a> {
+    type Item = TreeNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, ancestors): (usize, Vec<usize>) = self.queue.pop_front()?;
+        let node: TreeNode<'a> = self.tree.node(index, &ancestors);
+        let mut child_ancestors: Vec<usize> = ancestors;
+        child_ancestors.push(index);
+        for &child in &self.tree.children[index] {
+            self.queue.push_back((child, child_ancestors.clone()));
+        }
+
+        Some(node)
+    }
+}
+
+/// Visitor of the course tree, with enter and leave hooks for every kind of node.
+///
+/// Every hook receives the row of the node and its ancestors, from the root down to its
+/// parent, and does nothing by default, so a visitor only implements the hooks it needs.
+/// `enter` and `leave` dispatch to the hooks of the kind of the node; they can be overridden
+/// to handle every node in the same way.
+#[allow(unused_variables)]
+pub trait CourseVisitor {
+    /// Called when the walk enters the course.
+    fn enter_course(&mut self, course: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk leaves the course, after all its descendants.
+    fn leave_course(&mut self, course: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk enters a unit.
+    fn enter_unit(&mut self, unit: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk leaves a unit, after all its descendants.
+    fn leave_unit(&mut self, unit: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk enters a lesson.
+    fn enter_lesson(&mut self, lesson: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk leaves a lesson, after all its descendants.
+    fn leave_lesson(&mut self, lesson: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk enters any other node with children.
+    fn enter_group(&mut self, group: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk leaves any other node with children, after all its descendants.
+    fn leave_group(&mut self, group: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk enters a quiz, unit test, or challenge.
+    fn enter_assessment(&mut self, assessment: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk leaves a quiz, unit test, or challenge.
+    fn leave_assessment(&mut self, assessment: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk enters a content item.
+    fn enter_item(&mut self, item: &DataStruct, ancestors: &[&DataStruct]) {}
+    /// Called when the walk leaves a content item.
+    fn leave_item(&mut self, item: &DataStruct, ancestors: &[&DataStruct]) {}
+
+    /// Called when the walk enters any node, before its children.
+    fn enter(&mut self, kind: NodeKind, node: &DataStruct, ancestors: &[&DataStruct]) {
+        match kind {
+            NodeKind::Course => self.enter_course(node, ancestors),
+            NodeKind::Unit => self.enter_unit(node, ancestors),
+            NodeKind::Lesson => self.enter_lesson(node, ancestors),
+            NodeKind::Group => self.enter_group(node, ancestors),
+            NodeKind::Assessment => self.enter_assessment(node, ancestors),
+            NodeKind::Item => self.enter_item(node, ancestors),
+        }
+    }
+
+    /// Called when the walk leaves any node, after its children.
+    fn leave(&mut self, kind: NodeKind, node: &DataStruct, ancestors: &[&DataStruct]) {
+        match kind {
+            NodeKind::Course => self.leave_course(node, ancestors),
+            NodeKind::Unit => self.leave_unit(node, ancestors),
+            NodeKind::Lesson => self.leave_lesson(node, ancestors),
+            NodeKind::Group => self.leave_group(node, ancestors),
+            NodeKind::Assessment => self.leave_assessment(node, ancestors),
+            NodeKind::Item => self.leave_item(node, ancestors),
+        }
+    }
+}
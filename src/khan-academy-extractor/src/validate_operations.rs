@@ -0,0 +1,273 @@
+use crate::course_utils::{content_items, find_quiz_index, find_unit_id, index_rows};
+use crate::error::AppError;
+use crate::extractors::{
+    extract_course_content, extract_item_progresses, extract_quiz_attempts,
+    extract_unit_test_attempts,
+};
+use crate::file_utils::{file_number, find_json_file, find_json_files};
+use crate::json_operations::collect_course;
+use crate::models::{
+    DataStruct, TopicQuizAttempt, TopicUnitTestAttempt, UnitCoverage, ValidationReport,
+};
+use crate::source_operations::InputSource;
+use std::collections::{HashMap, HashSet};
+
+/// Finds the numbers missing from a sequence that should go from 1 to its highest number.
+///
+/// # Parameters
+///
+/// - `numbers`: The numbers of the files of a capture type.
+///
+/// # Returns
+///
+/// - `Vec<u32>`: The missing numbers, in ascending order.
+pub fn sequence_gaps(numbers: &[u32]) -> Vec<u32> {
+    let highest: u32 = numbers.iter().copied().max().unwrap_or(0);
+
+    (1..=highest)
+        .filter(|number| !numbers.contains(number))
+        .collect()
+}
+
+/// Reads a required capture file, recording it as missing when it does not exist.
+fn read_required_file(
+    source: &dyn InputSource,
+    files: &[String],
+    prefix: &str,
+    suffix: &str,
+    report: &mut ValidationReport,
+) -> Result<Option<String>, AppError> {
+    match find_json_file(files, prefix, suffix) {
+        Some(file) => Ok(Some(source.read_file(file)?)),
+        None => {
+            report
+                .missing_files
+                .push(format!("{}{}.json", prefix, suffix));
+            Ok(None)
+        }
+    }
+}
+
+/// Checks that a capture set is complete before extracting it.
+///
+/// This function looks for the required files, for gaps in the numbered files, for units
+/// whose items are not covered by any `getUserInfoForTopicProgressMastery-N` file, and for
+/// quizzes and unit tests without attempt data. A quiz or unit test is considered unattempted
+/// when the `quizAndUnitTestAttemptsQuery-N` file with the same number as the unit progress
+/// file of its unit exists, since both are captured from the same unit page.
+///
+/// # Parameters
+///
+/// - `source`: The source of the capture files.
+/// - `prefix`: A string slice with the prefix of the capture files.
+///
+/// # Returns
+///
+/// - `Result<ValidationReport, AppError>`: On success, returns the problems found in the
+///   capture set. On failure, returns an `AppError` indicating the type of error that occurred,
+///   such as an I/O error or a JSON error.
+pub fn validate_capture(
+    source: &dyn InputSource,
+    prefix: &str,
+) -> Result<ValidationReport, AppError> {
+    let files: Vec<String> = source.list_files()?;
+    let mut report: ValidationReport = ValidationReport::default();
+
+    let json_content: Option<String> =
+        read_required_file(source, &files, prefix, "contentForPath", &mut report)?;
+    read_required_file(source, &files, prefix, "courseProgressQuery", &mut report)?;
+
+    let unit_files: Vec<&String> =
+        find_json_files(&files, prefix, "getUserInfoForTopicProgressMastery-");
+    let quiz_test_files: Vec<&String> =
+        find_json_files(&files, prefix, "quizAndUnitTestAttemptsQuery-");
+    for (suffix, numbered) in [
+        ("getUserInfoForTopicProgressMastery-", &unit_files),
+        ("quizAndUnitTestAttemptsQuery-", &quiz_test_files),
+    ] {
+        let numbers: Vec<u32> = numbered.iter().map(|file| file_number(file)).collect();
+        for gap in sequence_gaps(&numbers) {
+            report
+                .sequence_gaps
+                .push(format!("{}{}{}.json", prefix, suffix, gap));
+        }
+    }
+
+    let Some(json_content) = json_content else {
+        return Ok(report);
+    };
+    let rows: Vec<DataStruct> = collect_course(&extract_course_content(&json_content)?)?;
+    let row_indexes: HashMap<&str, usize> = index_rows(&rows);
+    let items: Vec<&DataStruct> = content_items(&rows);
+
+    let mut captured_keys: Vec<(u32, HashSet<String>)> = Vec::new();
+    for file in &unit_files {
+        let json_content: String = source.read_file(file)?;
+        let keys: HashSet<String> = extract_item_progresses(&json_content)?
+            .into_iter()
+            .map(|item_progress| item_progress.content.progress_key)
+            .collect();
+        captured_keys.push((file_number(file), keys));
+    }
+    let mut quiz_attempts: Vec<TopicQuizAttempt> = Vec::new();
+    let mut test_attempts: Vec<TopicUnitTestAttempt> = Vec::new();
+    for file in &quiz_test_files {
+        let json_content: String = source.read_file(file)?;
+        quiz_attempts.extend(extract_quiz_attempts(&json_content)?);
+        test_attempts.extend(extract_unit_test_attempts(&json_content)?);
+    }
+
+    for unit in rows.iter().filter(|row| row.type_name == "Unit") {
+        let in_unit = |row: &&DataStruct| -> bool {
+            find_unit_id(&rows, &row_indexes, row) == Some(unit.id.as_str())
+        };
+        let item_keys: Vec<&str> = items
+            .iter()
+            .copied()
+            .filter(in_unit)
+            .filter_map(|row| row.progress_key.as_deref())
+            .collect();
+        let covered: usize = item_keys
+            .iter()
+            .filter(|key| captured_keys.iter().any(|(_, keys)| keys.contains(**key)))
+            .count();
+        let capture_number: Option<u32> = captured_keys
+            .iter()
+            .map(|(number, keys)| {
+                let count: usize = item_keys.iter().filter(|key| keys.contains(**key)).count();
+                (count, *number)
+            })
+            .filter(|(count, _)| *count > 0)
+            .max_by_key(|(count, number)| (*count, u32::MAX - number))
+            .map(|(_, number)| number);
+        let mut needs_capture: bool = covered < item_keys.len();
+        if needs_capture {
+            report.incomplete_units.push(UnitCoverage {
+                id: unit.id.clone(),
+                title: unit.title.clone(),
+                relative_url: unit.relative_url.clone(),
+                covered: covered as u32,
+                total: item_keys.len() as u32,
+                capture_number,
+            });
+        }
+
+        for (index, row) in rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.type_name == "TopicQuiz" || row.type_name == "TopicUnitTest")
+            .filter(|(_, row)| in_unit(row))
+        {
+            let has_attempt: bool = if row.type_name == "TopicQuiz" {
+                quiz_attempts
+                    .iter()
+                    .any(|attempt| find_quiz_index(&rows, attempt) == Some(index))
+            } else {
+                test_attempts
+                    .iter()
+                    .any(|attempt| row.parent_id.as_deref() == Some(&attempt.parent_id))
+            };
+            let has_capture: bool = capture_number.is_some_and(|capture_number| {
+                quiz_test_files
+                    .iter()
+                    .any(|file| file_number(file) == capture_number)
+            });
+            let label: String = format!("{} ({}) in {}", row.title, row.relative_url, unit.title);
+            if has_attempt {
+                continue;
+            } else if has_capture {
+                report.unattempted.push(label);
+            } else {
+                report.missing_attempts.push(label);
+                needs_capture = true;
+            }
+        }
+
+        if needs_capture {
+            report.pages_to_capture.push(unit.relative_url.clone());
+        }
+    }
+
+    Ok(report)
+}
+
+/// Counts the problems of a validation report. Unattempted quizzes and unit tests are not
+/// problems, since they are clearly captured as such.
+///
+/// # Parameters
+///
+/// - `report`: A reference to the `ValidationReport` of a capture set.
+///
+/// # Returns
+///
+/// - `usize`: The number of problems found.
+pub fn count_problems(report: &ValidationReport) -> usize {
+    report.missing_files.len()
+        + report.sequence_gaps.len()
+        + report.incomplete_units.len()
+        + report.missing_attempts.len()
+}
+
+/// Prints a validation report, listing the unit pages that still need to be captured.
+///
+/// # Parameters
+///
+/// - `report`: A reference to the `ValidationReport` of a capture set.
+pub fn print_validation(report: &ValidationReport) {
+    if !report.missing_files.is_empty() {
+        println!("Missing files ({})", report.missing_files.len());
+        for file in &report.missing_files {
+            println!("  {}", file);
+        }
+    }
+    if !report.sequence_gaps.is_empty() {
+        println!(
+            "Gaps in the numbered files ({})",
+            report.sequence_gaps.len()
+        );
+        for file in &report.sequence_gaps {
+            println!("  {}", file);
+        }
+    }
+    if !report.incomplete_units.is_empty() {
+        println!(
+            "Units without complete progress ({})",
+            report.incomplete_units.len()
+        );
+        for unit in &report.incomplete_units {
+            println!(
+                "  {} ({}): {} of {} items captured",
+                unit.title, unit.relative_url, unit.covered, unit.total
+            );
+        }
+    }
+    if !report.missing_attempts.is_empty() {
+        println!(
+            "Quizzes and unit tests without attempt data ({})",
+            report.missing_attempts.len()
+        );
+        for label in &report.missing_attempts {
+            println!("  {}", label);
+        }
+    }
+    if !report.unattempted.is_empty() {
+        println!(
+            "Unattempted quizzes and unit tests ({})",
+            report.unattempted.len()
+        );
+        for label in &report.unattempted {
+            println!("  {}", label);
+        }
+    }
+    if report.pages_to_capture.is_empty() {
+        println!("All unit pages are captured");
+    } else {
+        println!(
+            "Unit pages to visit and capture ({})",
+            report.pages_to_capture.len()
+        );
+        for page in &report.pages_to_capture {
+            println!("  {}", page);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::error::AppError;
+use fs2::FileExt;
+use std::ffi::OsString;
+use std::fs::{copy, remove_file, rename, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Name of the lock file created in the output directory while a run writes to it.
+pub const LOCK_FILE_NAME: &str = ".khan-academy-extractor.lock";
+
+/// Appends a suffix to the file name of a path.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name: OsString = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+
+    path.with_file_name(file_name)
+}
+
+/// Keeps a copy of the current content of a file as its newest backup.
+///
+/// The backups are named after the file with a `.1`, `.2`, ... suffix, `.1` being the newest.
+/// The existing backups are shifted by one and the oldest one is dropped when there are
+/// already as many as requested.
+///
+/// # Parameters
+///
+/// - `path`: The path of the file whose content is backed up.
+/// - `backups`: The number of backups to keep. No backup is made when it is zero or when the
+///   file does not exist.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns an `AppError`
+///   indicating the I/O error that occurred.
+pub fn rotate_backups<P: AsRef<Path>>(path: P, backups: usize) -> Result<(), AppError> {
+    let path: &Path = path.as_ref();
+    if backups == 0 || !path.exists() {
+        return Ok(());
+    }
+
+    for number in (1..backups).rev() {
+        let backup: PathBuf = with_suffix(path, &format!(".{}", number));
+        if backup.exists() {
+            rename(&backup, with_suffix(path, &format!(".{}", number + 1)))?;
+        }
+    }
+    copy(path, with_suffix(path, ".1"))?;
+
+    Ok(())
+}
+
+/// Writes a file atomically, so a failed run never leaves it truncated.
+///
+/// The content is written to a temporary file in the same directory, flushed to disk, and
+/// then renamed over the file. The directory is flushed as well, so the rename survives a
+/// crash. The previous content is kept in the requested number of backups before being
+/// replaced. The temporary file is removed if the write fails.
+///
+/// # Parameters
+///
+/// - `path`: The path of the file to be written.
+/// - `backups`: The number of backups of the previous content to keep.
+/// - `write`: A function that writes the content to the temporary file at the given path.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns the `AppError`
+///   of the write function, or the I/O error that occurred while replacing the file.
+pub fn write_file_atomically<P, F>(path: P, backups: usize, write: F) -> Result<(), AppError>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&Path) -> Result<(), AppError>,
+{
+    let path: &Path = path.as_ref();
+    let temporary: PathBuf = with_suffix(path, &format!(".{}.tmp", process::id()));
+
+    let result: Result<(), AppError> = write(&temporary)
+        .and_then(|_| Ok(File::open(&temporary)?.sync_all()?))
+        .and_then(|_| rotate_backups(path, backups))
+        .and_then(|_| Ok(rename(&temporary, path)?));
+    if result.is_err() {
+        let _ = remove_file(&temporary);
+        return result;
+    }
+
+    match path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => sync_directory(directory),
+        _ => sync_directory(Path::new(".")),
+    }
+}
+
+/// Flushes the entries of a directory to disk, such as a file renamed into it.
+///
+/// Directories cannot be opened as files on every platform, so this is only done on Unix.
+///
+/// # Parameters
+///
+/// - `directory`: The directory to be flushed.
+///
+/// # Returns
+///
+/// - `Result<(), AppError>`: On success, returns `Ok(())`. On failure, returns the I/O error
+///   that occurred.
+#[cfg(unix)]
+fn sync_directory(directory: &Path) -> Result<(), AppError> {
+    Ok(File::open(directory)?.sync_all()?)
+}
+
+#[cfg(not(unix))]
+fn sync_directory(_directory: &Path) -> Result<(), AppError> {
+    Ok(())
+}
+
+/// Locks a directory so that two runs cannot write their outputs to it at the same time.
+///
+/// The lock is held on a lock file in the directory until the returned file is dropped or
+/// the process exits, even if it crashes. The lock file is left in place, since removing it
+/// would let a run waiting on the removed file and a new run hold the lock together; the
+/// directory listings skip it instead.
+///
+/// # Parameters
+///
+/// - `directory`: The directory to be locked.
+///
+/// # Returns
+///
+/// - `Result<File, AppError>`: On success, returns the lock file, which holds the lock. On
+///   failure, returns an `AppError::Locked` if another run holds the lock, or the I/O error
+///   that occurred.
+pub fn lock_directory<P: AsRef<Path>>(directory: P) -> Result<File, AppError> {
+    let lock_path: PathBuf = directory.as_ref().join(LOCK_FILE_NAME);
+    let lock_file: File = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    lock_file
+        .try_lock_exclusive()
+        .map_err(|_| AppError::Locked(lock_path.display().to_string()))?;
+
+    Ok(lock_file)
+}
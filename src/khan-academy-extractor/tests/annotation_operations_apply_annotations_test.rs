@@ -0,0 +1,158 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use chrono::NaiveDate;
+use khan_academy_extractor::annotation_operations::{
+    annotate_reviews, apply_annotations, filter_rows, parse_annotations, Annotations,
+};
+use khan_academy_extractor::models::{DataStruct, ReviewItem};
+
+fn row(id: &str, slug: &str, parent_id: Option<&str>) -> DataStruct {
+    DataStruct {
+        id: id.to_string(),
+        type_name: "Exercise".to_string(),
+        order: 1,
+        title: id.to_string(),
+        slug: slug.to_string(),
+        relative_url: format!("/{}", slug),
+        parent_id: parent_id.map(|parent_id| parent_id.to_string()),
+        path: slug.to_string(),
+        ..Default::default()
+    }
+}
+
+fn course() -> Vec<DataStruct> {
+    vec![
+        row("u1", "unit-1", None),
+        row("e1", "exercise-1", Some("u1")),
+        row("u2", "unit-2", None),
+        row("e2", "exercise-2", Some("u2")),
+    ]
+}
+
+#[test]
+fn test_apply_annotations_toml_by_id_and_slug() {
+    let annotations: Annotations = parse_annotations(
+        r#"
+[e1]
+tags = ["hard", "exam"]
+notes = "Redo the last part"
+priority = 1
+due = "2024-05-01"
+
+[exercise-2]
+skip = true
+
+[missing]
+notes = "Gone"
+"#,
+        "toml",
+    )
+    .unwrap();
+    let mut rows: Vec<DataStruct> = course();
+
+    let unmatched: Vec<String> = apply_annotations(&mut rows, &annotations).unwrap();
+
+    custom_assert_eq!(unmatched, vec!["missing".to_string()]);
+    custom_assert_eq!(rows[1].tags.as_deref(), Some("hard;exam"));
+    custom_assert_eq!(rows[1].notes.as_deref(), Some("Redo the last part"));
+    custom_assert_eq!(rows[1].priority, Some(1));
+    custom_assert_eq!(rows[1].due, NaiveDate::from_ymd_opt(2024, 5, 1));
+    custom_assert_eq!(rows[3].skip, Some(true));
+    custom_assert_eq!(rows[0].skip, None);
+}
+
+#[test]
+fn test_apply_annotations_csv() {
+    let annotations: Annotations = parse_annotations(
+        "key,tags,notes,priority,skip,due\ne2,review; exam,,2,false,\nunit-1,,,,true,\n",
+        "csv",
+    )
+    .unwrap();
+    let mut rows: Vec<DataStruct> = course();
+
+    let unmatched: Vec<String> = apply_annotations(&mut rows, &annotations).unwrap();
+
+    assert!(unmatched.is_empty());
+    custom_assert_eq!(rows[3].tags.as_deref(), Some("review;exam"));
+    custom_assert_eq!(rows[3].priority, Some(2));
+    custom_assert_eq!(rows[3].skip, Some(false));
+    custom_assert_eq!(rows[0].skip, Some(true));
+}
+
+#[test]
+fn test_apply_annotations_invalid_due_date() {
+    let annotations: Annotations =
+        parse_annotations(r#"{"e1": {"due": "next week"}}"#, "json").unwrap();
+    let mut rows: Vec<DataStruct> = course();
+
+    assert!(apply_annotations(&mut rows, &annotations).is_err());
+}
+
+#[test]
+fn test_filter_rows_skips_content_of_skipped_rows() {
+    let annotations: Annotations = parse_annotations(
+        r#"{"u1": {"skip": true}, "e2": {"tags": ["exam"]}}"#,
+        "json",
+    )
+    .unwrap();
+    let mut rows: Vec<DataStruct> = course();
+    apply_annotations(&mut rows, &annotations).unwrap();
+
+    let kept: Vec<String> = filter_rows(rows, None, true)
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+    custom_assert_eq!(kept, vec!["u2".to_string(), "e2".to_string()]);
+
+    let mut rows: Vec<DataStruct> = course();
+    apply_annotations(&mut rows, &annotations).unwrap();
+    let tagged: Vec<String> = filter_rows(rows, Some("exam"), false)
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+    custom_assert_eq!(tagged, vec!["e2".to_string()]);
+}
+
+fn review_item(id: &str, due_date: NaiveDate) -> ReviewItem {
+    ReviewItem {
+        id: id.to_string(),
+        title: id.to_string(),
+        parent_title: None,
+        relative_url: format!("/{}", id),
+        status: None,
+        accuracy: None,
+        review_box: 1,
+        completed_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        due_date,
+    }
+}
+
+#[test]
+fn test_annotate_reviews_skips_content_of_skipped_rows() {
+    let annotations: Annotations = parse_annotations(
+        r#"{"u1": {"skip": true}, "e2": {"due": "2024-03-02"}}"#,
+        "json",
+    )
+    .unwrap();
+    let mut rows: Vec<DataStruct> = course();
+    apply_annotations(&mut rows, &annotations).unwrap();
+
+    let reviews: Vec<(String, NaiveDate)> = annotate_reviews(
+        vec![
+            review_item("e1", NaiveDate::from_ymd_opt(2024, 3, 5).unwrap()),
+            review_item("e2", NaiveDate::from_ymd_opt(2024, 3, 9).unwrap()),
+        ],
+        &rows,
+    )
+    .into_iter()
+    .map(|item| (item.id, item.due_date))
+    .collect();
+    custom_assert_eq!(
+        reviews,
+        vec![(
+            "e2".to_string(),
+            NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()
+        )]
+    );
+}
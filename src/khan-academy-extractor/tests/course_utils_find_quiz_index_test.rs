@@ -0,0 +1,71 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::course_utils::find_quiz_index;
+use khan_academy_extractor::models::{DataStruct, TopicQuizAttempt};
+
+fn row(id: &str, type_name: &str, parent_topic: &str) -> DataStruct {
+    DataStruct {
+        id: id.to_string(),
+        type_name: type_name.to_string(),
+        order: 1,
+        title: id.to_string(),
+        slug: id.to_string(),
+        relative_url: format!("/{}", id),
+        parent_topic: Some(parent_topic.to_string()),
+        parent_id: Some(parent_topic.to_string()),
+        parent_type: Some("Unit".to_string()),
+        depth: 2,
+        path: format!("course/unit/{}", id),
+        ..Default::default()
+    }
+}
+
+fn attempt(parent_id: &str, position: Option<u32>) -> TopicQuizAttempt {
+    TopicQuizAttempt {
+        type_name: "TopicQuizAttempt".to_string(),
+        is_completed: true,
+        num_attempted: 10,
+        num_correct: 8,
+        position_key: "key".to_string(),
+        completed_date: None,
+        parent_id: parent_id.to_string(),
+        position,
+    }
+}
+
+fn course() -> Vec<DataStruct> {
+    vec![
+        row("lesson-1", "Lesson", "u1"),
+        row("quiz-1", "TopicQuiz", "u1"),
+        row("lesson-2", "Lesson", "u1"),
+        row("quiz-2", "TopicQuiz", "u1"),
+        row("quiz-3", "TopicQuiz", "u1"),
+        row("quiz-4", "TopicQuiz", "u2"),
+    ]
+}
+
+#[test]
+fn test_find_quiz_index_uses_position() {
+    let rows: Vec<DataStruct> = course();
+
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u1", Some(1))), Some(1));
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u1", Some(2))), Some(3));
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u1", Some(3))), Some(4));
+}
+
+#[test]
+fn test_find_quiz_index_single_quiz_without_position() {
+    let rows: Vec<DataStruct> = course();
+
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u2", None)), Some(5));
+}
+
+#[test]
+fn test_find_quiz_index_unmappable() {
+    let rows: Vec<DataStruct> = course();
+
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u1", None)), None);
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u1", Some(4))), None);
+    custom_assert_eq!(find_quiz_index(&rows, &attempt("u3", Some(1))), None);
+}
@@ -0,0 +1,19 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::csv_operations::write_course_csv;
+use khan_academy_extractor::models::DataStruct;
+
+#[test]
+fn test_write_course_csv_without_rows_writes_the_header() {
+    let mut empty: Vec<u8> = Vec::new();
+    write_course_csv(&mut empty, &[]).unwrap();
+    let mut one_row: Vec<u8> = Vec::new();
+    write_course_csv(&mut one_row, &[DataStruct::default()]).unwrap();
+
+    let empty: String = String::from_utf8(empty).unwrap();
+    let one_row: String = String::from_utf8(one_row).unwrap();
+    custom_assert_eq!(empty.starts_with("id,"), true);
+    custom_assert_eq!(empty.lines().count(), 1);
+    custom_assert_eq!(one_row.lines().next(), empty.lines().next());
+}
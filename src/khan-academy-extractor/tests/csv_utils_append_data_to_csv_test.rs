@@ -33,6 +33,16 @@ fn test_append_data_to_csv_success() {
         num_attempted: Some("5".to_string()),
         num_correct: Some("4".to_string()),
         num_incorrect: Some("1".to_string()),
+        depth: 3,
+        path: "course/unit/parent-slug/test-slug".to_string(),
+        best_score: Some("80".to_string()),
+        latest_score: Some("80".to_string()),
+        first_score: Some("60".to_string()),
+        tags: None,
+        notes: None,
+        priority: None,
+        skip: None,
+        due: None,
     };
 
     let result = append_data_to_csv(&data, &mut writer);
@@ -40,6 +50,6 @@ fn test_append_data_to_csv_success() {
     writer.flush().unwrap();
 
     let content = read_to_string(temp_file.path()).unwrap();
-    let expected_content = "id,typeName,order,title,slug,relativeUrl,progressKey,parentTopic,parentId,parentType,parentTitle,parentSlug,parentRelativeUrl,percentage,pointsEarned,status,completionStatus,numAttempted,numCorrect,numIncorrect\ntest_id,TestType,1,Test Title,test-slug,/test/url,test_progress,parent_topic,parent_id,ParentType,Parent Title,parent-slug,/parent/url,50,100,Completed,Finished,5,4,1\n";
+    let expected_content = "id,typeName,order,title,slug,relativeUrl,progressKey,parentTopic,parentId,parentType,parentTitle,parentSlug,parentRelativeUrl,percentage,pointsEarned,status,completionStatus,numAttempted,numCorrect,numIncorrect,depth,path,bestScore,latestScore,firstScore,annotationTags,annotationNotes,annotationPriority,annotationSkip,annotationDue\ntest_id,TestType,1,Test Title,test-slug,/test/url,test_progress,parent_topic,parent_id,ParentType,Parent Title,parent-slug,/parent/url,50,100,Completed,Finished,5,4,1,3,course/unit/parent-slug/test-slug,80,80,60,,,,,\n";
     custom_assert_eq!(content, expected_content);
 }
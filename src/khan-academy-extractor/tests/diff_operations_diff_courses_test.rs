@@ -0,0 +1,172 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::diff_operations::{diff_courses, match_nodes};
+use khan_academy_extractor::json_operations::collect_course;
+use khan_academy_extractor::models::{CurriculumChange, DataStruct, NodeMapping};
+use serde_json::{from_str, Value};
+
+const OLD_COURSE: &str = r#"{
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1",
+         "allOrderedChildren": [
+            {"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1", "slug": "lesson-1",
+             "relativeUrl": "/math/unit-1/lesson-1",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-1/e/exercise-1"},
+                {"id": "v1", "__typename": "Video", "translatedTitle": "Video 1",
+                 "slug": "video-1", "relativeUrl": "/math/unit-1/lesson-1/v/video-1"}
+             ]},
+            {"id": "l2", "__typename": "Lesson", "translatedTitle": "Lesson 2", "slug": "lesson-2",
+             "relativeUrl": "/math/unit-1/lesson-2",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-2/e/exercise-1"},
+                {"id": "e2", "__typename": "Exercise", "translatedTitle": "Exercise 2",
+                 "slug": "exercise-2", "relativeUrl": "/math/unit-1/lesson-2/e/exercise-2"}
+             ]}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/math/unit-2",
+         "allOrderedChildren": [
+            {"id": "a1", "__typename": "Article", "translatedTitle": "Article 1",
+             "slug": "article-1", "relativeUrl": "/math/unit-2/a/article-1"},
+            {"id": "x1", "__typename": "Article", "translatedTitle": "Old article",
+             "slug": "old-article", "relativeUrl": "/math/unit-2/a/old-article"}
+         ]}
+    ]
+}"#;
+
+// The exercise listed in both lessons is retitled and reordered in the first one, the
+// second exercise moves to the second unit, an exercise is added and an article removed
+const NEW_COURSE: &str = r#"{
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1",
+         "allOrderedChildren": [
+            {"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1", "slug": "lesson-1",
+             "relativeUrl": "/math/unit-1/lesson-1",
+             "curatedChildren": [
+                {"id": "v1", "__typename": "Video", "translatedTitle": "Video 1",
+                 "slug": "video-1", "relativeUrl": "/math/unit-1/lesson-1/v/video-1"},
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise One",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-1/e/exercise-1"}
+             ]},
+            {"id": "l2", "__typename": "Lesson", "translatedTitle": "Lesson 2", "slug": "lesson-2",
+             "relativeUrl": "/math/unit-1/lesson-2",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise One",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-2/e/exercise-1"},
+                {"id": "e3", "__typename": "Exercise", "translatedTitle": "Exercise 3",
+                 "slug": "exercise-3", "relativeUrl": "/math/unit-1/lesson-2/e/exercise-3"}
+             ]}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/math/unit-2",
+         "allOrderedChildren": [
+            {"id": "a1", "__typename": "Article", "translatedTitle": "Article 1",
+             "slug": "article-1", "relativeUrl": "/math/unit-2/a/article-1"},
+            {"id": "e2", "__typename": "Exercise", "translatedTitle": "Exercise 2",
+             "slug": "exercise-2", "relativeUrl": "/math/unit-2/e/exercise-2"}
+         ]}
+    ]
+}"#;
+
+fn rows(course: &str) -> Vec<DataStruct> {
+    let course: Value = from_str(course).unwrap();
+    collect_course(&course).unwrap()
+}
+
+fn summary(changes: &[CurriculumChange]) -> Vec<String> {
+    changes
+        .iter()
+        .map(|change| format!("{} {} {}", change.change, change.title, change.detail))
+        .collect()
+}
+
+#[test]
+fn test_diff_courses() {
+    let old_rows: Vec<DataStruct> = rows(OLD_COURSE);
+    let new_rows: Vec<DataStruct> = rows(NEW_COURSE);
+    let mappings: Vec<NodeMapping> = match_nodes(&old_rows, &new_rows);
+    let changes: Vec<CurriculumChange> = diff_courses(&old_rows, &new_rows, &mappings);
+
+    custom_assert_eq!(
+        summary(&changes),
+        vec![
+            "removed Old article from Unit 2",
+            "reordered Exercise One from position 1 to 2",
+            "retitled Exercise One from \"Exercise 1\"",
+            "reordered Video 1 from position 2 to 1",
+            "retitled Exercise One from \"Exercise 1\"",
+            "moved Exercise 2 from Lesson 2 to Unit 2",
+            "added Exercise 3 to Lesson 2",
+        ]
+    );
+}
+
+#[test]
+fn test_diff_courses_without_changes() {
+    let old_rows: Vec<DataStruct> = rows(OLD_COURSE);
+    let new_rows: Vec<DataStruct> = rows(OLD_COURSE);
+    let mappings: Vec<NodeMapping> = match_nodes(&old_rows, &new_rows);
+
+    custom_assert_eq!(mappings.len(), old_rows.len());
+    custom_assert_eq!(diff_courses(&old_rows, &new_rows, &mappings).len(), 0);
+}
+
+#[test]
+fn test_match_nodes_pairs_reused_content_by_parent() {
+    let old_rows: Vec<DataStruct> = rows(OLD_COURSE);
+    // The lessons swap, so the first occurrence of the exercise is now the one of the second
+    // lesson, and the article gets a new id
+    let lesson_1: usize = OLD_COURSE.find(r#"{"id": "l1""#).unwrap();
+    let lesson_2: usize = OLD_COURSE.find(r#"{"id": "l2""#).unwrap();
+    let lesson_2_end: usize = lesson_2 + OLD_COURSE[lesson_2..].find("]}").unwrap() + 2;
+    let new_course: String = format!(
+        "{}{},{}{}",
+        &OLD_COURSE[..lesson_1],
+        &OLD_COURSE[lesson_2..lesson_2_end],
+        OLD_COURSE[lesson_1..lesson_2]
+            .trim_end()
+            .trim_end_matches(','),
+        &OLD_COURSE[lesson_2_end..]
+    )
+    .replace(r#""id": "a1""#, r#""id": "a9""#);
+    let new_rows: Vec<DataStruct> = rows(&new_course);
+    let mappings: Vec<NodeMapping> = match_nodes(&old_rows, &new_rows);
+
+    let reused: Vec<(Option<&str>, Option<&str>)> = mappings
+        .iter()
+        .filter(|mapping| mapping.old_id == "e1")
+        .map(|mapping| {
+            (
+                old_rows[mapping.old_index].parent_id.as_deref(),
+                new_rows[mapping.new_index].parent_id.as_deref(),
+            )
+        })
+        .collect();
+    custom_assert_eq!(
+        reused,
+        vec![(Some("l1"), Some("l1")), (Some("l2"), Some("l2"))]
+    );
+    let article: &NodeMapping = mappings
+        .iter()
+        .find(|mapping| mapping.old_id == "a1")
+        .unwrap();
+    custom_assert_eq!(article.new_id.as_str(), "a9");
+    custom_assert_eq!(article.matched_by.as_str(), "slug");
+    custom_assert_eq!(
+        summary(&diff_courses(&old_rows, &new_rows, &mappings)),
+        vec![
+            "reordered Lesson 1 from position 1 to 2",
+            "reordered Lesson 2 from position 2 to 1",
+        ]
+    );
+}
@@ -0,0 +1,286 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::extractor::Extractor;
+use khan_academy_extractor::models::{DataStruct, EnrichedCourse};
+use std::fs::write;
+use tempfile::tempdir;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1",
+    "unitChildren": [{
+        "id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+        "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+        "allOrderedChildren": [
+            {"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1", "slug": "lesson-1",
+             "relativeUrl": "/math/unit-1/lesson-1",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-1/e/exercise-1",
+                 "progressKey": "ke1"}
+             ]},
+            {"id": "q1", "__typename": "TopicQuiz", "translatedTitle": "Quiz 1", "slug": "quiz-1",
+             "relativeUrl": "/math/unit-1/quiz/quiz-1", "progressKey": "kq1",
+             "parentTopic": {"id": "u1"}},
+            {"id": "q2", "__typename": "TopicQuiz", "translatedTitle": "Quiz 2", "slug": "quiz-2",
+             "relativeUrl": "/math/unit-1/quiz/quiz-2", "progressKey": "kq2",
+             "parentTopic": {"id": "u1"}}
+        ]
+    }]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {
+    "currentMasteryV2": {"percentage": 40, "pointsEarned": 900},
+    "masteryMap": [{"progressKey": "ke1", "status": "MASTERED"}],
+    "unitProgresses": [{"unitId": "u1", "currentMasteryV2": {"percentage": 70, "pointsEarned": 500}}]
+}}}}"#;
+
+const UNIT_PROGRESS: &str = r#"{"data": {"user": {"contentItemProgresses": [{
+    "__typename": "BasicContentItemProgress",
+    "bestScore": {"completedDate": "2024-03-01T10:00:00Z", "numAttempted": 4, "numCorrect": 3},
+    "completionStatus": "COMPLETE",
+    "content": {"__typename": "Exercise", "id": "e1", "progressKey": "ke1"}
+}]}}}"#;
+
+const QUIZ_TEST_PROGRESS: &str = r#"{"data": {"user": {"latestQuizAttempts": [
+    {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 10,
+     "numCorrect": 6, "positionKey": "CgJ1MRAB"},
+    {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 10,
+     "numCorrect": 9, "positionKey": "CgJ1MRAC"}
+]}}}"#;
+
+fn find<'a>(course: &'a EnrichedCourse, id: &str) -> &'a DataStruct {
+    course.rows.iter().find(|row| row.id == id).unwrap()
+}
+
+#[test]
+fn test_extract_from_strings() {
+    let course: EnrichedCourse = Extractor::from_strings(
+        CONTENT_FOR_PATH,
+        COURSE_PROGRESS,
+        vec![UNIT_PROGRESS.to_string()],
+        vec![QUIZ_TEST_PROGRESS.to_string()],
+    )
+    .extract()
+    .unwrap();
+
+    custom_assert_eq!(course.rows.len(), 6);
+    custom_assert_eq!(find(&course, "c1").percentage.as_deref(), Some("40"));
+    custom_assert_eq!(find(&course, "e1").status.as_deref(), Some("MASTERED"));
+    custom_assert_eq!(find(&course, "e1").num_correct.as_deref(), Some("3"));
+    custom_assert_eq!(find(&course, "q1").best_score.as_deref(), Some("60"));
+    custom_assert_eq!(find(&course, "q2").best_score.as_deref(), Some("90"));
+    custom_assert_eq!(course.attempts.len(), 2);
+    assert!(course.unmapped_attempts.is_empty());
+}
+
+#[test]
+fn test_extract_from_dir() {
+    let directory = tempdir().unwrap();
+    write(
+        directory.path().join("contentForPath.json"),
+        CONTENT_FOR_PATH,
+    )
+    .unwrap();
+    write(
+        directory.path().join("courseProgressQuery.json"),
+        COURSE_PROGRESS,
+    )
+    .unwrap();
+    write(
+        directory
+            .path()
+            .join("getUserInfoForTopicProgressMastery-1.json"),
+        UNIT_PROGRESS,
+    )
+    .unwrap();
+    write(
+        directory.path().join("quizAndUnitTestAttemptsQuery-1.json"),
+        QUIZ_TEST_PROGRESS,
+    )
+    .unwrap();
+
+    let course: EnrichedCourse = Extractor::from_dir(directory.path().to_str().unwrap(), "")
+        .unwrap()
+        .extract()
+        .unwrap();
+
+    custom_assert_eq!(course.rows.len(), 6);
+    custom_assert_eq!(find(&course, "q2").latest_score.as_deref(), Some("90"));
+}
+
+#[test]
+fn test_extract_from_dir_missing_capture() {
+    let directory = tempdir().unwrap();
+
+    assert!(Extractor::from_dir(directory.path().to_str().unwrap(), "").is_err());
+}
+
+/// Runs the extraction of the valid course with malformed progress captures, and returns
+/// the error message.
+fn extract_error(unit_progress: &str, quiz_test_progress: &str) -> String {
+    Extractor::from_strings(
+        CONTENT_FOR_PATH,
+        COURSE_PROGRESS,
+        vec![unit_progress.to_string()],
+        vec![quiz_test_progress.to_string()],
+    )
+    .extract()
+    .unwrap_err()
+    .to_string()
+}
+
+#[test]
+fn test_extract_malformed_captures() {
+    custom_assert_eq!(
+        extract_error("{}", QUIZ_TEST_PROGRESS),
+        "Missing field: data".to_string()
+    );
+    custom_assert_eq!(
+        extract_error(UNIT_PROGRESS, "not json").starts_with("JSON error"),
+        true
+    );
+    // "YWJj" decodes to "abc", which holds no parent id
+    custom_assert_eq!(
+        extract_error(
+            UNIT_PROGRESS,
+            r#"{"data": {"user": {"latestQuizAttempts": [{"__typename": "TopicQuizAttempt",
+                "isCompleted": true, "numAttempted": 1, "numCorrect": 1, "positionKey": "YWJj"}]}}}"#
+        ),
+        "Missing field: parent id in positionKey YWJj".to_string()
+    );
+    custom_assert_eq!(
+        extract_error(
+            UNIT_PROGRESS,
+            r#"{"data": {"user": {"latestUnitTestAttempts": [{"__typename": "TopicUnitTestAttempt",
+                "id": "YWJj", "isCompleted": true, "numAttempted": 1, "numCorrect": 1}]}}}"#
+        ),
+        "Missing field: parent id in unit test attempt id YWJj".to_string()
+    );
+}
+
+#[test]
+fn test_extract_inconsistent_counts() {
+    // More correct answers than attempted ones must not underflow
+    let course: EnrichedCourse = Extractor::from_strings(
+        CONTENT_FOR_PATH,
+        COURSE_PROGRESS,
+        vec![UNIT_PROGRESS.replace("\"numCorrect\": 3", "\"numCorrect\": 7")],
+        vec![QUIZ_TEST_PROGRESS.replace("\"numCorrect\": 9", "\"numCorrect\": 12")],
+    )
+    .extract()
+    .unwrap();
+
+    custom_assert_eq!(
+        find(&course, "e1").num_incorrect.clone(),
+        Some("0".to_string())
+    );
+    custom_assert_eq!(
+        find(&course, "q2").num_incorrect.clone(),
+        Some("0".to_string())
+    );
+}
+
+/// Returns the course with a unit test, extracted with the given attempt captures.
+fn extract_attempts(quiz_test_progress: &[&str]) -> EnrichedCourse {
+    let content_for_path: String = CONTENT_FOR_PATH.replace(
+        r#"{"id": "q2""#,
+        r#"{"id": "t1", "__typename": "TopicUnitTest", "translatedTitle": "Unit test",
+             "slug": "test", "relativeUrl": "/math/unit-1/test", "progressKey": "kt1"},
+            {"id": "q2""#,
+    );
+    Extractor::from_strings(
+        &content_for_path,
+        COURSE_PROGRESS,
+        vec![UNIT_PROGRESS.to_string()],
+        quiz_test_progress
+            .iter()
+            .map(|content| content.to_string())
+            .collect(),
+    )
+    .extract()
+    .unwrap()
+}
+
+/// Returns the attempt number, score, and completion of the attempts at a row.
+fn history(course: &EnrichedCourse, id: &str) -> Vec<(u32, u32, bool)> {
+    course
+        .attempts
+        .iter()
+        .filter(|attempt| attempt.content_id == id)
+        .map(|attempt| (attempt.attempt_number, attempt.score, attempt.is_completed))
+        .collect()
+}
+
+/// Returns the first, latest, and best score and the completion status of a row.
+fn scores<'a>(course: &'a EnrichedCourse, id: &str) -> Vec<Option<&'a str>> {
+    let row: &DataStruct = find(course, id);
+    vec![
+        row.first_score.as_deref(),
+        row.latest_score.as_deref(),
+        row.best_score.as_deref(),
+        row.completion_status.as_deref(),
+    ]
+}
+
+#[test]
+fn test_extract_attempt_seen_in_progress_then_completed() {
+    // The attempt `A` is captured in progress, then completed in a later capture
+    let course: EnrichedCourse = extract_attempts(&[
+        r#"{"data": {"user": {"latestUnitTestAttempts": [
+            {"__typename": "TopicUnitTestAttempt", "id": "VW5pdFRlc3Q6dTEMQQ==",
+             "isCompleted": false, "numAttempted": 5, "numCorrect": 4}
+        ]}}}"#,
+        r#"{"data": {"user": {"latestUnitTestAttempts": [
+            {"__typename": "TopicUnitTestAttempt", "id": "VW5pdFRlc3Q6dTEMQQ==",
+             "isCompleted": true, "numAttempted": 20, "numCorrect": 18,
+             "completedDate": "2024-03-03T10:00:00Z"},
+            {"__typename": "TopicUnitTestAttempt", "id": "VW5pdFRlc3Q6dTEMQg==",
+             "isCompleted": true, "numAttempted": 20, "numCorrect": 10,
+             "completedDate": "2024-03-01T10:00:00Z"},
+            {"__typename": "TopicUnitTestAttempt", "id": "VW5pdFRlc3Q6dTEMQg==",
+             "isCompleted": true, "numAttempted": 20, "numCorrect": 10,
+             "completedDate": "2024-03-01T10:00:00Z"}
+        ]}}}"#,
+    ]);
+
+    custom_assert_eq!(history(&course, "t1"), vec![(1, 50, true), (2, 90, true)]);
+    custom_assert_eq!(
+        scores(&course, "t1"),
+        vec![Some("50"), Some("90"), Some("90"), Some("COMPLETE")]
+    );
+}
+
+#[test]
+fn test_extract_latest_attempt_with_missing_dates() {
+    let course: EnrichedCourse = extract_attempts(&[r#"{"data": {"user": {"latestQuizAttempts": [
+        {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 10,
+         "numCorrect": 9, "positionKey": "CgJ1MRAC", "completedDate": "2024-03-05T10:00:00Z"},
+        {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 10,
+         "numCorrect": 6, "positionKey": "CgJ1MRAC"},
+        {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 10,
+         "numCorrect": 5, "positionKey": "CgJ1MRAB", "completedDate": "2024-03-01T10:00:00Z"},
+        {"__typename": "TopicQuizAttempt", "isCompleted": false, "numAttempted": 9,
+         "numCorrect": 9, "positionKey": "CgJ1MRAB"},
+        {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 10,
+         "numCorrect": 8, "positionKey": "CgJ1MRAB", "completedDate": "2024-03-02T10:00:00Z"}
+    ]}}}"#]);
+
+    // The undated attempt is numbered first, and the dated one stays the latest
+    custom_assert_eq!(history(&course, "q2"), vec![(1, 60, true), (2, 90, true)]);
+    custom_assert_eq!(
+        scores(&course, "q2"),
+        vec![Some("60"), Some("90"), Some("90"), Some("COMPLETE")]
+    );
+    // The attempt in progress, with more correct answers than any completed attempt so it
+    // cannot be one of them, is the latest, after the completed ones by date
+    custom_assert_eq!(
+        history(&course, "q1"),
+        vec![(1, 50, true), (2, 80, true), (3, 100, false)]
+    );
+    custom_assert_eq!(
+        scores(&course, "q1"),
+        vec![Some("50"), Some("100"), Some("100"), Some("UNCOMPLETED")]
+    );
+}
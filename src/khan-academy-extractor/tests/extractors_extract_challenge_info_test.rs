@@ -0,0 +1,210 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::extractor::Extractor;
+use khan_academy_extractor::extractors::{
+    extract_challenge_attempts, extract_challenge_info, extract_info,
+};
+use khan_academy_extractor::models::{ChallengeAttempt, DataStruct, EnrichedCourse};
+use serde_json::{json, Value};
+
+// No capture with a challenge is available, so the fixtures follow the field names of
+// `CHALLENGE_FIELDS` and `CHALLENGE_ATTEMPT_POINTERS`. The course challenge is captured with
+// a title and URL, the mastery challenge with its id only.
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1",
+    "courseChallenge": {"id": "cc1", "translatedTitle": "Course challenge",
+                        "relativeUrl": "/math/test/course-challenge"},
+    "unitChildren": [{
+        "id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+        "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+        "masteryChallenge": {"id": "mc1"},
+        "allOrderedChildren": [
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/math/unit-1/e/exercise-1", "progressKey": "ke1"}
+        ]
+    }]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {
+    "currentMasteryV2": {"percentage": 40, "pointsEarned": 900},
+    "masteryMap": [],
+    "unitProgresses": []
+}}}}"#;
+
+// The attempt ids hold the type and parent of the challenge, like the unit test attempt ids:
+// "CourseChallenge:c1\x0cA", "MasteryChallenge:u1\x0cB" and "MasteryChallenge:u1\x0cC"
+const QUIZ_TEST_PROGRESS: &str = r#"{"data": {"user": {
+    "latestCourseChallengeAttempts": [
+        {"__typename": "CourseChallengeAttempt", "id": "Q291cnNlQ2hhbGxlbmdlOmMxDEE=",
+         "isCompleted": true, "numAttempted": 10, "numCorrect": 8,
+         "completedDate": "2024-03-01T10:00:00Z"}
+    ],
+    "latestMasteryChallengeAttempts": [
+        {"__typename": "MasteryChallengeAttempt", "id": "TWFzdGVyeUNoYWxsZW5nZTp1MQxC",
+         "isCompleted": true, "numAttempted": 6, "numCorrect": 3,
+         "completedDate": "2024-03-02T10:00:00Z"},
+        {"__typename": "MasteryChallengeAttempt", "id": "not-a-key",
+         "isCompleted": false, "numAttempted": 0, "numCorrect": 0}
+    ]
+}}}"#;
+
+fn parent() -> DataStruct {
+    let unit: Value = json!({
+        "id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+        "relativeUrl": "/math/unit-1"
+    });
+    extract_info(&unit, None, 1).unwrap()
+}
+
+#[test]
+fn test_extract_challenge_info_keeps_captured_fields() {
+    let challenge: Value = json!({
+        "id": "mc1", "__typename": "UnitMasteryChallenge", "translatedTitle": "Unit challenge",
+        "slug": "unit-challenge", "relativeUrl": "/math/unit-1/challenge"
+    });
+    let info: DataStruct =
+        extract_challenge_info(&challenge, &parent(), "MasteryChallenge", 2).unwrap();
+
+    custom_assert_eq!(info.type_name.as_str(), "UnitMasteryChallenge");
+    custom_assert_eq!(info.title.as_str(), "Unit challenge");
+    custom_assert_eq!(info.slug.as_str(), "unit-challenge");
+    custom_assert_eq!(info.relative_url.as_str(), "/math/unit-1/challenge");
+    custom_assert_eq!(info.order, 2);
+    custom_assert_eq!(info.parent_id.as_deref(), Some("u1"));
+}
+
+#[test]
+fn test_extract_challenge_info_does_not_make_up_fields() {
+    let info: DataStruct =
+        extract_challenge_info(&json!({"id": "mc1"}), &parent(), "MasteryChallenge", 1).unwrap();
+
+    custom_assert_eq!(info.type_name.as_str(), "MasteryChallenge");
+    custom_assert_eq!(info.title.as_str(), "");
+    custom_assert_eq!(info.slug.as_str(), "");
+    custom_assert_eq!(info.relative_url.as_str(), "/math/unit-1");
+}
+
+#[test]
+fn test_extract_challenge_info_without_id() {
+    let result: Result<DataStruct, AppError> =
+        extract_challenge_info(&json!({}), &parent(), "MasteryChallenge", 1);
+
+    custom_assert_eq!(
+        result.unwrap_err().to_string(),
+        "Missing field: id".to_string()
+    );
+}
+
+#[test]
+fn test_extract_challenge_attempts() {
+    let attempts: Vec<ChallengeAttempt> = extract_challenge_attempts(QUIZ_TEST_PROGRESS).unwrap();
+
+    custom_assert_eq!(
+        attempts
+            .iter()
+            .map(|attempt| (
+                attempt.challenge_type.as_str(),
+                attempt.parent_id.as_deref(),
+                attempt.num_correct
+            ))
+            .collect::<Vec<(&str, Option<&str>, u32)>>(),
+        vec![
+            ("CourseChallenge", Some("c1"), 8),
+            ("MasteryChallenge", Some("u1"), 3),
+            ("MasteryChallenge", None, 0),
+        ]
+    );
+    custom_assert_eq!(
+        extract_challenge_attempts(r#"{"data": {"user": {}}}"#)
+            .unwrap()
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_extract_challenges() {
+    let course: EnrichedCourse = Extractor::from_strings(
+        CONTENT_FOR_PATH,
+        COURSE_PROGRESS,
+        vec![],
+        vec![QUIZ_TEST_PROGRESS.to_string()],
+    )
+    .extract()
+    .unwrap();
+
+    let challenges: Vec<(&str, &str, &str, &str, Option<&str>)> = course
+        .rows
+        .iter()
+        .filter(|row| row.type_name.ends_with("Challenge"))
+        .map(|row| {
+            (
+                row.id.as_str(),
+                row.title.as_str(),
+                row.slug.as_str(),
+                row.relative_url.as_str(),
+                row.best_score.as_deref(),
+            )
+        })
+        .collect();
+    custom_assert_eq!(
+        challenges,
+        vec![
+            ("mc1", "", "", "/math/unit-1", Some("50")),
+            (
+                "cc1",
+                "Course challenge",
+                "",
+                "/math/test/course-challenge",
+                Some("80")
+            ),
+        ]
+    );
+    custom_assert_eq!(course.attempts.len(), 3);
+}
+
+#[test]
+fn test_extract_challenge_attempts_skips_unexpected_shape() {
+    let quiz_test_progress: &str = r#"{"data": {"user": {
+        "latestCourseChallengeAttempts": [
+            {"__typename": "CourseChallengeAttempt", "id": "Q291cnNlQ2hhbGxlbmdlOmMxDEE=",
+             "isCompleted": true, "numAttempted": 10, "numCorrect": 8},
+            {"__typename": "CourseChallengeAttempt", "score": 0.8},
+            "not-an-attempt"
+        ],
+        "latestMasteryChallengeAttempts": {"edges": []}
+    }}}"#;
+    let attempts: Vec<ChallengeAttempt> = extract_challenge_attempts(quiz_test_progress).unwrap();
+
+    custom_assert_eq!(attempts.len(), 1);
+    custom_assert_eq!(attempts[0].parent_id.as_deref(), Some("c1"));
+}
+
+#[test]
+fn test_extract_challenges_skips_challenge_without_id() {
+    let content_for_path: String = CONTENT_FOR_PATH.replace(
+        r#""masteryChallenge": {"id": "mc1"}"#,
+        r#""masteryChallenge": {}"#,
+    );
+    let course: EnrichedCourse = Extractor::from_strings(
+        &content_for_path,
+        COURSE_PROGRESS,
+        vec![],
+        vec![QUIZ_TEST_PROGRESS.to_string()],
+    )
+    .extract()
+    .unwrap();
+
+    custom_assert_eq!(
+        course
+            .rows
+            .iter()
+            .filter(|row| row.type_name.ends_with("Challenge"))
+            .map(|row| row.id.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["cc1"]
+    );
+}
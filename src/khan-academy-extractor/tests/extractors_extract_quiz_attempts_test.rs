@@ -0,0 +1,89 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::extractors::{
+    decode_base64_bytes, decode_position_key, extract_quiz_attempts,
+};
+use khan_academy_extractor::models::TopicQuizAttempt;
+
+// Position keys are Protocol Buffers messages: the parent id as the length-delimited field 1
+// (`0a`, the length, the id), then the position as the varint field 2 (`10`, the varint).
+// `CgN4cTEQgAE` encodes the position 128, whose varint `80 01` is not valid UTF-8, and
+// `ChF4MDEyMzQ1Njc4OWFiY2RlZhAD` a parent id of 17 characters, whose length is `11`.
+const QUIZ_ATTEMPTS: &str = r#"{"data": {"user": {"latestQuizAttempts": [
+    {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 5, "numCorrect": 4,
+     "positionKey": "CgN4cTEQAg", "completedDate": "2024-03-01T10:00:00Z"},
+    {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 5, "numCorrect": 5,
+     "positionKey": "CgN4cTEQgAE"},
+    {"__typename": "TopicQuizAttempt", "isCompleted": false, "numAttempted": 0, "numCorrect": 0,
+     "positionKey": "CgN4cTI"},
+    {"__typename": "TopicQuizAttempt", "isCompleted": true, "numAttempted": 5, "numCorrect": 3,
+     "positionKey": "ChF4MDEyMzQ1Njc4OWFiY2RlZhAD"}
+]}}}"#;
+
+#[test]
+fn test_extract_quiz_attempts_decodes_parent_and_position() {
+    let attempts: Vec<TopicQuizAttempt> = extract_quiz_attempts(QUIZ_ATTEMPTS).unwrap();
+
+    custom_assert_eq!(
+        attempts
+            .iter()
+            .map(|attempt| (attempt.parent_id.as_str(), attempt.position))
+            .collect::<Vec<(&str, Option<u32>)>>(),
+        vec![
+            ("xq1", Some(2)),
+            ("xq1", Some(128)),
+            ("xq2", None),
+            ("x0123456789abcdef", Some(3)),
+        ]
+    );
+    custom_assert_eq!(
+        attempts[0].completed_date.as_deref(),
+        Some("2024-03-01T10:00:00Z")
+    );
+}
+
+#[test]
+fn test_decode_position_key_reads_fields() {
+    // The position is read from its own field, not from the first `10` byte after the id
+    custom_assert_eq!(
+        decode_position_key(&[0x0a, 0x03, b'x', 0x10, b'1', 0x10, 0x04]),
+        Some(("x\u{10}1".to_string(), Some(4)))
+    );
+    // The fields may come in any order, and unknown fields are skipped
+    custom_assert_eq!(
+        decode_position_key(&[0x10, 0x02, 0x1a, 0x02, b'k', b'a', 0x0a, 0x02, b'u', b'1']),
+        Some(("u1".to_string(), Some(2)))
+    );
+    // The parent id stops at a `0c` byte within field 1
+    custom_assert_eq!(
+        decode_position_key(&[0x0a, 0x04, b'x', b'q', b'1', 0x0c, 0x10, 0x01]),
+        Some(("xq1".to_string(), Some(1)))
+    );
+}
+
+#[test]
+fn test_decode_position_key_rejects_invalid_messages() {
+    // A length past the end of the key
+    custom_assert_eq!(
+        decode_position_key(&[0x0a, 0x11, b'u', b'1', 0x10, 0x01]),
+        None
+    );
+    // A truncated varint
+    custom_assert_eq!(
+        decode_position_key(&[0x0a, 0x02, b'u', b'1', 0x10, 0x80]),
+        None
+    );
+    // No parent id
+    custom_assert_eq!(decode_position_key(&[0x10, 0x01]), None);
+    custom_assert_eq!(decode_position_key(b"abc"), None);
+}
+
+#[test]
+fn test_decode_base64_bytes_keeps_high_bytes() {
+    custom_assert_eq!(
+        decode_base64_bytes("CgN4cTEQgAE").unwrap(),
+        vec![0x0a, 0x03, b'x', b'q', b'1', 0x10, 0x80, 0x01]
+    );
+    custom_assert_eq!(decode_base64_bytes("EAI").unwrap(), vec![0x10, 0x02]);
+}
@@ -0,0 +1,423 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::fetch_operations::{
+    built_in_request, fetch_course, normalise_course_path, retry_delay, FetchClient,
+    RecordedRequest, RecordedRequests, MAX_RETRY_DELAY,
+};
+use khan_academy_extractor::replay_operations::{replay, ReplayCaptures};
+use khan_academy_extractor::source_operations::MemorySource;
+use serde_json::{json, Value};
+use std::fs::{read_to_string, write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+use tempfile::tempdir;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+         "allOrderedChildren": [
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/math/unit-1/e/exercise-1", "progressKey": "ke1"}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/math/unit-2", "progressKey": "ku2",
+         "allOrderedChildren": [
+            {"id": "e2", "__typename": "Exercise", "translatedTitle": "Exercise 2",
+             "slug": "exercise-2", "relativeUrl": "/math/unit-2/e/exercise-2", "progressKey": "ke2"}
+         ]}
+    ]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {}}}}"#;
+
+fn unit_progress(id: &str) -> String {
+    format!(
+        r#"{{"data": {{"user": {{"contentItemProgresses": [{{
+            "__typename": "BasicContentItemProgress", "bestScore": null,
+            "completionStatus": "UNSTARTED",
+            "content": {{"__typename": "Exercise", "id": "e{0}", "progressKey": "ke{0}"}}
+        }}]}}}}}}"#,
+        id
+    )
+}
+
+fn quiz_test_progress(id: &str) -> String {
+    // Unit test attempt ids encode `UnitTest:u{id}`
+    let attempt_id: &str = match id {
+        "1" => "VW5pdFRlc3Q6dTEM",
+        _ => "VW5pdFRlc3Q6dTIM",
+    };
+    format!(
+        r#"{{"data": {{"user": {{"latestQuizAttempts": [], "latestUnitTestAttempts": [{{
+            "__typename": "TopicUnitTestAttempt", "id": "{0}",
+            "isCompleted": false, "numAttempted": 0, "numCorrect": 0
+        }}]}}}}}}"#,
+        attempt_id
+    )
+}
+
+const PROGRESS_QUERY: &str = "query courseProgressQuery($courseId: String!) { user { courseProgress(courseId: $courseId) { currentMasteryV2 { percentage } } } }";
+
+const TOPIC_QUERY: &str = "query getUserInfoForTopicProgressMastery($topicId: String!, $path: String!) { user { contentItemProgresses(topicId: $topicId) { completionStatus } } }";
+
+/// Returns an entry of a HAR file.
+fn har_entry(method: &str, url: &str, body: Option<Value>, response: Option<&str>) -> Value {
+    let mut entry: Value = json!({
+        "request": {
+            "method": method,
+            "url": url,
+            "headers": [
+                { "name": ":authority", "value": "www.khanacademy.org" },
+                { "name": "content-type", "value": "application/json" },
+                { "name": "cookie", "value": "KAAS=recorded" },
+                { "name": "x-ka-fkey", "value": "recorded" }
+            ]
+        },
+        "response": { "status": 200, "content": { "mimeType": "application/json" } }
+    });
+    if let Some(body) = body {
+        entry["request"]["postData"] =
+            json!({ "mimeType": "application/json", "text": body.to_string() });
+    }
+    if let Some(response) = response {
+        entry["response"]["content"]["text"] = json!(response);
+    }
+
+    entry
+}
+
+/// Returns a HAR file recorded while browsing the second unit of a `science` course, with
+/// the structure of the `math` course under other paths.
+fn har() -> String {
+    let recorded_content: String = CONTENT_FOR_PATH.replace("math", "science");
+    json!({ "log": { "version": "1.2", "entries": [
+        har_entry("GET", "https://www.khanacademy.org/static/app.js", None, None),
+        har_entry(
+            "GET",
+            "https://www.khanacademy.org/api/internal/graphql/contentForPath?fastly_cacheable=persist_until_publish&hash=45296627&variables=%7B%22path%22%3A%22science%22%2C%22countryCode%22%3A%22US%22%7D&lang=en",
+            None,
+            Some(&recorded_content),
+        ),
+        har_entry(
+            "POST",
+            "https://www.khanacademy.org/api/internal/graphql/courseProgressQuery?lang=en",
+            Some(json!({
+                "operationName": "courseProgressQuery",
+                "query": PROGRESS_QUERY,
+                "variables": { "courseId": "c1" }
+            })),
+            Some(COURSE_PROGRESS),
+        ),
+        har_entry(
+            "POST",
+            "https://www.khanacademy.org/api/internal/graphql/getUserInfoForTopicProgressMastery",
+            Some(json!({
+                "operationName": "getUserInfoForTopicProgressMastery",
+                "query": TOPIC_QUERY,
+                "variables": { "topicId": "u2", "path": "science/unit-2" }
+            })),
+            None,
+        ),
+        har_entry(
+            "POST",
+            "https://www.khanacademy.org/api/internal/graphql/quizAndUnitTestAttemptsQuery",
+            Some(json!({
+                "operationName": "quizAndUnitTestAttemptsQuery",
+                "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "0a1b2c" } },
+                "variables": { "input": { "unitId": "u2", "countryCode": "US" } }
+            })),
+            None,
+        )
+    ]}})
+    .to_string()
+}
+
+/// Starts a replay server of the captures on a free port and returns its base URL.
+fn start_replay(source: MemorySource) -> String {
+    let captures: ReplayCaptures = ReplayCaptures::load(&source, "").unwrap();
+    let address: String = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let server_address: String = address.clone();
+    spawn(move || replay(&server_address, &captures));
+    while TcpStream::connect(&address).is_err() {
+        sleep(Duration::from_millis(10));
+    }
+
+    format!("http://{}", address)
+}
+
+#[test]
+fn test_fetch_course_saves_every_unit_under_the_capture_names() {
+    // The replay captures number the units in reverse order, the fetch numbers them in
+    // course order
+    let source: MemorySource = MemorySource::new()
+        .with_file("contentForPath.json", CONTENT_FOR_PATH)
+        .with_file("courseProgressQuery.json", COURSE_PROGRESS)
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress("2"),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-2.json",
+            unit_progress("1"),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-1.json",
+            quiz_test_progress("2"),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-2.json",
+            quiz_test_progress("1"),
+        );
+    let base_url: String = start_replay(source);
+    let directory = tempdir().unwrap();
+
+    let mut client: FetchClient = FetchClient::new(&base_url, "fkey=abc; KAAS=secret")
+        .with_delay(Duration::from_millis(5))
+        .with_retries(2);
+    let requests: RecordedRequests = RecordedRequests::from_har(&har()).unwrap();
+    let written: Vec<String> = fetch_course(
+        &mut client,
+        Some(&requests),
+        "https://www.khanacademy.org/math?lang=en",
+        "ka-",
+        directory.path(),
+    )
+    .unwrap();
+
+    custom_assert_eq!(
+        written,
+        vec![
+            "ka-contentForPath.json".to_string(),
+            "ka-courseProgressQuery.json".to_string(),
+            "ka-getUserInfoForTopicProgressMastery-1.json".to_string(),
+            "ka-quizAndUnitTestAttemptsQuery-1.json".to_string(),
+            "ka-getUserInfoForTopicProgressMastery-2.json".to_string(),
+            "ka-quizAndUnitTestAttemptsQuery-2.json".to_string(),
+        ]
+    );
+    let read = |name: &str| read_to_string(directory.path().join(name)).unwrap();
+    custom_assert_eq!(read("ka-contentForPath.json"), CONTENT_FOR_PATH.to_string());
+    custom_assert_eq!(
+        read("ka-getUserInfoForTopicProgressMastery-1.json"),
+        unit_progress("1")
+    );
+    custom_assert_eq!(
+        read("ka-getUserInfoForTopicProgressMastery-2.json"),
+        unit_progress("2")
+    );
+    custom_assert_eq!(
+        read("ka-quizAndUnitTestAttemptsQuery-1.json"),
+        quiz_test_progress("1")
+    );
+}
+
+#[test]
+fn test_fetch_course_without_recording_removes_stale_files() {
+    let source: MemorySource = MemorySource::new()
+        .with_file("contentForPath.json", CONTENT_FOR_PATH)
+        .with_file("courseProgressQuery.json", COURSE_PROGRESS)
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress("1"),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-2.json",
+            unit_progress("2"),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-1.json",
+            quiz_test_progress("1"),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-2.json",
+            quiz_test_progress("2"),
+        );
+    let base_url: String = start_replay(source);
+    let directory = tempdir().unwrap();
+    // Left from an earlier capture of a course with three units, and from another prefix
+    for name in [
+        "ka-getUserInfoForTopicProgressMastery-3.json",
+        "ka-quizAndUnitTestAttemptsQuery-3.json",
+        "other-getUserInfoForTopicProgressMastery-3.json",
+    ] {
+        write(directory.path().join(name), "{}").unwrap();
+    }
+
+    let mut client: FetchClient = FetchClient::new(&base_url, "");
+    let written: Vec<String> =
+        fetch_course(&mut client, None, "/math", "ka-", directory.path()).unwrap();
+
+    custom_assert_eq!(written.len(), 6);
+    custom_assert_eq!(
+        read_to_string(
+            directory
+                .path()
+                .join("ka-getUserInfoForTopicProgressMastery-2.json")
+        )
+        .unwrap(),
+        unit_progress("2")
+    );
+    let exists = |name: &str| directory.path().join(name).exists();
+    custom_assert_eq!(
+        exists("ka-getUserInfoForTopicProgressMastery-3.json"),
+        false
+    );
+    custom_assert_eq!(exists("ka-quizAndUnitTestAttemptsQuery-3.json"), false);
+    custom_assert_eq!(
+        exists("other-getUserInfoForTopicProgressMastery-3.json"),
+        true
+    );
+}
+
+#[test]
+fn test_built_in_request_names_the_operation() {
+    let request: RecordedRequest =
+        built_in_request("courseProgressQuery", json!({ "courseId": "c1" }));
+
+    custom_assert_eq!(request.method.as_str(), "POST");
+    custom_assert_eq!(
+        request.path.as_str(),
+        "/api/internal/graphql/courseProgressQuery"
+    );
+    custom_assert_eq!(
+        serde_json::from_str::<Value>(request.body.as_deref().unwrap()).unwrap(),
+        json!({ "operationName": "courseProgressQuery", "variables": { "courseId": "c1" } })
+    );
+}
+
+#[test]
+fn test_fetch_course_fails_on_a_missing_operation() {
+    let source: MemorySource =
+        MemorySource::new().with_file("contentForPath.json", CONTENT_FOR_PATH);
+    let base_url: String = start_replay(source);
+    let directory = tempdir().unwrap();
+
+    let requests: RecordedRequests = RecordedRequests::from_har(&har()).unwrap();
+    let mut client: FetchClient = FetchClient::new(&base_url, "");
+    let error: String = fetch_course(&mut client, Some(&requests), "/math", "", directory.path())
+        .unwrap_err()
+        .to_string();
+
+    custom_assert_eq!(
+        error.contains("courseProgressQuery?lang=en returned status 404"),
+        true
+    );
+    custom_assert_eq!(directory.path().join("contentForPath.json").exists(), true);
+}
+
+#[test]
+fn test_normalise_course_path() {
+    custom_assert_eq!(
+        normalise_course_path("/math/algebra/"),
+        "math/algebra".to_string()
+    );
+    custom_assert_eq!(
+        normalise_course_path("https://www.khanacademy.org/math/algebra#units"),
+        "math/algebra".to_string()
+    );
+}
+
+#[test]
+fn test_recorded_request_with_values_keeps_the_recorded_query() {
+    let requests: RecordedRequests = RecordedRequests::from_har(&har()).unwrap();
+    let replacements: Vec<(String, String)> = vec![
+        ("u2".to_string(), "u1".to_string()),
+        ("science/unit-2".to_string(), "math/unit-1".to_string()),
+        ("science".to_string(), "math".to_string()),
+    ];
+
+    let content_for_path: &RecordedRequest = requests.get("contentForPath").unwrap();
+    custom_assert_eq!(content_for_path.method.as_str(), "GET");
+    custom_assert_eq!(content_for_path.body, None);
+    custom_assert_eq!(
+        content_for_path.with_values(&replacements).unwrap().path,
+        "/api/internal/graphql/contentForPath?fastly_cacheable=persist_until_publish&hash=45296627&variables=%7B%22countryCode%22%3A%22US%22%2C%22path%22%3A%22math%22%7D&lang=en".to_string()
+    );
+
+    let topic: RecordedRequest = requests
+        .get("getUserInfoForTopicProgressMastery")
+        .unwrap()
+        .with_values(&replacements)
+        .unwrap();
+    custom_assert_eq!(
+        serde_json::from_str::<Value>(topic.body.as_deref().unwrap()).unwrap(),
+        json!({
+            "operationName": "getUserInfoForTopicProgressMastery",
+            "query": TOPIC_QUERY,
+            "variables": { "topicId": "u1", "path": "math/unit-1" }
+        })
+    );
+    custom_assert_eq!(
+        topic.headers[1],
+        ("content-type".to_string(), "application/json".to_string())
+    );
+
+    let error: String = requests
+        .get("courseProgressQuery")
+        .unwrap()
+        .with_values(&[("u9".to_string(), "u1".to_string())])
+        .unwrap_err()
+        .to_string();
+    custom_assert_eq!(
+        error.contains("no variable of the recorded courseProgressQuery request"),
+        true
+    );
+}
+
+#[test]
+fn test_recorded_requests_from_har_errors() {
+    let requests: RecordedRequests =
+        RecordedRequests::from_har(r#"{"log": {"entries": []}}"#).unwrap();
+    custom_assert_eq!(
+        requests
+            .get("contentForPath")
+            .unwrap_err()
+            .to_string()
+            .contains("no contentForPath request was recorded"),
+        true
+    );
+    custom_assert_eq!(
+        RecordedRequests::from_har("{}").unwrap_err().to_string(),
+        "Missing field: log.entries".to_string()
+    );
+
+    // A recording whose unit requests name no unit cannot select the units to fetch
+    let directory = tempdir().unwrap();
+    let mut har: Value = serde_json::from_str(&har()).unwrap();
+    har["log"]["entries"][3]["request"]["postData"]["text"] =
+        json!(json!({ "variables": { "topicId": "u9" } }).to_string());
+    let requests: RecordedRequests = RecordedRequests::from_har(&har.to_string()).unwrap();
+    let mut client: FetchClient = FetchClient::new("http://127.0.0.1:9", "");
+    let error: String = fetch_course(&mut client, Some(&requests), "/math", "", directory.path())
+        .unwrap_err()
+        .to_string();
+    custom_assert_eq!(
+        error,
+        "Invalid argument: no variable of the recorded getUserInfoForTopicProgressMastery request names a unit of the recorded course".to_string()
+    );
+    custom_assert_eq!(directory.path().join("contentForPath.json").exists(), false);
+}
+
+#[test]
+fn test_retry_delay_is_capped() {
+    custom_assert_eq!(retry_delay(0, None), Duration::from_millis(500));
+    custom_assert_eq!(retry_delay(2, None), Duration::from_secs(2));
+    custom_assert_eq!(retry_delay(30, None), MAX_RETRY_DELAY);
+    custom_assert_eq!(
+        retry_delay(0, Some(Duration::from_secs(5))),
+        Duration::from_secs(5)
+    );
+    custom_assert_eq!(
+        retry_delay(0, Some(Duration::from_secs(86_400))),
+        MAX_RETRY_DELAY
+    );
+}
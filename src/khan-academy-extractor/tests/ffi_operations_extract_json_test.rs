@@ -0,0 +1,124 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::ffi_operations::{
+    kae_extractor_extract_json, kae_extractor_free, kae_extractor_from_buffers,
+    kae_extractor_from_dir, kae_last_error, kae_string_free, KaeBuffer, KaeExtractor,
+};
+use serde_json::{from_str, Value};
+use std::ffi::{c_char, CStr, CString};
+use std::fs::write;
+use std::ptr::null;
+use tempfile::tempdir;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1"
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {
+    "currentMasteryV2": {"percentage": 40, "pointsEarned": 900},
+    "masteryMap": [],
+    "unitProgresses": []
+}}}}"#;
+
+fn buffer(content: &str) -> KaeBuffer {
+    KaeBuffer {
+        data: content.as_ptr(),
+        len: content.len(),
+    }
+}
+
+fn last_error() -> Option<String> {
+    let message: *const c_char = kae_last_error();
+    if message.is_null() {
+        return None;
+    }
+    Some(
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+unsafe fn extract_json(extractor: *mut KaeExtractor) -> Option<Value> {
+    let json_content: *mut c_char = kae_extractor_extract_json(extractor);
+    kae_extractor_free(extractor);
+    if json_content.is_null() {
+        return None;
+    }
+    let course: Value = from_str(CStr::from_ptr(json_content).to_str().unwrap()).unwrap();
+    kae_string_free(json_content);
+    Some(course)
+}
+
+#[test]
+fn test_extract_json_from_buffers() {
+    let course: Value = unsafe {
+        extract_json(kae_extractor_from_buffers(
+            buffer(CONTENT_FOR_PATH),
+            buffer(COURSE_PROGRESS),
+            null(),
+            0,
+            null(),
+            0,
+        ))
+    }
+    .unwrap();
+
+    custom_assert_eq!(course["rows"][0]["id"], "c1");
+    custom_assert_eq!(course["rows"][0]["percentage"], "40");
+    custom_assert_eq!(course["attempts"], Value::Array(Vec::new()));
+    custom_assert_eq!(last_error(), None);
+}
+
+#[test]
+fn test_extract_json_from_dir() {
+    let directory = tempdir().unwrap();
+    write(
+        directory.path().join("contentForPath.json"),
+        CONTENT_FOR_PATH,
+    )
+    .unwrap();
+    write(
+        directory.path().join("courseProgressQuery.json"),
+        COURSE_PROGRESS,
+    )
+    .unwrap();
+    let path: CString = CString::new(directory.path().to_str().unwrap()).unwrap();
+
+    let course: Value =
+        unsafe { extract_json(kae_extractor_from_dir(path.as_ptr(), null())) }.unwrap();
+    custom_assert_eq!(course["rows"][0]["title"], "Math");
+}
+
+#[test]
+fn test_extract_json_errors() {
+    let course: Option<Value> = unsafe {
+        extract_json(kae_extractor_from_buffers(
+            buffer("{}"),
+            buffer(COURSE_PROGRESS),
+            null(),
+            0,
+            null(),
+            0,
+        ))
+    };
+    assert!(course.is_none());
+    custom_assert_eq!(last_error().as_deref(), Some("Missing field: course"));
+
+    let extractor: *mut KaeExtractor = unsafe { kae_extractor_from_dir(null(), null()) };
+    assert!(extractor.is_null());
+    custom_assert_eq!(
+        last_error().as_deref(),
+        Some("Invalid argument: path is null")
+    );
+
+    let extractor: *mut KaeExtractor =
+        unsafe { kae_extractor_from_buffers(buffer("{}"), buffer("{}"), null(), 2, null(), 0) };
+    assert!(extractor.is_null());
+    custom_assert_eq!(
+        last_error().as_deref(),
+        Some("Invalid argument: unit_progress is null")
+    );
+}
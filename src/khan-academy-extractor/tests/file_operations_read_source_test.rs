@@ -0,0 +1,100 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::file_operations::{read_files, read_source, FileContents};
+use khan_academy_extractor::source_operations::{BundleSource, InputSource, MemorySource};
+use std::fs::write;
+use tempfile::tempdir;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": null}}"#;
+const COURSE_PROGRESS: &str = r#"{"data": {"user": null}}"#;
+
+fn memory_source() -> MemorySource {
+    MemorySource::new()
+        .with_file("kacontentForPath.json", CONTENT_FOR_PATH)
+        .with_file("kacourseProgressQuery.json", COURSE_PROGRESS)
+        .with_file("kagetUserInfoForTopicProgressMastery-10.json", "unit 10")
+        .with_file("kagetUserInfoForTopicProgressMastery-2.json", "unit 2")
+        .with_file("kaquizAndUnitTestAttemptsQuery-1", "quiz 1")
+        .with_file("otherContentForPath.json", "other")
+}
+
+#[test]
+fn test_read_source_memory() {
+    let file_contents: FileContents = read_source(&memory_source(), "ka").unwrap();
+
+    custom_assert_eq!(file_contents.json_content, CONTENT_FOR_PATH);
+    custom_assert_eq!(file_contents.json_course_progress, COURSE_PROGRESS);
+    custom_assert_eq!(
+        file_contents.json_unit_progress_files,
+        vec!["unit 2".to_string(), "unit 10".to_string()]
+    );
+    custom_assert_eq!(
+        file_contents.json_quiz_test_progress_files,
+        vec!["quiz 1".to_string()]
+    );
+}
+
+#[test]
+fn test_read_source_missing_file() {
+    let source: MemorySource = MemorySource::new().with_file("contentForPath.json", "{}");
+
+    assert!(matches!(
+        read_source(&source, ""),
+        Err(AppError::MissingFile(_))
+    ));
+    assert!(matches!(
+        source.read_file("courseProgressQuery.json"),
+        Err(AppError::MissingFile(_))
+    ));
+}
+
+#[test]
+fn test_read_source_bundle() {
+    let bundle: String = format!(
+        r#"{{"contentForPath.json": {}, "courseProgressQuery.json": {},
+            "getUserInfoForTopicProgressMastery-1.json": "unit 1"}}"#,
+        CONTENT_FOR_PATH, COURSE_PROGRESS
+    );
+    let source: BundleSource = BundleSource::parse(&bundle).unwrap();
+    let file_contents: FileContents = read_source(&source, "").unwrap();
+
+    custom_assert_eq!(
+        file_contents.json_content,
+        r#"{"data":{"contentRoute":null}}"#
+    );
+    custom_assert_eq!(
+        file_contents.json_unit_progress_files,
+        vec!["unit 1".to_string()]
+    );
+    assert!(file_contents.json_quiz_test_progress_files.is_empty());
+    assert!(BundleSource::parse("[]").is_err());
+}
+
+#[test]
+fn test_read_files_directory_and_bundle() {
+    let directory = tempdir().unwrap();
+    write(
+        directory.path().join("contentForPath.json"),
+        CONTENT_FOR_PATH,
+    )
+    .unwrap();
+    write(
+        directory.path().join("courseProgressQuery.json"),
+        COURSE_PROGRESS,
+    )
+    .unwrap();
+    let from_directory: FileContents = read_files(directory.path().to_str().unwrap(), "").unwrap();
+    custom_assert_eq!(from_directory.json_content, CONTENT_FOR_PATH);
+
+    let bundle_file = directory.path().join("bundle.json");
+    write(
+        &bundle_file,
+        r#"{"contentForPath.json": "content", "courseProgressQuery.json": "progress"}"#,
+    )
+    .unwrap();
+    let from_bundle: FileContents = read_files(bundle_file.to_str().unwrap(), "").unwrap();
+    custom_assert_eq!(from_bundle.json_content, "content");
+    custom_assert_eq!(from_bundle.json_course_progress, "progress");
+}
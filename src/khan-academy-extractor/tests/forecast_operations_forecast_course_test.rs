@@ -0,0 +1,120 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use chrono::NaiveDate;
+use khan_academy_extractor::forecast_operations::{forecast_course, required_weekly_pace};
+use khan_academy_extractor::models::{BestScore, Content, ContentItemProgress, DataStruct};
+
+fn row(id: &str, type_name: &str, parent: Option<(&str, &str)>) -> DataStruct {
+    DataStruct {
+        id: id.to_string(),
+        type_name: type_name.to_string(),
+        order: 1,
+        title: id.to_string(),
+        slug: id.to_string(),
+        relative_url: format!("/{}", id),
+        progress_key: Some(format!("k{}", id)),
+        parent_topic: Some("".to_string()),
+        parent_id: parent.map(|(id, _)| id.to_string()),
+        parent_type: parent.map(|(_, type_name)| type_name.to_string()),
+        path: id.to_string(),
+        ..Default::default()
+    }
+}
+
+fn completed(id: &str, completed_date: &str) -> ContentItemProgress {
+    ContentItemProgress {
+        type_name: "BasicContentItemProgress".to_string(),
+        best_score: Some(BestScore {
+            completed_date: Some(completed_date.to_string()),
+            num_attempted: None,
+            num_correct: None,
+        }),
+        completion_status: "COMPLETE".to_string(),
+        content: Content {
+            type_name: "Video".to_string(),
+            id: id.to_string(),
+            progress_key: format!("k{}", id),
+        },
+    }
+}
+
+fn date(value: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn test_forecast_course_projects_units_in_order() {
+    let rows = vec![
+        row("c", "Course", None),
+        row("u1", "Unit", Some(("c", "Course"))),
+        row("l1", "Lesson", Some(("u1", "Unit"))),
+        row("a", "Video", Some(("l1", "Lesson"))),
+        row("b", "Video", Some(("l1", "Lesson"))),
+        row("u2", "Unit", Some(("c", "Course"))),
+        row("l2", "Lesson", Some(("u2", "Unit"))),
+        row("d", "Video", Some(("l2", "Lesson"))),
+        row("e", "Video", Some(("l2", "Lesson"))),
+    ];
+    let items_progresses = vec![vec![
+        completed("a", "2024-01-01T08:00:00Z"),
+        completed("b", "2024-01-08T08:00:00Z"),
+    ]];
+
+    let forecast = forecast_course(&rows, &items_progresses, date("2024-01-08")).unwrap();
+
+    custom_assert_eq!(forecast.total, 4);
+    custom_assert_eq!(forecast.completed, 2);
+    custom_assert_eq!(forecast.history.len(), 2);
+    custom_assert_eq!(forecast.units[0].finish_date, Some(date("2024-01-08")));
+    custom_assert_eq!(forecast.units[1].finish_date, Some(date("2024-01-22")));
+    custom_assert_eq!(forecast.finish_date, Some(date("2024-01-22")));
+    custom_assert_eq!(
+        required_weekly_pace(&forecast, date("2024-01-22")),
+        Some(1.0)
+    );
+    custom_assert_eq!(required_weekly_pace(&forecast, date("2024-01-01")), None);
+}
+
+#[test]
+fn test_forecast_course_without_history() {
+    let rows = vec![
+        row("c", "Course", None),
+        row("u1", "Unit", Some(("c", "Course"))),
+        row("l1", "Lesson", Some(("u1", "Unit"))),
+        row("a", "Video", Some(("l1", "Lesson"))),
+    ];
+
+    let forecast = forecast_course(&rows, &[], date("2024-01-08")).unwrap();
+
+    custom_assert_eq!(forecast.weekly_pace, 0.0);
+    custom_assert_eq!(forecast.finish_date, None);
+}
+
+#[test]
+fn test_forecast_course_counts_complete_items_without_best_score() {
+    let rows = vec![
+        row("c", "Course", None),
+        row("u1", "Unit", Some(("c", "Course"))),
+        row("l1", "Lesson", Some(("u1", "Unit"))),
+        row("a", "Video", Some(("l1", "Lesson"))),
+        row("b", "Article", Some(("l1", "Lesson"))),
+        row("d", "Exercise", Some(("l1", "Lesson"))),
+    ];
+    let mut started: ContentItemProgress = completed("d", "2024-01-05T08:00:00Z");
+    started.completion_status = "STARTED".to_string();
+    let mut undated: ContentItemProgress = completed("b", "2024-01-01T08:00:00Z");
+    undated.best_score = None;
+    let items_progresses = vec![vec![
+        completed("a", "2024-01-01T08:00:00Z"),
+        undated,
+        started,
+    ]];
+
+    let forecast = forecast_course(&rows, &items_progresses, date("2024-01-08")).unwrap();
+
+    custom_assert_eq!(forecast.total, 3);
+    custom_assert_eq!(forecast.completed, 2);
+    custom_assert_eq!(forecast.units[0].completed, 2);
+    custom_assert_eq!(forecast.history, vec![(date("2024-01-01"), 1)]);
+}
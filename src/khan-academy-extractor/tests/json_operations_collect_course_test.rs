@@ -0,0 +1,125 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::json_operations::collect_course;
+use khan_academy_extractor::models::DataStruct;
+use serde_json::{from_str, Value};
+
+// A unit holding topics, which hold lessons or content items directly
+const COURSE_WITH_TOPICS: &str = r#"{
+    "id": "c1", "__typename": "Course", "translatedTitle": "Science", "slug": "science",
+    "relativeUrl": "/science",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/science/unit-1",
+         "children": [
+            {"id": "t1", "__typename": "Topic", "translatedTitle": "Topic 1", "slug": "topic-1",
+             "relativeUrl": "/science/unit-1/topic-1",
+             "children": [
+                {"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1",
+                 "slug": "lesson-1", "relativeUrl": "/science/unit-1/topic-1/lesson-1",
+                 "curatedChildren": [
+                    {"id": "v1", "__typename": "Video", "translatedTitle": "Video 1",
+                     "slug": "video-1", "relativeUrl": "/science/unit-1/topic-1/v/video-1"}
+                 ]}
+             ]},
+            {"id": "t2", "__typename": "Topic", "translatedTitle": "Topic 2", "slug": "topic-2",
+             "relativeUrl": "/science/unit-1/topic-2",
+             "children": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/science/unit-1/topic-2/e/exercise-1"}
+             ]}
+         ]}
+    ]
+}"#;
+
+// Units holding content items without lessons
+const COURSE_WITHOUT_LESSONS: &str = r#"{
+    "id": "c2", "__typename": "Course", "translatedTitle": "Reading", "slug": "reading",
+    "relativeUrl": "/reading",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/reading/unit-1",
+         "allOrderedChildren": [
+            {"id": "a1", "__typename": "Article", "translatedTitle": "Article 1",
+             "slug": "article-1", "relativeUrl": "/reading/unit-1/a/article-1"},
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/reading/unit-1/e/exercise-1"}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/reading/unit-2"}
+    ]
+}"#;
+
+/// Returns the id, depth, path, parent id, and order of every row of a course.
+fn tree(course: &str) -> Vec<(String, u32, String, Option<String>, u32)> {
+    let course: Value = from_str(course).unwrap();
+    let rows: Vec<DataStruct> = collect_course(&course).unwrap();
+
+    rows.into_iter()
+        .map(|row| (row.id, row.depth, row.path, row.parent_id, row.order))
+        .collect()
+}
+
+fn row(
+    id: &str,
+    depth: u32,
+    path: &str,
+    parent_id: Option<&str>,
+    order: u32,
+) -> (String, u32, String, Option<String>, u32) {
+    (
+        id.to_string(),
+        depth,
+        path.to_string(),
+        parent_id.map(|parent_id| parent_id.to_string()),
+        order,
+    )
+}
+
+#[test]
+fn test_collect_course_with_topics_under_units() {
+    custom_assert_eq!(
+        tree(COURSE_WITH_TOPICS),
+        vec![
+            row("c1", 0, "science", None, 1),
+            row("u1", 1, "science/unit-1", Some("c1"), 1),
+            row("t1", 2, "science/unit-1/topic-1", Some("u1"), 1),
+            row("l1", 3, "science/unit-1/topic-1/lesson-1", Some("t1"), 1),
+            row(
+                "v1",
+                4,
+                "science/unit-1/topic-1/lesson-1/video-1",
+                Some("l1"),
+                1
+            ),
+            row("t2", 2, "science/unit-1/topic-2", Some("u1"), 2),
+            row("e1", 3, "science/unit-1/topic-2/exercise-1", Some("t2"), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_course_without_lessons() {
+    custom_assert_eq!(
+        tree(COURSE_WITHOUT_LESSONS),
+        vec![
+            row("c2", 0, "reading", None, 1),
+            row("u1", 1, "reading/unit-1", Some("c2"), 1),
+            row("a1", 2, "reading/unit-1/article-1", Some("u1"), 1),
+            row("e1", 2, "reading/unit-1/exercise-1", Some("u1"), 2),
+            row("u2", 1, "reading/unit-2", Some("c2"), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_course_missing_field() {
+    let course: Value =
+        from_str(&COURSE_WITHOUT_LESSONS.replace(r#""slug": "article-1","#, "")).unwrap();
+
+    custom_assert_eq!(
+        collect_course(&course).unwrap_err().to_string(),
+        "Missing field: slug".to_string()
+    );
+}
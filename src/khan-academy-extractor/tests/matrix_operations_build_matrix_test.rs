@@ -0,0 +1,126 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use csv::Writer;
+use khan_academy_extractor::json_operations::collect_course;
+use khan_academy_extractor::matrix_operations::{build_matrix, parse_learner, write_matrix_csv};
+use khan_academy_extractor::models::{DataStruct, ProgressMatrix};
+use serde_json::{from_str, Value};
+
+const COURSE: &str = r#"{
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1",
+         "allOrderedChildren": [
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/math/unit-1/e/exercise-1"},
+            {"id": "v1", "__typename": "Video", "translatedTitle": "Video, part 1",
+             "slug": "video-1", "relativeUrl": "/math/unit-1/v/video-1"}
+         ]}
+    ]
+}"#;
+
+/// Returns the rows of the course with the given progress, by id.
+fn learner(progress: &[(&str, &str)]) -> Vec<DataStruct> {
+    let course: Value = from_str(COURSE).unwrap();
+    let mut rows: Vec<DataStruct> = collect_course(&course).unwrap();
+    for row in rows.iter_mut() {
+        match progress.iter().find(|(id, _)| *id == row.id) {
+            Some((_, value)) if value.ends_with('%') => {
+                row.percentage = Some(value.trim_end_matches('%').to_string())
+            }
+            Some((_, value)) => row.completion_status = Some(value.to_string()),
+            None => {}
+        }
+    }
+
+    rows
+}
+
+#[test]
+fn test_parse_learner() {
+    custom_assert_eq!(
+        parse_learner("alice=captures/a"),
+        ("alice".to_string(), "captures/a".to_string())
+    );
+    custom_assert_eq!(
+        parse_learner("captures/bob/"),
+        ("bob".to_string(), "captures/bob/".to_string())
+    );
+    custom_assert_eq!(
+        parse_learner("carol"),
+        ("carol".to_string(), "carol".to_string())
+    );
+}
+
+#[test]
+fn test_build_matrix() {
+    let learners: Vec<(String, Vec<DataStruct>)> = vec![
+        (
+            "alice".to_string(),
+            learner(&[("u1", "70%"), ("e1", "COMPLETE"), ("v1", "COMPLETE")]),
+        ),
+        (
+            "bob".to_string(),
+            learner(&[("u1", "20%"), ("e1", "STARTED"), ("v1", "COMPLETE")]),
+        ),
+        // A learner whose captures miss the video
+        (
+            "carol".to_string(),
+            learner(&[("e1", "COMPLETE")])
+                .into_iter()
+                .filter(|row| row.id != "v1")
+                .collect(),
+        ),
+    ];
+    let matrix: ProgressMatrix = build_matrix(&learners);
+
+    custom_assert_eq!(matrix.learners, vec!["alice", "bob", "carol"]);
+    custom_assert_eq!(
+        matrix
+            .rows
+            .iter()
+            .map(|row| row.id.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["c1", "u1", "e1", "v1"]
+    );
+    custom_assert_eq!(matrix.rows[1].cells, vec!["70%", "20%", ""]);
+    custom_assert_eq!(matrix.rows[1].completion_rate, None);
+    custom_assert_eq!(
+        matrix.rows[2].cells,
+        vec!["COMPLETE", "STARTED", "COMPLETE"]
+    );
+    custom_assert_eq!(matrix.rows[2].completion_rate, Some(66));
+    custom_assert_eq!(matrix.rows[3].cells, vec!["COMPLETE", "COMPLETE", ""]);
+    custom_assert_eq!(matrix.rows[3].completion_rate, Some(100));
+}
+
+#[test]
+fn test_build_matrix_without_learners() {
+    let matrix: ProgressMatrix = build_matrix(&[]);
+
+    custom_assert_eq!(matrix.learners.len(), 0);
+    custom_assert_eq!(matrix.rows.len(), 0);
+}
+
+#[test]
+fn test_write_matrix_csv() {
+    let learners: Vec<(String, Vec<DataStruct>)> = vec![
+        ("alice".to_string(), learner(&[("v1", "COMPLETE")])),
+        ("bob".to_string(), learner(&[("v1", "UNSTARTED")])),
+    ];
+    let mut writer: Writer<Vec<u8>> = Writer::from_writer(Vec::new());
+    write_matrix_csv(&build_matrix(&learners), &mut writer).unwrap();
+
+    custom_assert_eq!(
+        String::from_utf8(writer.into_inner().unwrap()).unwrap(),
+        "id,typeName,title,alice,bob,completionRate\n\
+         c1,Course,Math,,,\n\
+         u1,Unit,Unit 1,,,\n\
+         e1,Exercise,Exercise 1,,,\n\
+         v1,Video,\"Video, part 1\",COMPLETE,UNSTARTED,50\n"
+            .to_string()
+    );
+}
@@ -0,0 +1,90 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use csv::{Reader, StringRecord, Writer};
+use khan_academy_extractor::csv_operations::write_course_csv;
+use khan_academy_extractor::merge_operations::merge_csv;
+use khan_academy_extractor::models::DataStruct;
+
+#[test]
+fn test_merge_csv_keeps_user_columns() {
+    let previous: &str =
+        "id,title,status,notes,target\na,Old A,,review,2024-05-01\nb,B,COMPLETE,,\n";
+    let current: &str = "id,title,status\na,New A,COMPLETE\nb,B,COMPLETE\nc,C,\n";
+    let mut output: Vec<u8> = Vec::new();
+
+    let removed: usize = merge_csv(previous.as_bytes(), current.as_bytes(), &mut output).unwrap();
+
+    custom_assert_eq!(removed, 0);
+    custom_assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "id,title,status,removed,notes,target\na,New A,COMPLETE,,review,2024-05-01\nb,B,COMPLETE,,,\nc,C,,,,\n"
+    );
+}
+
+#[test]
+fn test_merge_csv_flags_removed_rows() {
+    let previous: &str = "id,title,removed,notes\na,A,,keep\ngone,Gone,,old note\n";
+    let current: &str = "id,title\na,A\n";
+    let mut output: Vec<u8> = Vec::new();
+
+    let removed: usize = merge_csv(previous.as_bytes(), current.as_bytes(), &mut output).unwrap();
+
+    custom_assert_eq!(removed, 1);
+    custom_assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "id,title,removed,notes\na,A,,keep\ngone,Gone,true,old note\n"
+    );
+}
+
+#[test]
+fn test_merge_csv_missing_id_column() {
+    let mut output: Vec<u8> = Vec::new();
+
+    let result = merge_csv(
+        "title\nA\n".as_bytes(),
+        "id,title\na,A\n".as_bytes(),
+        &mut output,
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_merge_csv_keeps_user_columns_named_like_annotations() {
+    let row: DataStruct = DataStruct {
+        id: "e1".to_string(),
+        type_name: "Exercise".to_string(),
+        title: "Exercise 1".to_string(),
+        ..Default::default()
+    };
+    let mut current: Vec<u8> = Vec::new();
+    write_course_csv(&mut current, &[row]).unwrap();
+    let mut previous: Vec<u8> = Vec::new();
+    let mut reader: Reader<&[u8]> = Reader::from_reader(current.as_slice());
+    let mut writer: Writer<&mut Vec<u8>> = Writer::from_writer(&mut previous);
+    let mut headers: StringRecord = reader.headers().unwrap().clone();
+    headers.extend(["notes", "skip", "due"]);
+    writer.write_record(&headers).unwrap();
+    for record in reader.records() {
+        let mut record: StringRecord = record.unwrap();
+        record.extend(["Redo it", "no", "2024-05-01"]);
+        writer.write_record(&record).unwrap();
+    }
+    drop(writer);
+    let mut output: Vec<u8> = Vec::new();
+
+    merge_csv(previous.as_slice(), current.as_slice(), &mut output).unwrap();
+
+    let mut merged: Reader<&[u8]> = Reader::from_reader(output.as_slice());
+    let merged_headers: StringRecord = merged.headers().unwrap().clone();
+    let merged_record: StringRecord = merged.records().next().unwrap().unwrap();
+    let user_values: Vec<&str> = ["notes", "skip", "due"]
+        .iter()
+        .map(|name| {
+            let index: usize = merged_headers.iter().position(|h| h == *name).unwrap();
+            &merged_record[index]
+        })
+        .collect();
+    custom_assert_eq!(user_values, vec!["Redo it", "no", "2024-05-01"]);
+}
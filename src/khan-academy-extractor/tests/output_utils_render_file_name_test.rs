@@ -0,0 +1,47 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use chrono::NaiveDate;
+use khan_academy_extractor::output_utils::render_file_name;
+
+fn date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, 5).unwrap()
+}
+
+#[test]
+fn test_render_file_name_default_template() {
+    let file_name: String = render_file_name(
+        "{prefix}{name}.{format}",
+        "algebra",
+        "run1-",
+        "information",
+        date(),
+        "csv",
+    )
+    .unwrap();
+
+    custom_assert_eq!(file_name, "run1-information.csv");
+}
+
+#[test]
+fn test_render_file_name_all_placeholders() {
+    let file_name: String = render_file_name(
+        "{course}/{date}-{name}.{format}",
+        "algebra",
+        "",
+        "attempts",
+        date(),
+        "json",
+    )
+    .unwrap();
+
+    custom_assert_eq!(file_name, "algebra/2024-03-05-attempts.json");
+}
+
+#[test]
+fn test_render_file_name_invalid_placeholder() {
+    assert!(
+        render_file_name("{learner}.csv", "algebra", "", "information", date(), "csv").is_err()
+    );
+    assert!(render_file_name("{course.csv", "algebra", "", "information", date(), "csv").is_err());
+}
@@ -0,0 +1,69 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::pseudonym_operations::{
+    load_pseudonym_map, pseudonymise, save_pseudonym_map,
+};
+
+#[test]
+fn test_pseudonymise_is_stable_and_salted() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pseudonyms.json");
+
+    let mut first = load_pseudonym_map(&path, Some("salt-a")).unwrap();
+    let mut second = load_pseudonym_map(&path, Some("salt-b")).unwrap();
+    let alias = pseudonymise(&mut first, "alice");
+
+    assert!(alias.starts_with("learner-"));
+    assert!(!alias.contains("alice"));
+    custom_assert_eq!(pseudonymise(&mut first, "alice"), alias);
+    assert_ne!(pseudonymise(&mut first, "bob"), alias);
+    assert_ne!(pseudonymise(&mut second, "alice"), alias);
+}
+
+#[test]
+fn test_pseudonym_map_round_trip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pseudonyms.json");
+
+    let mut pseudonym_map = load_pseudonym_map(&path, None).unwrap();
+    let alias = pseudonymise(&mut pseudonym_map, "alice");
+    save_pseudonym_map(&path, &pseudonym_map).unwrap();
+
+    let mut reloaded = load_pseudonym_map(&path, None).unwrap();
+    custom_assert_eq!(reloaded.salt, pseudonym_map.salt);
+    custom_assert_eq!(pseudonymise(&mut reloaded, "alice"), alias);
+}
+
+#[test]
+fn test_pseudonym_map_generates_random_salts() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pseudonyms.json");
+
+    let first = load_pseudonym_map(&path, None).unwrap();
+    let second = load_pseudonym_map(&path, None).unwrap();
+
+    custom_assert_eq!(first.salt.len(), 64);
+    custom_assert_eq!(
+        first
+            .salt
+            .chars()
+            .all(|character| character.is_ascii_hexdigit()),
+        true
+    );
+    assert_ne!(first.salt, second.salt);
+}
+
+#[test]
+fn test_pseudonym_map_salt_mismatch() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("pseudonyms.json");
+    let pseudonym_map = load_pseudonym_map(&path, Some("salt-a")).unwrap();
+    save_pseudonym_map(&path, &pseudonym_map).unwrap();
+
+    match load_pseudonym_map(&path, Some("salt-b")).unwrap_err() {
+        AppError::InvalidArgument(_) => {}
+        _ => panic!("Expected AppError::InvalidArgument"),
+    }
+}
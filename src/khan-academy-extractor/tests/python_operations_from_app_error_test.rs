@@ -0,0 +1,55 @@
+#![cfg(feature = "python")]
+
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::python_operations::{
+    ExtractorError, IoError, MissingFieldError, ParseError,
+};
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use std::io::{Error, ErrorKind};
+
+/// Returns one error of every kind.
+fn errors() -> Vec<AppError> {
+    vec![
+        AppError::Io(Error::new(ErrorKind::NotFound, "captures")),
+        AppError::Json(serde_json::from_str::<u32>("x").unwrap_err()),
+        AppError::MissingField("id".to_string()),
+        AppError::MissingFile("contentForPath.json".to_string()),
+        AppError::InvalidDate("2024-13-01".to_string()),
+        AppError::InvalidArgument("prefix".to_string()),
+        AppError::Locked("captures".to_string()),
+        AppError::Http("status 500".to_string()),
+        AppError::IncompleteCapture("1 missing file".to_string()),
+    ]
+}
+
+#[test]
+fn test_every_error_is_an_extractor_error() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        for error in errors() {
+            let message: String = error.to_string();
+            let error: PyErr = error.into();
+
+            custom_assert_eq!(error.is_instance_of::<ExtractorError>(py), true);
+            custom_assert_eq!(error.value(py).to_string(), message);
+        }
+    });
+}
+
+#[test]
+fn test_errors_keep_their_kind() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let io: PyErr = AppError::Io(Error::new(ErrorKind::NotFound, "captures")).into();
+        custom_assert_eq!(io.is_instance_of::<IoError>(py), true);
+        custom_assert_eq!(io.is_instance_of::<PyOSError>(py), false);
+
+        let missing_field: PyErr = AppError::MissingField("id".to_string()).into();
+        custom_assert_eq!(missing_field.is_instance_of::<MissingFieldError>(py), true);
+        custom_assert_eq!(missing_field.is_instance_of::<ParseError>(py), false);
+    });
+}
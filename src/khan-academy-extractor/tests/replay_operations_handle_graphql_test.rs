@@ -0,0 +1,151 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::replay_operations::{handle_graphql, ReplayCaptures};
+use khan_academy_extractor::source_operations::MemorySource;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+         "allOrderedChildren": [
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/math/unit-1/e/exercise-1", "progressKey": "ke1"}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/math/unit-2", "progressKey": "ku2",
+         "allOrderedChildren": [
+            {"id": "e2", "__typename": "Exercise", "translatedTitle": "Exercise 2",
+             "slug": "exercise-2", "relativeUrl": "/math/unit-2/e/exercise-2", "progressKey": "ke2"}
+         ]}
+    ]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {}}}}"#;
+
+fn unit_progress(id: &str) -> String {
+    format!(
+        r#"{{"data": {{"user": {{"contentItemProgresses": [{{
+            "__typename": "BasicContentItemProgress", "bestScore": null,
+            "completionStatus": "UNSTARTED",
+            "content": {{"__typename": "Exercise", "id": "e{0}", "progressKey": "ke{0}"}}
+        }}]}}}}}}"#,
+        id
+    )
+}
+
+const QUIZ_TEST_PROGRESS: &str =
+    r#"{"data": {"user": {"latestQuizAttempts": [], "latestUnitTestAttempts": []}}}"#;
+
+fn captures() -> ReplayCaptures {
+    let source: MemorySource = MemorySource::new()
+        .with_file("contentForPath.json", CONTENT_FOR_PATH)
+        .with_file("courseProgressQuery.json", COURSE_PROGRESS)
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress("2"),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-2.json",
+            unit_progress("1"),
+        )
+        .with_file("quizAndUnitTestAttemptsQuery-1.json", QUIZ_TEST_PROGRESS)
+        .with_file("quizAndUnitTestAttemptsQuery-2.json", QUIZ_TEST_PROGRESS);
+
+    ReplayCaptures::load(&source, "").unwrap()
+}
+
+#[test]
+fn test_handle_graphql_by_operation_name() {
+    let captures: ReplayCaptures = captures();
+
+    custom_assert_eq!(
+        handle_graphql(
+            &captures,
+            "/api/internal/graphql",
+            r#"{"operationName": "courseProgressQuery", "variables": {}}"#
+        ),
+        (200, COURSE_PROGRESS.to_string())
+    );
+    custom_assert_eq!(
+        handle_graphql(
+            &captures,
+            "/api/internal/graphql/contentForPath?lang=en",
+            ""
+        ),
+        (200, CONTENT_FOR_PATH.to_string())
+    );
+    custom_assert_eq!(
+        handle_graphql(&captures, "/graphql", r#"{"operationName": "unknown"}"#).0,
+        404
+    );
+    custom_assert_eq!(handle_graphql(&captures, "/graphql", "{").0, 400);
+}
+
+#[test]
+fn test_handle_graphql_selects_numbered_file() {
+    let captures: ReplayCaptures = captures();
+    let request = |variables: &str| -> (u16, String) {
+        handle_graphql(
+            &captures,
+            "/graphql",
+            &format!(
+                r#"{{"operationName": "getUserInfoForTopicProgressMastery", "variables": {}}}"#,
+                variables
+            ),
+        )
+    };
+
+    custom_assert_eq!(request(r#"{"topicId": "u1"}"#), (200, unit_progress("1")));
+    custom_assert_eq!(request(r#"{"topicId": "u2"}"#), (200, unit_progress("2")));
+    custom_assert_eq!(
+        request(r#"{"input": {"path": "math/unit-1/"}}"#),
+        (200, unit_progress("1"))
+    );
+    custom_assert_eq!(request(r#"{"topicId": "u3"}"#).0, 404);
+    custom_assert_eq!(
+        handle_graphql(
+            &captures,
+            "/api/internal/graphql/getUserInfoForTopicProgressMastery?hash=1&variables=%7B%22topicId%22%3A%22u2%22%7D",
+            ""
+        ),
+        (200, unit_progress("2"))
+    );
+    custom_assert_eq!(
+        handle_graphql(
+            &captures,
+            "/graphql",
+            r#"{"operationName": "quizAndUnitTestAttemptsQuery", "variables": {"unitId": "u1"}}"#
+        ),
+        (200, QUIZ_TEST_PROGRESS.to_string())
+    );
+}
+
+#[test]
+fn test_replay_captures_load_malformed_capture() {
+    let load = |name: &str, content: &str| -> String {
+        let source: MemorySource = MemorySource::new()
+            .with_file("contentForPath.json", CONTENT_FOR_PATH)
+            .with_file("courseProgressQuery.json", COURSE_PROGRESS)
+            .with_file(name, content);
+        match ReplayCaptures::load(&source, "") {
+            Ok(_) => "loaded".to_string(),
+            Err(error) => error.to_string(),
+        }
+    };
+
+    custom_assert_eq!(
+        load("getUserInfoForTopicProgressMastery-1.json", "{"),
+        "JSON error: EOF while parsing an object at line 1 column 1".to_string()
+    );
+    custom_assert_eq!(
+        load(
+            "quizAndUnitTestAttemptsQuery-1.json",
+            r#"{"data": {"user": {"latestQuizAttempts": [{"__typename": "TopicQuizAttempt",
+                "isCompleted": true, "numAttempted": 1, "numCorrect": 1, "positionKey": "YWJj"}]}}}"#
+        ),
+        "Missing field: parent id in positionKey YWJj".to_string()
+    );
+}
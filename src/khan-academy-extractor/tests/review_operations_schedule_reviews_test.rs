@@ -0,0 +1,174 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use chrono::NaiveDate;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::models::{
+    AttemptRecord, BestScore, Content, ContentItemProgress, DataStruct,
+};
+use khan_academy_extractor::review_operations::{review_box, schedule_reviews};
+
+fn row(id: &str, progress_key: &str) -> DataStruct {
+    DataStruct {
+        id: id.to_string(),
+        type_name: "Exercise".to_string(),
+        order: 1,
+        title: format!("Title {}", id),
+        slug: id.to_string(),
+        relative_url: format!("/e/{}", id),
+        progress_key: Some(progress_key.to_string()),
+        parent_topic: Some("".to_string()),
+        parent_id: Some("lesson".to_string()),
+        parent_type: Some("Lesson".to_string()),
+        parent_title: Some("Lesson".to_string()),
+        parent_slug: Some("lesson".to_string()),
+        parent_relative_url: Some("/lesson".to_string()),
+        depth: 3,
+        path: format!("course/unit/lesson/{}", id),
+        ..Default::default()
+    }
+}
+
+fn progress(
+    type_name: &str,
+    progress_key: &str,
+    completed_date: Option<&str>,
+    num_attempted: u32,
+    num_correct: u32,
+) -> ContentItemProgress {
+    ContentItemProgress {
+        type_name: "BasicContentItemProgress".to_string(),
+        best_score: Some(BestScore {
+            completed_date: completed_date.map(|d| d.to_string()),
+            num_attempted: Some(num_attempted),
+            num_correct: Some(num_correct),
+        }),
+        completion_status: "COMPLETE".to_string(),
+        content: Content {
+            type_name: type_name.to_string(),
+            id: progress_key.to_string(),
+            progress_key: progress_key.to_string(),
+        },
+    }
+}
+
+fn attempt(
+    content_id: &str,
+    attempt_number: u32,
+    completed_date: Option<&str>,
+    score: u32,
+) -> AttemptRecord {
+    AttemptRecord {
+        content_id: content_id.to_string(),
+        type_name: "TopicQuiz".to_string(),
+        title: format!("Title {}", content_id),
+        attempt_number,
+        is_completed: completed_date.is_some(),
+        num_attempted: 10,
+        num_correct: score / 10,
+        score,
+        completed_date: completed_date.map(|d| d.to_string()),
+    }
+}
+
+#[test]
+fn test_review_box_replays_history() {
+    custom_assert_eq!(review_box(&[]), 1);
+    custom_assert_eq!(review_box(&[80]), 2);
+    custom_assert_eq!(review_box(&[50, 70]), 2);
+    custom_assert_eq!(review_box(&[80, 90, 50]), 1);
+    custom_assert_eq!(review_box(&[80, 90, 100, 100, 100, 100]), 5);
+}
+
+#[test]
+fn test_schedule_reviews_sorted_by_due_date() {
+    let mut mastered = row("a", "ka");
+    mastered.status = Some("MASTERED".to_string());
+    let rows = vec![mastered, row("b", "kb")];
+    // The same best score can be found in several captures, and counts once
+    let items_progresses = vec![
+        vec![
+            progress("Exercise", "ka", Some("2024-03-10T10:00:00Z"), 4, 4),
+            progress("Exercise", "kb", Some("2024-03-05T10:00:00Z"), 4, 3),
+        ],
+        vec![
+            progress("Exercise", "ka", Some("2024-03-01T10:00:00Z"), 4, 4),
+            progress("Exercise", "ka", Some("2024-03-01T10:00:00Z"), 4, 4),
+        ],
+    ];
+
+    let review_items = schedule_reviews(&rows, &items_progresses, &[]).unwrap();
+
+    custom_assert_eq!(review_items.len(), 2);
+    custom_assert_eq!(review_items[0].id, "b");
+    custom_assert_eq!(review_items[0].accuracy, Some(75));
+    custom_assert_eq!(review_items[0].review_box, 2);
+    custom_assert_eq!(
+        review_items[0].due_date,
+        NaiveDate::from_ymd_opt(2024, 3, 8).unwrap()
+    );
+    custom_assert_eq!(review_items[1].id, "a");
+    custom_assert_eq!(review_items[1].status.as_deref(), Some("MASTERED"));
+    custom_assert_eq!(review_items[1].review_box, 3);
+    custom_assert_eq!(
+        review_items[1].completed_date,
+        NaiveDate::from_ymd_opt(2024, 3, 10).unwrap()
+    );
+    custom_assert_eq!(
+        review_items[1].due_date,
+        NaiveDate::from_ymd_opt(2024, 3, 17).unwrap()
+    );
+}
+
+#[test]
+fn test_schedule_reviews_replays_attempts() {
+    let mut quiz = row("q", "kq");
+    quiz.type_name = "TopicQuiz".to_string();
+    let rows = vec![quiz];
+    let attempts = vec![
+        attempt("q", 3, Some("2024-03-06T10:00:00Z"), 80),
+        attempt("q", 1, Some("2024-03-01T10:00:00Z"), 90),
+        attempt("q", 2, Some("2024-03-04T10:00:00Z"), 40),
+        attempt("q", 4, None, 0),
+        attempt("unknown", 1, Some("2024-03-01T10:00:00Z"), 90),
+    ];
+
+    let review_items = schedule_reviews(&rows, &[], &attempts).unwrap();
+
+    custom_assert_eq!(review_items.len(), 1);
+    custom_assert_eq!(review_items[0].id, "q");
+    custom_assert_eq!(review_items[0].accuracy, Some(80));
+    custom_assert_eq!(review_items[0].review_box, 2);
+    custom_assert_eq!(
+        review_items[0].due_date,
+        NaiveDate::from_ymd_opt(2024, 3, 9).unwrap()
+    );
+}
+
+#[test]
+fn test_schedule_reviews_skips_unfinished_and_non_exercises() {
+    let rows = vec![row("a", "ka"), row("v", "kv")];
+    let items_progresses = vec![vec![
+        progress("Exercise", "ka", None, 0, 0),
+        progress("Video", "kv", Some("2024-03-01T10:00:00Z"), 0, 0),
+    ]];
+
+    let review_items = schedule_reviews(&rows, &items_progresses, &[]).unwrap();
+
+    assert!(review_items.is_empty());
+}
+
+#[test]
+fn test_schedule_reviews_invalid_date() {
+    let rows = vec![row("a", "ka")];
+    let items_progresses = vec![vec![progress("Exercise", "ka", Some("yesterday"), 1, 1)]];
+
+    let result = schedule_reviews(&rows, &items_progresses, &[]);
+
+    match result.unwrap_err() {
+        AppError::InvalidDate(value) => {
+            custom_assert_eq!(value, "yesterday");
+        }
+        _ => panic!("Expected AppError::InvalidDate"),
+    }
+}
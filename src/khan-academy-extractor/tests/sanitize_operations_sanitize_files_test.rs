@@ -0,0 +1,179 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::extractor::Extractor;
+use khan_academy_extractor::extractors::{extract_quiz_attempts, extract_unit_test_attempts};
+use khan_academy_extractor::file_operations::{read_files, FileContents};
+use khan_academy_extractor::json_operations::collect_course;
+use khan_academy_extractor::models::{EnrichedCourse, TopicQuizAttempt};
+use khan_academy_extractor::sanitize_operations::{capture_fields, sanitize_files, sanitize_value};
+use serde_json::{json, Value};
+use std::fs::{read_to_string, write};
+use tempfile::tempdir;
+
+const KAID: &str = "kaid_1234567890";
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1", "translatedDescription": "About math",
+    "unitChildren": [{
+        "id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+        "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+        "allOrderedChildren": [
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/math/unit-1/e/exercise-1", "progressKey": "ke1"},
+            {"id": "q1", "__typename": "TopicQuiz", "translatedTitle": "Quiz 1", "slug": "quiz-1",
+             "relativeUrl": "/math/unit-1/quiz/quiz-1", "progressKey": "kq1",
+             "parentTopic": {"id": "u1"}}
+        ]
+    }]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {
+    "id": "kaid_1234567890", "kaid": "kaid_1234567890", "nickname": "Alice Example",
+    "courseProgress": {
+        "currentMasteryV2": {"percentage": 40, "pointsEarned": 900},
+        "masteryMap": [{"progressKey": "ke1", "status": "MASTERED"}],
+        "unitProgresses": [{"unitId": "u1", "currentMasteryV2": {"percentage": 70, "pointsEarned": 500}}]
+    }
+}}}"#;
+
+const UNIT_PROGRESS: &str = r#"{"data": {"user": {"id": "kaid_1234567890", "contentItemProgresses": [{
+    "__typename": "BasicContentItemProgress",
+    "bestScore": {"completedDate": "2024-03-01T10:00:00Z", "numAttempted": 4, "numCorrect": 3},
+    "completionStatus": "COMPLETE",
+    "content": {"__typename": "Exercise", "id": "e1", "progressKey": "ke1"}
+}]}}}"#;
+
+// The attempt keys carry the kaid after the fields the extractors decode
+const QUIZ_TEST_PROGRESS: &str = r#"{"data": {"user": {
+    "latestQuizAttempts": [{"__typename": "TopicQuizAttempt", "isCompleted": true,
+        "numAttempted": 5, "numCorrect": 4, "positionKey": "CgJxMRABGg9rYWlkXzEyMzQ1Njc4OTA"}],
+    "latestUnitTestAttempts": [{"__typename": "TopicUnitTestAttempt",
+        "id": "VW5pdFRlc3Q6dTEMa2FpZF8xMjM0NTY3ODkw", "isCompleted": false,
+        "numAttempted": 0, "numCorrect": 0}]
+}}}"#;
+
+#[test]
+fn test_sanitize_files_output_loads_without_learner_identity() {
+    let captures = tempdir().unwrap();
+    let output = tempdir().unwrap();
+    let files: [(&str, &str); 4] = [
+        ("contentForPath.json", CONTENT_FOR_PATH),
+        ("courseProgressQuery.json", COURSE_PROGRESS),
+        ("getUserInfoForTopicProgressMastery-1.json", UNIT_PROGRESS),
+        ("quizAndUnitTestAttemptsQuery-1.json", QUIZ_TEST_PROGRESS),
+    ];
+    for (name, content) in files {
+        write(captures.path().join(name), content).unwrap();
+    }
+    write(captures.path().join("notes.txt"), KAID).unwrap();
+
+    let sanitized: Vec<String> = sanitize_files(
+        captures.path().to_str().unwrap(),
+        "",
+        output.path().to_str().unwrap(),
+    )
+    .unwrap();
+
+    custom_assert_eq!(
+        sanitized,
+        files
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<String>>()
+    );
+    for name in &sanitized {
+        let content: String = read_to_string(output.path().join(name)).unwrap();
+        custom_assert_eq!(content.contains(KAID), false);
+        custom_assert_eq!(content.contains("Alice"), false);
+        custom_assert_eq!(content.contains("nickname"), false);
+        custom_assert_eq!(content.contains("translatedDescription"), false);
+    }
+    custom_assert_eq!(output.path().join("notes.txt").exists(), false);
+
+    let contents: FileContents = read_files(output.path().to_str().unwrap(), "").unwrap();
+    let quiz_test_progress: &str = &contents.json_quiz_test_progress_files[0];
+    custom_assert_eq!(
+        quiz_test_progress.contains("VW5pdFRlc3Q6dTEMeHh4eHh4eHh4eHh4eHh4"),
+        true
+    );
+    let quiz_attempt: &TopicQuizAttempt = &extract_quiz_attempts(quiz_test_progress).unwrap()[0];
+    custom_assert_eq!(quiz_attempt.parent_id.as_str(), "q1");
+    custom_assert_eq!(quiz_attempt.position, Some(1));
+    custom_assert_eq!(
+        extract_unit_test_attempts(quiz_test_progress).unwrap()[0]
+            .parent_id
+            .as_str(),
+        "u1"
+    );
+
+    let extracted: EnrichedCourse = Extractor::from_dir(output.path().to_str().unwrap(), "")
+        .unwrap()
+        .extract()
+        .unwrap();
+    let original: EnrichedCourse = Extractor::from_dir(captures.path().to_str().unwrap(), "")
+        .unwrap()
+        .extract()
+        .unwrap();
+    custom_assert_eq!(
+        serde_json::to_value(&extracted.rows).unwrap(),
+        serde_json::to_value(&original.rows).unwrap()
+    );
+}
+
+#[test]
+fn test_sanitize_value_strips_identifying_fields_at_any_depth() {
+    let value: Value = json!({ "data": { "items": [{
+        "id": "i1",
+        "kaid": KAID,
+        "owner": { "__typename": "User", "id": KAID, "nickname": "Alice" },
+        "user": { "id": KAID, "email": "alice@example.com" }
+    }]}});
+    let fields: [&str; 9] = [
+        "data",
+        "items",
+        "id",
+        "kaid",
+        "owner",
+        "__typename",
+        "nickname",
+        "user",
+        "email",
+    ];
+
+    custom_assert_eq!(
+        sanitize_value(&value, &fields),
+        json!({ "data": { "items": [{
+            "id": "i1",
+            "owner": { "__typename": "User" },
+            "user": {}
+        }]}})
+    );
+}
+
+#[test]
+fn test_sanitize_value_keeps_every_child_field() {
+    let course: Value = json!({
+        "id": "c1", "__typename": "Course", "translatedTitle": "Science", "slug": "science",
+        "relativeUrl": "/science",
+        "unitChildren": [{
+            "id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+            "relativeUrl": "/science/unit-1",
+            "children": [{
+                "id": "t1", "__typename": "Topic", "translatedTitle": "Topic 1",
+                "slug": "topic-1", "relativeUrl": "/science/unit-1/topic-1",
+                "children": [{
+                    "id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                    "slug": "exercise-1", "relativeUrl": "/science/unit-1/topic-1/e/exercise-1"
+                }]
+            }]
+        }]
+    });
+    let fields: Vec<&str> = capture_fields("contentForPath.json", "").unwrap();
+
+    custom_assert_eq!(
+        serde_json::to_value(collect_course(&sanitize_value(&course, &fields)).unwrap()).unwrap(),
+        serde_json::to_value(collect_course(&course).unwrap()).unwrap()
+    );
+}
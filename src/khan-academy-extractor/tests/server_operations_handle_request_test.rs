@@ -0,0 +1,218 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::server_operations::{handle_request, CourseStore};
+use serde_json::Value;
+use std::fs::write;
+use std::path::Path;
+use tempfile::tempdir;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1",
+    "unitChildren": [{
+        "id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+        "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+        "allOrderedChildren": [
+            {"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1", "slug": "lesson-1",
+             "relativeUrl": "/math/unit-1/lesson-1",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-1/e/exercise-1",
+                 "progressKey": "ke1"}
+             ]},
+            {"id": "q1", "__typename": "TopicQuiz", "translatedTitle": "Quiz 1", "slug": "quiz-1",
+             "relativeUrl": "/math/unit-1/quiz/quiz-1", "progressKey": "kq1",
+             "parentTopic": {"id": "u1"}},
+            {"id": "q2", "__typename": "TopicQuiz", "translatedTitle": "Quiz 2", "slug": "quiz-2",
+             "relativeUrl": "/math/unit-1/quiz/quiz-2", "progressKey": "kq2",
+             "parentTopic": {"id": "u1"}}
+        ]
+    }]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {
+    "currentMasteryV2": {"percentage": 40, "pointsEarned": 900},
+    "masteryMap": [{"progressKey": "ke1", "status": "MASTERED"}],
+    "unitProgresses": [{"unitId": "u1", "currentMasteryV2": {"percentage": 70, "pointsEarned": 500}}]
+}}}}"#;
+
+const UNIT_PROGRESS: &str = r#"{"data": {"user": {"contentItemProgresses": [{
+    "__typename": "BasicContentItemProgress",
+    "bestScore": {"completedDate": "2024-03-01T10:00:00Z", "numAttempted": 4, "numCorrect": 3},
+    "completionStatus": "COMPLETE",
+    "content": {"__typename": "Exercise", "id": "e1", "progressKey": "ke1"}
+}]}}}"#;
+
+fn write_captures(directory: &Path, unit_progress: &str) {
+    write(directory.join("contentForPath.json"), CONTENT_FOR_PATH).unwrap();
+    write(directory.join("courseProgressQuery.json"), COURSE_PROGRESS).unwrap();
+    write(
+        directory.join("getUserInfoForTopicProgressMastery-1.json"),
+        unit_progress,
+    )
+    .unwrap();
+}
+
+fn ids(body: &Value) -> Vec<&str> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["id"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_handle_request_routes() {
+    let directory = tempdir().unwrap();
+    write_captures(directory.path(), UNIT_PROGRESS);
+    let mut store: CourseStore = CourseStore::new(
+        vec![(
+            "math".to_string(),
+            directory.path().to_str().unwrap().to_string(),
+        )],
+        "",
+        None,
+    );
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+
+    let (status, courses): (u16, Value) = handle_request(&store, "/courses");
+    custom_assert_eq!(status, 200);
+    custom_assert_eq!(courses[0]["title"], "Math");
+
+    let (_, tree): (u16, Value) = handle_request(&store, "/courses/math/tree");
+    custom_assert_eq!(tree[0]["kind"], "Course");
+    custom_assert_eq!(tree[0]["children"][0]["id"], "u1");
+    custom_assert_eq!(
+        ids(&tree[0]["children"][0]["children"]),
+        vec!["l1", "q1", "q2"]
+    );
+
+    let (_, units): (u16, Value) = handle_request(&store, "/courses/math/units");
+    custom_assert_eq!(units[0]["percentage"], "70");
+    custom_assert_eq!(units[0]["total"], 1);
+    custom_assert_eq!(units[0]["completed"], 1);
+
+    let (_, next): (u16, Value) = handle_request(&store, "/courses/math/next?limit=1");
+    custom_assert_eq!(ids(&next), vec!["q1"]);
+
+    let (_, found): (u16, Value) = handle_request(&store, "/courses/math/search?q=QUIZ%202");
+    custom_assert_eq!(ids(&found), vec!["q2"]);
+
+    custom_assert_eq!(handle_request(&store, "/courses/other/tree").0, 404);
+    custom_assert_eq!(handle_request(&store, "/courses/math/search").0, 400);
+    custom_assert_eq!(handle_request(&store, "/unknown").0, 404);
+}
+
+#[test]
+fn test_course_store_refresh_reloads_changed_captures() {
+    let directory = tempdir().unwrap();
+    let mut store: CourseStore = CourseStore::new(
+        vec![(
+            "math".to_string(),
+            directory.path().to_str().unwrap().to_string(),
+        )],
+        "",
+        None,
+    );
+
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+    custom_assert_eq!(handle_request(&store, "/courses/math/units").0, 503);
+
+    write_captures(directory.path(), UNIT_PROGRESS);
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+    custom_assert_eq!(store.refresh(), Vec::<String>::new());
+    let (_, next): (u16, Value) = handle_request(&store, "/courses/math/next");
+    custom_assert_eq!(ids(&next), vec!["q1", "q2"]);
+
+    write_captures(
+        directory.path(),
+        &UNIT_PROGRESS.replace("\"COMPLETE\"", "\"UNSTARTED\""),
+    );
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+    let (_, next): (u16, Value) = handle_request(&store, "/courses/math/next");
+    custom_assert_eq!(ids(&next), vec!["e1", "q1", "q2"]);
+}
+
+#[test]
+fn test_course_store_refresh_keeps_the_last_extraction_over_a_corrupt_capture() {
+    let directory = tempdir().unwrap();
+    write_captures(directory.path(), UNIT_PROGRESS);
+    let mut store: CourseStore = CourseStore::new(
+        vec![(
+            "math".to_string(),
+            directory.path().to_str().unwrap().to_string(),
+        )],
+        "",
+        None,
+    );
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+
+    // A unit capture still being written
+    write(
+        directory
+            .path()
+            .join("getUserInfoForTopicProgressMastery-1.json"),
+        &UNIT_PROGRESS[..UNIT_PROGRESS.len() / 2],
+    )
+    .unwrap();
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+
+    let (status, courses): (u16, Value) = handle_request(&store, "/courses");
+    custom_assert_eq!(status, 200);
+    custom_assert_eq!(
+        courses[0]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("JSON error"),
+        true
+    );
+    let (status, units): (u16, Value) = handle_request(&store, "/courses/math/units");
+    custom_assert_eq!(status, 200);
+    custom_assert_eq!(units[0]["completed"], 1);
+
+    write_captures(directory.path(), UNIT_PROGRESS);
+    custom_assert_eq!(store.refresh(), vec!["math".to_string()]);
+    custom_assert_eq!(
+        handle_request(&store, "/courses").1[0]["error"],
+        Value::Null
+    );
+}
+
+#[test]
+fn test_handle_request_tree_with_reused_content() {
+    let directory = tempdir().unwrap();
+    // The same exercise is listed in two lessons
+    let content_for_path: String = CONTENT_FOR_PATH.replace(
+        r#"{"id": "q1""#,
+        r#"{"id": "l2", "__typename": "Lesson", "translatedTitle": "Lesson 2", "slug": "lesson-2",
+             "relativeUrl": "/math/unit-1/lesson-2",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-2/e/exercise-1",
+                 "progressKey": "ke1"}
+             ]},
+            {"id": "q1""#,
+    );
+    write_captures(directory.path(), UNIT_PROGRESS);
+    write(
+        directory.path().join("contentForPath.json"),
+        content_for_path,
+    )
+    .unwrap();
+    let mut store: CourseStore = CourseStore::new(
+        vec![(
+            "math".to_string(),
+            directory.path().to_str().unwrap().to_string(),
+        )],
+        "",
+        None,
+    );
+    store.refresh();
+
+    let (_, tree): (u16, Value) = handle_request(&store, "/courses/math/tree");
+    let unit: &Value = &tree[0]["children"][0];
+    custom_assert_eq!(ids(&unit["children"]), vec!["l1", "l2", "q1", "q2"]);
+    custom_assert_eq!(ids(&unit["children"][0]["children"]), vec!["e1"]);
+    custom_assert_eq!(ids(&unit["children"][1]["children"]), vec!["e1"]);
+}
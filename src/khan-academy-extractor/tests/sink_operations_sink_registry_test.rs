@@ -0,0 +1,110 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::models::{AttemptRecord, DataStruct};
+use khan_academy_extractor::sink_operations::{OutputSink, SinkRegistry};
+use std::io::Write;
+
+fn attempt(content_id: &str, score: u32) -> AttemptRecord {
+    AttemptRecord {
+        content_id: content_id.to_string(),
+        type_name: "TopicQuiz".to_string(),
+        title: "Quiz 1".to_string(),
+        attempt_number: 1,
+        is_completed: true,
+        num_attempted: 10,
+        num_correct: score / 10,
+        score,
+        completed_date: None,
+    }
+}
+
+/// Sink writing one line per record with the id and the title, as a downstream crate would.
+struct TitleSink;
+
+impl OutputSink for TitleSink {
+    fn extension(&self) -> &str {
+        "txt"
+    }
+
+    fn write_rows(&self, rows: &[DataStruct], output: &mut dyn Write) -> Result<(), AppError> {
+        for row in rows {
+            writeln!(output, "{} {}", row.id, row.title)?;
+        }
+        Ok(())
+    }
+
+    fn write_attempts(
+        &self,
+        attempts: &[AttemptRecord],
+        output: &mut dyn Write,
+    ) -> Result<(), AppError> {
+        for attempt in attempts {
+            writeln!(output, "{} {}", attempt.content_id, attempt.score)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_builtin_sinks_write_to_memory() {
+    let registry: SinkRegistry = SinkRegistry::new();
+    let attempts: Vec<AttemptRecord> = vec![attempt("q1", 80)];
+
+    let mut csv: Vec<u8> = Vec::new();
+    registry
+        .get("csv")
+        .unwrap()
+        .write_attempts(&attempts, &mut csv)
+        .unwrap();
+    custom_assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "contentId,typeName,title,attemptNumber,isCompleted,numAttempted,numCorrect,score,completedDate\nq1,TopicQuiz,Quiz 1,1,true,10,8,80,\n"
+    );
+
+    let mut json: Vec<u8> = Vec::new();
+    registry
+        .get("json")
+        .unwrap()
+        .write_attempts(&attempts, &mut json)
+        .unwrap();
+    let parsed: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    custom_assert_eq!(parsed[0]["contentId"], "q1");
+    custom_assert_eq!(parsed[0]["score"], 80);
+}
+
+#[test]
+fn test_register_custom_sink() {
+    let mut registry: SinkRegistry = SinkRegistry::new();
+    registry.register("titles", Box::new(TitleSink));
+
+    let sink: &dyn OutputSink = registry.get("titles").unwrap();
+    let mut output: Vec<u8> = Vec::new();
+    sink.write_attempts(&[attempt("q1", 80), attempt("q2", 50)], &mut output)
+        .unwrap();
+
+    custom_assert_eq!(sink.extension(), "txt");
+    custom_assert_eq!(String::from_utf8(output).unwrap(), "q1 80\nq2 50\n");
+    custom_assert_eq!(registry.formats(), vec!["csv", "json", "titles"]);
+}
+
+#[test]
+fn test_unknown_format() {
+    let registry: SinkRegistry = SinkRegistry::new();
+
+    assert!(matches!(
+        registry.get("xlsx"),
+        Err(AppError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn test_empty_registry() {
+    let mut registry: SinkRegistry = SinkRegistry::empty();
+    custom_assert_eq!(registry.formats(), Vec::<&str>::new());
+
+    registry.register("titles", Box::new(TitleSink));
+    custom_assert_eq!(registry.formats(), vec!["titles"]);
+    custom_assert_eq!(SinkRegistry::default().formats(), vec!["csv", "json"]);
+}
@@ -0,0 +1,110 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use khan_academy_extractor::file_operations::{read_files, FileContents};
+use khan_academy_extractor::source_operations::{ArchiveSource, InputSource};
+use std::fs::{write, File};
+use std::io::{Cursor, Write};
+use tar::{Builder, Header};
+use tempfile::tempdir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+fn gzip(content: &str) -> Vec<u8> {
+    let mut encoder: GzEncoder<Vec<u8>> = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn entries() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("week-42/contentForPath.json", b"content".to_vec()),
+        ("week-42/courseProgressQuery.json.gz", gzip("progress")),
+        (
+            "week-42/getUserInfoForTopicProgressMastery-1.json",
+            b"unit 1".to_vec(),
+        ),
+        ("week-42/._contentForPath.json", vec![0, 159, 146, 150]),
+    ]
+}
+
+fn zip_archive() -> Vec<u8> {
+    let mut writer: ZipWriter<Cursor<Vec<u8>>> = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in entries() {
+        writer
+            .start_file(name, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(&content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn tar_gz_archive() -> Vec<u8> {
+    let mut builder: Builder<GzEncoder<Vec<u8>>> =
+        Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, content) in entries() {
+        let mut header: Header = Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, &content[..])
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+fn assert_entries(source: &ArchiveSource) {
+    custom_assert_eq!(
+        source.list_files().unwrap(),
+        vec![
+            "contentForPath.json".to_string(),
+            "courseProgressQuery.json".to_string(),
+            "getUserInfoForTopicProgressMastery-1.json".to_string(),
+        ]
+    );
+    custom_assert_eq!(
+        source.read_file("courseProgressQuery.json").unwrap(),
+        "progress"
+    );
+}
+
+#[test]
+fn test_archive_source_zip() {
+    assert_entries(&ArchiveSource::from_zip(Cursor::new(zip_archive())).unwrap());
+}
+
+#[test]
+fn test_archive_source_tar_gz() {
+    assert_entries(&ArchiveSource::from_tar_gz(&tar_gz_archive()[..]).unwrap());
+}
+
+#[test]
+fn test_read_files_from_archives_and_compressed_files() {
+    let directory = tempdir().unwrap();
+    let zip_file = directory.path().join("week-42.zip");
+    let tar_gz_file = directory.path().join("week-42.tar.gz");
+    write(&zip_file, zip_archive()).unwrap();
+    write(&tar_gz_file, tar_gz_archive()).unwrap();
+    for archive in [&zip_file, &tar_gz_file] {
+        let file_contents: FileContents = read_files(archive.to_str().unwrap(), "").unwrap();
+        custom_assert_eq!(file_contents.json_content, "content");
+        custom_assert_eq!(file_contents.json_course_progress, "progress");
+        custom_assert_eq!(
+            file_contents.json_unit_progress_files,
+            vec!["unit 1".to_string()]
+        );
+    }
+
+    let captures = directory.path().join("captures");
+    std::fs::create_dir(&captures).unwrap();
+    write(captures.join("contentForPath.json"), "content").unwrap();
+    File::create(captures.join("courseProgressQuery.json.gz"))
+        .unwrap()
+        .write_all(&gzip("progress"))
+        .unwrap();
+    let file_contents: FileContents = read_files(captures.to_str().unwrap(), "").unwrap();
+    custom_assert_eq!(file_contents.json_course_progress, "progress");
+}
@@ -0,0 +1,174 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::json_operations::collect_course;
+use khan_academy_extractor::models::DataStruct;
+use khan_academy_extractor::tree_operations::{CourseTree, CourseVisitor, NodeKind, TreeNode};
+use serde_json::{from_str, Value};
+
+const COURSE: &str = r#"{
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1",
+         "allOrderedChildren": [
+            {"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1", "slug": "lesson-1",
+             "relativeUrl": "/math/unit-1/lesson-1",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-1/e/exercise-1"},
+                {"id": "v1", "__typename": "Video", "translatedTitle": "Video 1",
+                 "slug": "video-1", "relativeUrl": "/math/unit-1/lesson-1/v/video-1"}
+             ]},
+            {"id": "q1", "__typename": "TopicQuiz", "translatedTitle": "Quiz 1", "slug": "quiz-1",
+             "relativeUrl": "/math/unit-1/quiz/quiz-1"}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/math/unit-2",
+         "allOrderedChildren": [
+            {"id": "a1", "__typename": "Article", "translatedTitle": "Article 1",
+             "slug": "article-1", "relativeUrl": "/math/unit-2/a/article-1"}
+         ]}
+    ]
+}"#;
+
+fn rows() -> Vec<DataStruct> {
+    let course: Value = from_str(COURSE).unwrap();
+    collect_course(&course).unwrap()
+}
+
+fn ids<'a>(nodes: impl Iterator<Item = TreeNode<'a>>) -> Vec<String> {
+    nodes.map(|node| node.row.id.clone()).collect()
+}
+
+#[derive(Default)]
+struct Outline {
+    lines: Vec<String>,
+}
+
+impl CourseVisitor for Outline {
+    fn enter_unit(&mut self, unit: &DataStruct, _: &[&DataStruct]) {
+        self.lines.push(format!("unit {}", unit.id));
+    }
+
+    fn leave_unit(&mut self, unit: &DataStruct, _: &[&DataStruct]) {
+        self.lines.push(format!("end {}", unit.id));
+    }
+
+    fn enter_item(&mut self, item: &DataStruct, ancestors: &[&DataStruct]) {
+        let path: Vec<&str> = ancestors.iter().map(|row| row.id.as_str()).collect();
+        self.lines.push(format!("{} {}", path.join("/"), item.id));
+    }
+}
+
+#[test]
+fn test_course_tree_traversals() {
+    let rows: Vec<DataStruct> = rows();
+    let tree: CourseTree = CourseTree::new(&rows);
+
+    custom_assert_eq!(
+        ids(tree.depth_first()),
+        vec!["c1", "u1", "l1", "e1", "v1", "q1", "u2", "a1"]
+    );
+    custom_assert_eq!(
+        ids(tree.breadth_first()),
+        vec!["c1", "u1", "u2", "l1", "q1", "a1", "e1", "v1"]
+    );
+
+    let video: TreeNode = tree.depth_first().find(|node| node.row.id == "v1").unwrap();
+    custom_assert_eq!(video.kind, NodeKind::Item);
+    custom_assert_eq!(video.depth(), 3);
+    custom_assert_eq!(
+        video
+            .ancestors
+            .iter()
+            .map(|row| row.id.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["c1", "u1", "l1"]
+    );
+    let quiz: TreeNode = tree
+        .breadth_first()
+        .find(|node| node.row.id == "q1")
+        .unwrap();
+    custom_assert_eq!(quiz.kind, NodeKind::Assessment);
+
+    let lesson: TreeNode = tree.depth_first().find(|node| node.row.id == "l1").unwrap();
+    custom_assert_eq!(rows[lesson.index].id.as_str(), "l1");
+    custom_assert_eq!(
+        tree.child_indexes(lesson.index)
+            .iter()
+            .map(|&index| rows[index].id.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["e1", "v1"]
+    );
+}
+
+#[test]
+fn test_course_tree_walk() {
+    let rows: Vec<DataStruct> = rows();
+    let tree: CourseTree = CourseTree::new(&rows);
+    let mut outline: Outline = Outline::default();
+    tree.walk(&mut outline);
+
+    custom_assert_eq!(
+        outline.lines,
+        vec![
+            "unit u1",
+            "c1/u1/l1 e1",
+            "c1/u1/l1 v1",
+            "end u1",
+            "unit u2",
+            "c1/u2 a1",
+            "end u2",
+        ]
+    );
+}
+
+#[test]
+fn test_course_tree_orphans_are_roots() {
+    let rows: Vec<DataStruct> = rows().into_iter().filter(|row| row.id != "u1").collect();
+    let tree: CourseTree = CourseTree::new(&rows);
+
+    custom_assert_eq!(
+        tree.roots()
+            .iter()
+            .map(|row| row.id.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["c1", "l1", "q1"]
+    );
+    custom_assert_eq!(tree.children("c1").len(), 1);
+}
+
+#[test]
+fn test_course_tree_keeps_the_children_of_reused_content() {
+    // The same lesson is listed in both units
+    let course: Value = from_str(&COURSE.replace(
+        r#"{"id": "a1", "__typename": "Article", "translatedTitle": "Article 1",
+             "slug": "article-1", "relativeUrl": "/math/unit-2/a/article-1"}"#,
+        r#"{"id": "l1", "__typename": "Lesson", "translatedTitle": "Lesson 1", "slug": "lesson-1",
+             "relativeUrl": "/math/unit-1/lesson-1",
+             "curatedChildren": [
+                {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+                 "slug": "exercise-1", "relativeUrl": "/math/unit-1/lesson-1/e/exercise-1"}
+             ]}"#,
+    ))
+    .unwrap();
+    let rows: Vec<DataStruct> = collect_course(&course).unwrap();
+    let tree: CourseTree = CourseTree::new(&rows);
+
+    let lessons: Vec<(Vec<&str>, usize)> = tree
+        .depth_first()
+        .filter(|node| node.row.id == "l1")
+        .map(|node| {
+            (
+                node.ancestors
+                    .iter()
+                    .map(|row| row.id.as_str())
+                    .collect::<Vec<&str>>(),
+                tree.child_indexes(node.index).len(),
+            )
+        })
+        .collect();
+    custom_assert_eq!(lessons, vec![(vec!["c1", "u1"], 2), (vec!["c1", "u2"], 1)]);
+}
@@ -0,0 +1,216 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::models::ValidationReport;
+use khan_academy_extractor::source_operations::MemorySource;
+use khan_academy_extractor::validate_operations::validate_capture;
+
+const CONTENT_FOR_PATH: &str = r#"{"data": {"contentRoute": {"listedPathData": {"course": {
+    "id": "c1", "__typename": "Course", "translatedTitle": "Math", "slug": "math",
+    "relativeUrl": "/math", "progressKey": "kc1",
+    "unitChildren": [
+        {"id": "u1", "__typename": "Unit", "translatedTitle": "Unit 1", "slug": "unit-1",
+         "relativeUrl": "/math/unit-1", "progressKey": "ku1",
+         "allOrderedChildren": [
+            {"id": "e1", "__typename": "Exercise", "translatedTitle": "Exercise 1",
+             "slug": "exercise-1", "relativeUrl": "/math/unit-1/e/exercise-1", "progressKey": "ke1"},
+            {"id": "e2", "__typename": "Exercise", "translatedTitle": "Exercise 2",
+             "slug": "exercise-2", "relativeUrl": "/math/unit-1/e/exercise-2", "progressKey": "ke2"},
+            {"id": "q1", "__typename": "TopicQuiz", "translatedTitle": "Quiz 1", "slug": "quiz-1",
+             "relativeUrl": "/math/unit-1/quiz/quiz-1", "progressKey": "kq1",
+             "parentTopic": {"id": "u1"}},
+            {"id": "t1", "__typename": "TopicUnitTest", "translatedTitle": "Unit test 1",
+             "slug": "test-1", "relativeUrl": "/math/unit-1/test/test-1", "progressKey": "kt1"}
+         ]},
+        {"id": "u2", "__typename": "Unit", "translatedTitle": "Unit 2", "slug": "unit-2",
+         "relativeUrl": "/math/unit-2", "progressKey": "ku2",
+         "allOrderedChildren": [
+            {"id": "e3", "__typename": "Exercise", "translatedTitle": "Exercise 3",
+             "slug": "exercise-3", "relativeUrl": "/math/unit-2/e/exercise-3", "progressKey": "ke3"},
+            {"id": "q2", "__typename": "TopicQuiz", "translatedTitle": "Quiz 2", "slug": "quiz-2",
+             "relativeUrl": "/math/unit-2/quiz/quiz-2", "progressKey": "kq2",
+             "parentTopic": {"id": "u2"}}
+         ]}
+    ]
+}}}}}"#;
+
+const COURSE_PROGRESS: &str = r#"{"data": {"user": {"courseProgress": {
+    "currentMasteryV2": {"percentage": 40, "pointsEarned": 900},
+    "masteryMap": [],
+    "unitProgresses": []
+}}}}"#;
+
+// The quiz attempts belong to the quizzes of `u1` and `u2`, the unit test attempt to `u1`
+const QUIZ_1_ATTEMPT: &str = r#"{"__typename": "TopicQuizAttempt", "isCompleted": true,
+    "numAttempted": 5, "numCorrect": 4, "positionKey": "CgJ1MRAB"}"#;
+const QUIZ_2_ATTEMPT: &str = r#"{"__typename": "TopicQuizAttempt", "isCompleted": true,
+    "numAttempted": 5, "numCorrect": 5, "positionKey": "CgJ1MhAB"}"#;
+const UNIT_TEST_ATTEMPT: &str = r#"{"__typename": "TopicUnitTestAttempt",
+    "id": "VW5pdFRlc3Q6dTEM", "isCompleted": true, "numAttempted": 10, "numCorrect": 9}"#;
+
+/// Returns a unit progress capture with the items of the given progress keys.
+fn unit_progress(keys: &[&str]) -> String {
+    let items: Vec<String> = keys
+        .iter()
+        .map(|key| {
+            format!(
+                r#"{{"__typename": "BasicContentItemProgress", "bestScore": null,
+                    "completionStatus": "COMPLETE",
+                    "content": {{"__typename": "Exercise", "id": "{}", "progressKey": "{}"}}}}"#,
+                key.trim_start_matches('k'),
+                key
+            )
+        })
+        .collect();
+
+    format!(
+        r#"{{"data": {{"user": {{"contentItemProgresses": [{}]}}}}}}"#,
+        items.join(",")
+    )
+}
+
+/// Returns a quiz and unit test capture with the given quiz and unit test attempts.
+fn quiz_test_progress(quiz_attempts: &[&str], test_attempts: &[&str]) -> String {
+    format!(
+        r#"{{"data": {{"user": {{"latestQuizAttempts": [{}], "latestUnitTestAttempts": [{}]}}}}}}"#,
+        quiz_attempts.join(","),
+        test_attempts.join(",")
+    )
+}
+
+fn course_source() -> MemorySource {
+    MemorySource::new()
+        .with_file("contentForPath.json", CONTENT_FOR_PATH)
+        .with_file("courseProgressQuery.json", COURSE_PROGRESS)
+}
+
+#[test]
+fn test_validate_capture_complete() {
+    let source: MemorySource = course_source()
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress(&["ke1", "ke2"]),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-2.json",
+            unit_progress(&["ke3"]),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-1.json",
+            quiz_test_progress(&[QUIZ_1_ATTEMPT], &[UNIT_TEST_ATTEMPT]),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-2.json",
+            quiz_test_progress(&[QUIZ_2_ATTEMPT], &[]),
+        );
+    let report: ValidationReport = validate_capture(&source, "").unwrap();
+
+    custom_assert_eq!(report.missing_files.len(), 0);
+    custom_assert_eq!(report.sequence_gaps.len(), 0);
+    custom_assert_eq!(report.incomplete_units.len(), 0);
+    custom_assert_eq!(report.missing_attempts.len(), 0);
+    custom_assert_eq!(report.unattempted.len(), 0);
+    custom_assert_eq!(report.pages_to_capture.len(), 0);
+}
+
+#[test]
+fn test_validate_capture_missing_files() {
+    let source: MemorySource = MemorySource::new().with_file(
+        "kagetUserInfoForTopicProgressMastery-1.json",
+        unit_progress(&["ke1"]),
+    );
+    let report: ValidationReport = validate_capture(&source, "ka").unwrap();
+
+    custom_assert_eq!(
+        report.missing_files,
+        vec!["kacontentForPath.json", "kacourseProgressQuery.json"]
+    );
+    custom_assert_eq!(report.incomplete_units.len(), 0);
+    custom_assert_eq!(report.pages_to_capture.len(), 0);
+}
+
+#[test]
+fn test_validate_capture_sequence_gaps() {
+    let source: MemorySource = course_source()
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress(&["ke1", "ke2"]),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-3.json",
+            unit_progress(&["ke3"]),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-2.json",
+            quiz_test_progress(&[], &[]),
+        );
+    let report: ValidationReport = validate_capture(&source, "").unwrap();
+
+    custom_assert_eq!(
+        report.sequence_gaps,
+        vec![
+            "getUserInfoForTopicProgressMastery-2.json",
+            "quizAndUnitTestAttemptsQuery-1.json",
+        ]
+    );
+}
+
+#[test]
+fn test_validate_capture_incomplete_unit() {
+    let source: MemorySource = course_source()
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress(&["ke1"]),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-2.json",
+            unit_progress(&["ke3"]),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-1.json",
+            quiz_test_progress(&[QUIZ_1_ATTEMPT], &[UNIT_TEST_ATTEMPT]),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-2.json",
+            quiz_test_progress(&[QUIZ_2_ATTEMPT], &[]),
+        );
+    let report: ValidationReport = validate_capture(&source, "").unwrap();
+
+    custom_assert_eq!(report.incomplete_units.len(), 1);
+    custom_assert_eq!(report.incomplete_units[0].id.as_str(), "u1");
+    custom_assert_eq!(report.incomplete_units[0].covered, 1);
+    custom_assert_eq!(report.incomplete_units[0].total, 2);
+    custom_assert_eq!(report.incomplete_units[0].capture_number, Some(1));
+    custom_assert_eq!(report.pages_to_capture, vec!["/math/unit-1"]);
+}
+
+#[test]
+fn test_validate_capture_missing_and_unattempted() {
+    // The first unit has a quiz and unit test capture without a unit test attempt, and the
+    // second unit has no quiz and unit test capture at all
+    let source: MemorySource = course_source()
+        .with_file(
+            "getUserInfoForTopicProgressMastery-1.json",
+            unit_progress(&["ke1", "ke2"]),
+        )
+        .with_file(
+            "getUserInfoForTopicProgressMastery-2.json",
+            unit_progress(&["ke3"]),
+        )
+        .with_file(
+            "quizAndUnitTestAttemptsQuery-1.json",
+            quiz_test_progress(&[QUIZ_1_ATTEMPT], &[]),
+        );
+    let report: ValidationReport = validate_capture(&source, "").unwrap();
+
+    custom_assert_eq!(
+        report.unattempted,
+        vec!["Unit test 1 (/math/unit-1/test/test-1) in Unit 1"]
+    );
+    custom_assert_eq!(
+        report.missing_attempts,
+        vec!["Quiz 2 (/math/unit-2/quiz/quiz-2) in Unit 2"]
+    );
+    custom_assert_eq!(report.incomplete_units.len(), 0);
+    custom_assert_eq!(report.pages_to_capture, vec!["/math/unit-2"]);
+}
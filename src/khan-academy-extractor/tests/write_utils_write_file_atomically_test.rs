@@ -0,0 +1,71 @@
+mod test_utils;
+
+use crate::test_utils::custom_assert_eq;
+use khan_academy_extractor::error::AppError;
+use khan_academy_extractor::file_utils::list_files_in_directory;
+use khan_academy_extractor::write_utils::{lock_directory, write_file_atomically};
+use std::fs::{read_dir, read_to_string, write, File};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_write_file_atomically_rotates_backups() {
+    let directory = tempdir().unwrap();
+    let path: PathBuf = directory.path().join("information.csv");
+
+    for content in ["first", "second", "third", "fourth"] {
+        write_file_atomically(&path, 2, |temporary| Ok(write(temporary, content)?)).unwrap();
+    }
+
+    custom_assert_eq!(read_to_string(&path).unwrap(), "fourth");
+    custom_assert_eq!(
+        read_to_string(directory.path().join("information.csv.1")).unwrap(),
+        "third"
+    );
+    custom_assert_eq!(
+        read_to_string(directory.path().join("information.csv.2")).unwrap(),
+        "second"
+    );
+    assert!(!directory.path().join("information.csv.3").exists());
+}
+
+#[test]
+fn test_write_file_atomically_keeps_previous_content_on_failure() {
+    let directory = tempdir().unwrap();
+    let path: PathBuf = directory.path().join("information.csv");
+    write(&path, "good data").unwrap();
+
+    let result = write_file_atomically(&path, 1, |temporary| {
+        write(temporary, "partial")?;
+        Err(AppError::MissingField("id".to_string()))
+    });
+
+    assert!(result.is_err());
+    custom_assert_eq!(read_to_string(&path).unwrap(), "good data");
+    custom_assert_eq!(read_dir(directory.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_lock_directory_rejects_second_run() {
+    let directory = tempdir().unwrap();
+
+    let lock: File = lock_directory(directory.path()).unwrap();
+    assert!(matches!(
+        lock_directory(directory.path()),
+        Err(AppError::Locked(_))
+    ));
+    drop(lock);
+    assert!(lock_directory(directory.path()).is_ok());
+}
+
+#[test]
+fn test_lock_file_is_not_listed() {
+    let directory = tempdir().unwrap();
+    write(directory.path().join("contentForPath.json"), "{}").unwrap();
+
+    let _lock: File = lock_directory(directory.path()).unwrap();
+    custom_assert_eq!(
+        list_files_in_directory(directory.path()).unwrap(),
+        vec!["contentForPath.json".to_string()]
+    );
+}